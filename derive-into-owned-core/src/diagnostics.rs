@@ -0,0 +1,169 @@
+//! The stable codes of the errors and warnings reported by the derives, with their explanations.
+//!
+//! Every message starts with its code in brackets, like `[DIO001] field `name` holds the plain
+//! reference ...`, so that the errors can be searched for in the build logs of large generated
+//! code bases. The codes are never reused for other diagnostics; [`explain`] returns the long
+//! form explanation for a code.
+//!
+//! ```
+//! let diagnostic = derive_into_owned_core::diagnostics::explain("DIO001").unwrap();
+//! assert_eq!(diagnostic.title, "reference field cannot be owned");
+//! ```
+
+use std::fmt;
+
+/// A diagnostic of the derives.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Diagnostic {
+    /// The stable code, like `DIO001`
+    pub code: &'static str,
+    /// A short summary of the problem
+    pub title: &'static str,
+    /// What causes the diagnostic and how to resolve it
+    pub explanation: &'static str,
+}
+
+impl Diagnostic {
+    /// `msg` prefixed with the code.
+    pub fn message(&self, msg: impl fmt::Display) -> String {
+        format!("[{}] {}", self.code, msg)
+    }
+
+    /// An error at `tokens` with `msg` prefixed with the code.
+    pub fn error(&self, tokens: impl quote::ToTokens, msg: impl fmt::Display) -> syn::Error {
+        syn::Error::new_spanned(tokens, self.message(msg))
+    }
+}
+
+impl fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}\n\n{}", self.code, self.title, self.explanation)
+    }
+}
+
+macro_rules! diagnostics {
+    ($($name:ident = $code:literal, $title:literal, $explanation:literal;)*) => {
+        $(
+            #[doc = concat!("`", $code, "`: ", $title)]
+            pub const $name: Diagnostic = Diagnostic {
+                code: $code,
+                title: $title,
+                explanation: $explanation,
+            };
+        )*
+
+        /// All the diagnostics, ordered by their codes.
+        pub const ALL: &[Diagnostic] = &[$($name),*];
+    };
+}
+
+diagnostics! {
+    REFERENCE_FIELD = "DIO001", "reference field cannot be owned",
+        "A field holds a plain reference like `&'a str`, possibly inside a container like the \
+         key of `HashMap<&'a str, V>`. `into_owned` cannot turn a reference into a `'static` one \
+         without leaking it. Generate an owned mirror type with `#[into_owned(mirror)]`, leak the \
+         data with `#[into_owned(leak)]` or hold the data in a `Cow` instead.";
+    ARENA_FIELD = "DIO002", "arena field cannot be owned",
+        "A field holds a collection allocated in an arena, like `bumpalo::collections::Vec<'a, T>`. \
+         The arena cannot be made `'static`, so only an owned mirror type generated with \
+         `#[into_owned(mirror)]` can hold the data.";
+    MUT_REFERENCE_FIELD = "DIO003", "mutable reference field",
+        "A field holds a mutable reference like `&'a mut [u8]`, which can be neither cloned by \
+         `Borrowed` nor owned by `IntoOwned`. Convert the field with \
+         `#[into_owned(with = \"...\")]`, leave it out with `#[into_owned(skip)]` or hold the \
+         data by value or in a `Cow`.";
+    MOVED_AS_IS = "DIO004", "field would be moved as is",
+        "With `#[into_owned(strict)]` every field whose type is not known to be convertible is an \
+         error instead of being moved as is. List the type with `#[into_owned(known(...))]` or in \
+         `derive-into-owned.toml`, or convert the field with `#[into_owned(with = \"...\")]`.";
+    UNEXPECTED_KIND = "DIO005", "field classified differently than expected",
+        "`#[into_owned(expect = \"...\")]` asserts how a field is classified and the field was \
+         classified differently, usually because the type is spelled through an alias or has \
+         changed. The message shows the actual classification.";
+    CLONED_FIELD = "DIO006", "field is deep cloned by `borrowed()`",
+        "With `#[borrowed(warn_clones)]` the fields which `borrowed()` clones instead of \
+         borrowing are reported as deprecation warnings. Hold the data in a `Cow`, or accept the \
+         cost with `#[borrowed(clone)]` on the field.";
+    UNSUPPORTED_SHAPE = "DIO007", "unsupported kind of type",
+        "The derive or option does not support the kind of type it is used on, like unions with \
+         lifetime parameters or tuple fields of view types. The message tells which kinds are supported.";
+    CONFLICTING_OPTIONS = "DIO008", "conflicting options",
+        "Two options which cannot be used together were given for the same type or field, like \
+         `leak` and `try_into_owned` or `with` and `skip`. Remove one of them.";
+    INAPPLICABLE_OPTION = "DIO009", "option does not apply to the type",
+        "The option needs a particular shape of type, like `transparent` a struct with a single \
+         field or `try_into_owned` an enum. The message tells what the option needs.";
+    MIRROR_FIELD_WITHOUT_MIRROR = "DIO010", "mirror options without a mirror type",
+        "A field has `#[into_owned(mirror(...))]` options but no mirror type is generated, as the \
+         type has no `#[into_owned(mirror)]`. Add it to the type or remove the field options.";
+    MISSING_FEATURE = "DIO011", "option needs a Cargo feature",
+        "The option generates code which depends on an optional crate, which is enabled with the \
+         Cargo feature of `derive-into-owned` named in the message.";
+    MSRV = "DIO012", "unsupported Rust version",
+        "`#[into_owned(msrv = \"...\")]` takes a Rust version like \"1.56\", which cannot be \
+         older than the 1.56 needed by the generated code.";
+    MALFORMED_OPTION = "DIO013", "malformed or unknown option",
+        "An `#[into_owned(...)]` or `#[borrowed(...)]` option is not known or its value has the \
+         wrong form. The crate documentation lists the options and their values.";
+    MODULE = "DIO014", "module cannot be expanded",
+        "`#[into_owned_module]` takes no arguments and needs the module contents inline. Files \
+         brought in with `include!` are read by the attribute, so their paths can only consist \
+         of string literals, `concat!` and `env!` and need to be absolute, as in \
+         `include!(concat!(env!(\"OUT_DIR\"), \"/person.rs\"))`.";
+    CONFIG = "DIO015", "invalid configuration file",
+        "`derive-into-owned.toml`, or the file named by `DERIVE_INTO_OWNED_CONFIG`, could not be \
+         read or does not have the expected form, a `[known]` table of paths to method names.";
+    MISSING_BORROWED = "DIO016", "Cow-alike field without `borrowed`",
+        "`#[derive(Borrowed)]` assumes that the fields of types with lifetime arguments, like \
+         `Foo<'a>`, are \"Cow-alike\" and calls `fn borrowed<'b>(&'b self) -> Foo<'b>` on them. \
+         The field type has no such method, or it returns another type. Derive `Borrowed` for \
+         the field type, or rebuild the field with `#[borrowed(as_ref)]`.";
+    TRAIT_OBJECT_FIELD = "DIO017", "borrowing trait object field",
+        "A field holds a trait object which names a lifetime, like `Arc<dyn Visitor<'a> + 'a>`. \
+         Only the trait itself can clone the object into a `'static` one, so `into_owned` needs a \
+         method like `fn clone_box(&self) -> Box<dyn Visitor<'static>>`, named with \
+         `#[into_owned(clone_box = \"Visitor::clone_box\")]`. Other conversions are given with \
+         `#[into_owned(with = \"...\")]`.";
+    LIFETIME_BOUND = "DIO018", "trait bound names a lifetime parameter",
+        "A type parameter is bounded by a trait naming a lifetime parameter of the type, like \
+         `T: Into<Cow<'a, str>>`, or a type naming one is bounded by a trait in the where \
+         clause, like `&'a T: IntoIterator`. The owned type `Foo<'static, T>` and the borrowed \
+         one would need the trait for other lifetimes, which cannot be required next to the \
+         original bound as the compiler then cannot choose between them. Require the trait for all lifetimes \
+         with `T: for<'x> Into<Cow<'x, str>>` or `for<'x> &'x T: IntoIterator` instead. Storage \
+         traits like `S: Storage<'a>` with `S::Str` fields are not supported, declare the stored \
+         types as generic associated types like `type Str<'a>;` instead.";
+    FN_POINTER_FIELD = "DIO019", "function pointer field borrows",
+        "A field holds a function pointer naming a lifetime parameter of the type. One returning \
+         borrowed data, like `fn() -> &'a str`, cannot be made `'static` by `into_owned`, and \
+         one taking borrowed arguments, like `fn(&'a str)`, cannot be called with the shorter \
+         borrows of `borrowed()`. Function pointers which work for any lifetime, like \
+         `fn(&str) -> &str` or `for<'x> fn(&'x str)`, are supported by both derives.";
+}
+
+/// The diagnostic with the `code`, like `DIO001`.
+pub fn explain(code: &str) -> Option<&'static Diagnostic> {
+    ALL.iter().find(|diagnostic| diagnostic.code == code)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn codes_are_sequential() {
+        for (index, diagnostic) in ALL.iter().enumerate() {
+            assert_eq!(diagnostic.code, format!("DIO{:03}", index + 1));
+        }
+    }
+
+    #[test]
+    fn message_starts_with_code() {
+        assert_eq!(
+            REFERENCE_FIELD.message("field `name` ..."),
+            "[DIO001] field `name` ..."
+        );
+        assert_eq!(explain("DIO003"), Some(&MUT_REFERENCE_FIELD));
+        assert_eq!(explain("DIO999"), None);
+    }
+}
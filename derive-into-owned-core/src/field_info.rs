@@ -0,0 +1,19 @@
+//! The runtime side of `#[into_owned(field_info)]`.
+
+/// Describes a field which `into_owned` converts, listed in the generated
+/// `Foo::BORROW_FIELDS`.
+///
+/// The generated code refers to this type as `::derive_into_owned_core::FieldInfo`, so the crate
+/// using the derive needs `derive-into-owned-core` as a dependency.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct FieldInfo {
+    /// The variant holding the field, `None` for structs
+    pub variant: Option<&'static str>,
+    /// The name of the field, or its index for tuple structs and variants
+    pub name: &'static str,
+    /// The classification in the form used by `#[into_owned(expect = "...")]`, like
+    /// `option(iterable(cow))`, or `with` and `skip` for the fields with those options
+    pub kind: &'static str,
+    /// How many containers, like options, vectors and boxes, are around the borrowed data
+    pub nesting: usize,
+}
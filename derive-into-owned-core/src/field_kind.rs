@@ -0,0 +1,2006 @@
+use quote::{format_ident, quote};
+use syn::visit_mut::VisitMut;
+
+use crate::helpers::{
+    collect_segments, has_non_static_lifetimes, is_arc, is_bound, is_box, is_bump_string,
+    is_bump_vec, is_c_str, is_cow, is_cow_alike, is_dashmap, is_known, is_map, is_once_cell,
+    is_opt, is_pin, is_rc, is_set, is_varzerovec, is_vec, is_zerovec, last_type_argument,
+    map_type_arguments, number_of_type_arguments, type_arguments, ungroup, StaticLifetimes,
+};
+
+/// What is done to the Cow and Cow-alike values found while walking a field by value with
+/// [`FieldKind::walk_by_value`].
+#[derive(Clone)]
+#[non_exhaustive]
+pub enum Strategy {
+    /// Same type with `'static` lifetimes, `Cow`s stay `Cow`s
+    IntoOwned,
+    /// Owned mirror type, `Cow<'a, T>` becomes `<T as ToOwned>::Owned`
+    Mirror,
+    /// Owned mirror type like `Mirror`, except that the owned values of `Cow`s and plain
+    /// references are passed through `From::from`, for the leaf types chosen with
+    /// [`MirrorLeaves`] in [`FieldKind::mirror_type_with`]
+    MirrorInto,
+    /// Same type with `'static` lifetimes by leaking the owned values, which also works for plain
+    /// references. "Cow-alike" values are expected to have a `leaked` method as well.
+    Leak,
+    /// Like `IntoOwned` but `Cow<'a, str>` values are routed through the [`Interner`] given as an
+    /// expression of type `&mut impl Interner`. "Cow-alike" values are expected to have an
+    /// `into_owned_interned` method as well.
+    ///
+    /// [`Interner`]: crate::Interner
+    Intern(proc_macro2::TokenStream),
+    /// Like `IntoOwned` but the owned values of `Cow`s and the collected vectors are shrunk to
+    /// fit, so that they do not keep the spare capacity of parse buffers. The owned types need a
+    /// `shrink_to_fit` method like `String` and `Vec<T>` have, and "Cow-alike" values are
+    /// expected to have an `into_owned_and_shrink` method as well.
+    Shrink,
+    /// The same type with every `Cow<'a, str>` passed through the `FnMut` given as an
+    /// expression, leaving the other values as they are. "Cow-alike" values are expected to have a
+    /// `map_cows` method as well.
+    MapCows(proc_macro2::TokenStream),
+}
+
+/// The owned types used for the leaves of the mirror type instead of `String` and `Vec<T>`.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct MirrorLeaves {
+    /// `Arc<str>` and `Arc<[T]>`, so that clones share the buffers
+    pub arc: bool,
+    /// `bytes::Bytes` for byte slices, taking precedence over `arc`
+    pub bytes: bool,
+}
+
+/// A type listed as known, like with `#[into_owned(known(...))]`.
+#[derive(Clone)]
+pub struct KnownType {
+    /// The path of the type, also matches when the field uses only a suffix of it
+    pub path: syn::Path,
+    /// The method returning the `'static` version
+    pub method: syn::Ident,
+}
+
+/// Options for classifying the field types with [`FieldKind::resolve`].
+#[derive(Clone, Default)]
+pub struct Classifier {
+    /// Types which are matched before any of the built-in ones, first match wins
+    pub known: Vec<KnownType>,
+    /// Generic types like `Spanned<T>` whose single type argument is converted through the
+    /// given method, which takes a closure like `Option::map`, keeping the wrapper
+    pub wrappers: Vec<KnownType>,
+    /// Converts the value within an `Rc<T>` or `Arc<T>`, which is taken out of it when the field
+    /// holds the only reference and cloned otherwise, so it needs `T: Clone`
+    pub unwrap_or_clone: bool,
+}
+
+/// How a field is converted, as decided from its type by [`FieldKind::resolve`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum FieldKind {
+    PlainCow,
+    /// `Cow<'a, str>`, handled like `PlainCow` except when interning
+    StrCow,
+    AssumedCow,
+    /// Type listed in `#[into_owned(known(...))]`, converted with the given method
+    Known(syn::Ident),
+    /// Wrapper listed in `#[into_owned(wrapper(...))]` whose payload needs converting, mapped with
+    /// the given method
+    Wrapper(syn::Ident, Box<FieldKind>),
+    /// `zerovec::ZeroVec`, with the `zerovec` feature
+    ZeroVec,
+    /// `zerovec::VarZeroVec`, with the `zerovec` feature
+    VarZeroVec,
+    /// Option fields with either PlainCow or AssumedCow
+    OptField(usize, Box<FieldKind>),
+    IterableField(Box<FieldKind>),
+    /// `HashSet<T>`, `BTreeSet<T>` or `BinaryHeap<T>` where `T` needs converting, collected back
+    /// into the same collection, so the converted `T` needs to implement `Hash` or `Ord` as well
+    SetField(Box<FieldKind>),
+    /// Maps where either the key or the value (or both) need converting
+    MapField(Box<FieldKind>, Box<FieldKind>),
+    /// `dashmap::DashMap<K, V>` where either the key or the value need converting, with the
+    /// `dashmap` feature. The entries can only be reached through lock guards, so `borrowed`
+    /// clones them into a new map, which keeps the borrowed `Cow`s borrowed.
+    DashMap(Box<FieldKind>, Box<FieldKind>),
+    /// `Box<T>` where `T` needs converting
+    Boxed(Box<FieldKind>),
+    /// `Rc<T>` where `T` needs converting, with [`Classifier::unwrap_or_clone`]. `borrowed`
+    /// shares the `Rc` instead.
+    RcField(Box<FieldKind>),
+    /// `Arc<T>` where `T` needs converting, like [`FieldKind::RcField`]
+    ArcField(Box<FieldKind>),
+    /// `Pin<Box<T>>` where `T` needs converting, holding the [`FieldKind::Boxed`] of the box.
+    /// The box is taken out of the `Pin` and pinned again, so `T` needs to be `Unpin`.
+    Pinned(Box<FieldKind>),
+    /// `std::ops::Bound<T>` where `T` needs converting, like the keys of range queries
+    Bound(Box<FieldKind>),
+    /// `OnceCell<T>` or `OnceLock<T>` where `T` needs converting, the value is converted if the
+    /// cell is initialized
+    OnceCell(Box<FieldKind>),
+    /// Tuple like `(String, Cow<'a, str>)` where any of the elements needs converting, holding
+    /// the kinds of all the elements
+    TupleField(Vec<FieldKind>),
+    /// Plain shared reference `&'a T`, which only the mirror type can own
+    Reference,
+    /// `bumpalo::collections::Vec<'bump, T>`, with the `bumpalo` feature, which like plain
+    /// references only the mirror type can own, as a `Vec`
+    BumpVec(Box<FieldKind>),
+    /// `bumpalo::collections::String<'bump>`, with the `bumpalo` feature, a `String` in the
+    /// mirror type
+    BumpString,
+    JustMoved,
+}
+
+impl FieldKind {
+    /// Classifies the field type `ty`.
+    pub fn resolve(ty: &syn::Type, classifier: &Classifier) -> Self {
+        let ty = ungroup(ty);
+
+        if let syn::Type::Path(syn::TypePath { ref path, .. }) = ty {
+            let segments = collect_segments(path);
+
+            if let Some(known) = classifier
+                .known
+                .iter()
+                .find(|known| is_known(&segments, &known.path))
+            {
+                FieldKind::Known(known.method.clone())
+            } else if let Some(wrapper) = classifier
+                .wrappers
+                .iter()
+                .find(|wrapper| is_known(&segments, &wrapper.path))
+                .filter(|_| number_of_type_arguments(&segments) == 1)
+            {
+                match FieldKind::resolve(&type_arguments(ty)[0], classifier) {
+                    FieldKind::JustMoved => FieldKind::JustMoved,
+                    inner => FieldKind::Wrapper(wrapper.method.clone(), Box::new(inner)),
+                }
+            } else if is_cow(&segments) {
+                match last_type_argument(ty).as_ref().map(ungroup) {
+                    Some(syn::Type::Path(p)) if p.qself.is_none() && p.path.is_ident("str") => {
+                        FieldKind::StrCow
+                    }
+                    _ => FieldKind::PlainCow,
+                }
+            } else if is_zerovec(&segments) {
+                FieldKind::ZeroVec
+            } else if is_varzerovec(&segments) {
+                FieldKind::VarZeroVec
+            } else if is_bump_string(&segments) {
+                FieldKind::BumpString
+            } else if let Some(inner) = is_bump_vec(&segments) {
+                FieldKind::BumpVec(Box::new(FieldKind::resolve(&inner, classifier)))
+            } else if is_cow_alike(&segments) {
+                FieldKind::AssumedCow
+            } else if let Some(inner) = is_opt(&segments) {
+                match FieldKind::resolve(&inner, classifier) {
+                    FieldKind::JustMoved => FieldKind::JustMoved,
+                    FieldKind::OptField(levels, inner) => FieldKind::OptField(levels + 1, inner),
+                    inner => FieldKind::OptField(1, Box::new(inner)),
+                }
+            } else if let Some(inner) = is_vec(&segments) {
+                match FieldKind::resolve(&inner, classifier) {
+                    FieldKind::JustMoved => FieldKind::JustMoved,
+                    inner => FieldKind::IterableField(Box::new(inner)),
+                }
+            } else if let Some(inner) = is_set(&segments) {
+                match FieldKind::resolve(&inner, classifier) {
+                    FieldKind::JustMoved => FieldKind::JustMoved,
+                    inner => FieldKind::SetField(Box::new(inner)),
+                }
+            } else if let Some(inner) = is_box(&segments) {
+                match FieldKind::resolve(&inner, classifier) {
+                    FieldKind::JustMoved => FieldKind::JustMoved,
+                    inner => FieldKind::Boxed(Box::new(inner)),
+                }
+            } else if let Some(inner) = is_rc(&segments).filter(|_| classifier.unwrap_or_clone) {
+                match FieldKind::resolve(&inner, classifier) {
+                    FieldKind::JustMoved => FieldKind::JustMoved,
+                    inner => FieldKind::RcField(Box::new(inner)),
+                }
+            } else if let Some(inner) = is_arc(&segments).filter(|_| classifier.unwrap_or_clone) {
+                match FieldKind::resolve(&inner, classifier) {
+                    FieldKind::JustMoved => FieldKind::JustMoved,
+                    inner => FieldKind::ArcField(Box::new(inner)),
+                }
+            } else if let Some(inner) = is_pin(&segments) {
+                match FieldKind::resolve(&inner, classifier) {
+                    inner @ FieldKind::Boxed(_) => FieldKind::Pinned(Box::new(inner)),
+                    _ => FieldKind::JustMoved,
+                }
+            } else if let Some(inner) = is_bound(&segments) {
+                match FieldKind::resolve(&inner, classifier) {
+                    FieldKind::JustMoved => FieldKind::JustMoved,
+                    inner => FieldKind::Bound(Box::new(inner)),
+                }
+            } else if let Some(inner) = is_once_cell(&segments) {
+                match FieldKind::resolve(&inner, classifier) {
+                    FieldKind::JustMoved => FieldKind::JustMoved,
+                    inner => FieldKind::OnceCell(Box::new(inner)),
+                }
+            } else if let Some((key, value)) = is_map(&segments) {
+                match (
+                    FieldKind::resolve(&key, classifier),
+                    FieldKind::resolve(&value, classifier),
+                ) {
+                    (FieldKind::JustMoved, FieldKind::JustMoved) => FieldKind::JustMoved,
+                    (key, value) => FieldKind::MapField(Box::new(key), Box::new(value)),
+                }
+            } else if let Some((key, value)) = is_dashmap(&segments) {
+                match (
+                    FieldKind::resolve(&key, classifier),
+                    FieldKind::resolve(&value, classifier),
+                ) {
+                    (FieldKind::JustMoved, FieldKind::JustMoved) => FieldKind::JustMoved,
+                    (key, value) => FieldKind::DashMap(Box::new(key), Box::new(value)),
+                }
+            } else {
+                FieldKind::JustMoved
+            }
+        } else if let syn::Type::Reference(ref reference) = ty {
+            let is_static = reference
+                .lifetime
+                .as_ref()
+                .map_or(false, |lifetime| lifetime.ident == "static");
+
+            if reference.mutability.is_none() && !is_static {
+                FieldKind::Reference
+            } else {
+                FieldKind::JustMoved
+            }
+        } else if let syn::Type::Tuple(ref tuple) = ty {
+            let elems = tuple
+                .elems
+                .iter()
+                .map(|ty| FieldKind::resolve(ty, classifier))
+                .collect::<Vec<_>>();
+
+            if elems.iter().all(|kind| *kind == FieldKind::JustMoved) {
+                FieldKind::JustMoved
+            } else {
+                FieldKind::TupleField(elems)
+            }
+        } else {
+            FieldKind::JustMoved
+        }
+    }
+
+    /// Finds the first part of `ty` which would be moved as is even though it borrows, or which
+    /// cannot be looked into at all, like a type given by a macro. These are what
+    /// `#[into_owned(strict)]` reports.
+    pub fn find_fallback(ty: &syn::Type, classifier: &Classifier) -> Option<syn::Type> {
+        use self::FieldKind::*;
+
+        let ty = ungroup(ty);
+
+        match FieldKind::resolve(ty, classifier) {
+            Reference | BumpVec(_) | BumpString => Some(ty.clone()),
+            Wrapper(..) | RcField(_) | ArcField(_) | TupleField(_) => contained_types(ty)
+                .iter()
+                .find_map(|ty| FieldKind::find_fallback(ty, classifier)),
+            JustMoved if matches!(ty, syn::Type::Macro(_)) => Some(ty.clone()),
+            JustMoved if !has_non_static_lifetimes(ty) => None,
+            JustMoved | OptField(..) | IterableField(_) | SetField(_) | MapField(..)
+            | DashMap(..) | Boxed(_) | Pinned(_) | Bound(_) | OnceCell(_) => {
+                let is_container = match ty {
+                    syn::Type::Path(syn::TypePath { ref path, .. }) => {
+                        let segments = collect_segments(path);
+                        is_opt(&segments).is_some()
+                            || is_vec(&segments).is_some()
+                            || is_set(&segments).is_some()
+                            || is_box(&segments).is_some()
+                            || is_pin(&segments).is_some()
+                            || is_bound(&segments).is_some()
+                            || is_once_cell(&segments).is_some()
+                            || is_map(&segments).is_some()
+                            || is_dashmap(&segments).is_some()
+                    }
+                    syn::Type::Tuple(_) => true,
+                    _ => false,
+                };
+
+                if is_container {
+                    // report the innermost part, like `RefCell<..>` for `Vec<RefCell<..>>`
+                    contained_types(ty)
+                        .iter()
+                        .find_map(|ty| FieldKind::find_fallback(ty, classifier))
+                } else {
+                    Some(ty.clone())
+                }
+            }
+            _ => None,
+        }
+    }
+
+    /// The parts of `ty` which [`FieldKind::borrow_or_clone`] clones, like `T` for
+    /// `HashMap<Cow<'a, str>, T>` or `Vec<T>` for `Vec<T>`. Plain references are copied and not
+    /// included.
+    pub fn cloned_types(ty: &syn::Type, classifier: &Classifier) -> Vec<syn::Type> {
+        use self::FieldKind::*;
+
+        let ty = ungroup(ty);
+
+        match FieldKind::resolve(ty, classifier) {
+            JustMoved | DashMap(..) | BumpVec(_) | BumpString | RcField(_) | ArcField(_) => {
+                vec![ty.clone()]
+            }
+            OptField(..) | IterableField(_) | SetField(_) | MapField(..) | Boxed(_) | Pinned(_)
+            | Bound(_) | OnceCell(_) | Wrapper(..) | TupleField(_) => contained_types(ty)
+                .iter()
+                .flat_map(|ty| FieldKind::cloned_types(ty, classifier))
+                .collect(),
+            PlainCow | StrCow | AssumedCow | Known(_) | ZeroVec | VarZeroVec | Reference => {
+                Vec::new()
+            }
+        }
+    }
+
+    /// The "Cow-alike" parts of `ty` which [`FieldKind::borrow_or_clone`] calls `borrowed()` on,
+    /// like `Foo<'a>` for `Vec<Foo<'a>>`. The known types are not included.
+    pub fn cow_alike_types(ty: &syn::Type, classifier: &Classifier) -> Vec<syn::Type> {
+        use self::FieldKind::*;
+
+        let ty = ungroup(ty);
+
+        match FieldKind::resolve(ty, classifier) {
+            AssumedCow => vec![ty.clone()],
+            OptField(..) | IterableField(_) | SetField(_) | MapField(..) | Boxed(_) | Pinned(_)
+            | Bound(_) | OnceCell(_) | Wrapper(..) | TupleField(_) => contained_types(ty)
+                .iter()
+                .flat_map(|ty| FieldKind::cow_alike_types(ty, classifier))
+                .collect(),
+            PlainCow | StrCow | Known(_) | ZeroVec | VarZeroVec | Reference | DashMap(..)
+            | BumpVec(_) | BumpString | RcField(_) | ArcField(_) | JustMoved => Vec::new(),
+        }
+    }
+
+    /// True if the field contains plain references which cannot be turned into `'static`.
+    pub fn has_references(&self) -> bool {
+        use self::FieldKind::*;
+
+        match *self {
+            Reference | BumpVec(_) | BumpString => true,
+            OptField(_, ref inner)
+            | IterableField(ref inner)
+            | SetField(ref inner)
+            | Boxed(ref inner)
+            | RcField(ref inner)
+            | ArcField(ref inner)
+            | Pinned(ref inner)
+            | Bound(ref inner)
+            | OnceCell(ref inner)
+            | Wrapper(_, ref inner) => inner.has_references(),
+            MapField(ref key, ref value) | DashMap(ref key, ref value) => {
+                key.has_references() || value.has_references()
+            }
+            TupleField(ref elems) => elems.iter().any(FieldKind::has_references),
+            PlainCow | StrCow | AssumedCow | Known(_) | ZeroVec | VarZeroVec | JustMoved => false,
+        }
+    }
+
+    /// How many containers, like options, vectors and boxes, are around the borrowed data, the
+    /// deeper one for maps and tuples. Tuples are not counted as containers.
+    pub fn nesting(&self) -> usize {
+        use self::FieldKind::*;
+
+        match *self {
+            OptField(levels, ref inner) => levels + inner.nesting(),
+            Pinned(ref inner) => inner.nesting(),
+            IterableField(ref inner)
+            | SetField(ref inner)
+            | Boxed(ref inner)
+            | RcField(ref inner)
+            | ArcField(ref inner)
+            | Bound(ref inner)
+            | OnceCell(ref inner)
+            | Wrapper(_, ref inner)
+            | BumpVec(ref inner) => 1 + inner.nesting(),
+            MapField(ref key, ref value) | DashMap(ref key, ref value) => {
+                1 + key.nesting().max(value.nesting())
+            }
+            TupleField(ref elems) => elems.iter().map(FieldKind::nesting).max().unwrap_or(0),
+            PlainCow | StrCow | AssumedCow | Known(_) | ZeroVec | VarZeroVec | Reference
+            | BumpString | JustMoved => 0,
+        }
+    }
+
+    /// Converts the field `var` into the `'static` version of its type.
+    pub fn move_or_clone_field(&self, var: &proc_macro2::TokenStream) -> proc_macro2::TokenStream {
+        self.walk_by_value(var, &Strategy::IntoOwned)
+    }
+
+    /// Converts the field into the type returned by [`FieldKind::mirror_type`].
+    pub fn move_into_mirror(&self, var: &proc_macro2::TokenStream) -> proc_macro2::TokenStream {
+        self.walk_by_value(var, &Strategy::Mirror)
+    }
+
+    /// Converts the field `var` by moving it and applying `strategy` to the Cow and Cow-alike
+    /// values within.
+    pub fn walk_by_value(
+        &self,
+        var: &proc_macro2::TokenStream,
+        strategy: &Strategy,
+    ) -> proc_macro2::TokenStream {
+        use self::FieldKind::*;
+
+        match *self {
+            StrCow => match strategy {
+                Strategy::Intern(ref interner) => quote! {
+                    ::derive_into_owned_core::Interner::intern(&mut *#interner, &#var)
+                },
+                Strategy::MapCows(ref f) => quote! { (#f)(#var) },
+                _ => PlainCow.walk_by_value(var, strategy),
+            },
+            PlainCow => match strategy {
+                Strategy::IntoOwned | Strategy::Intern(_) => {
+                    quote! { ::std::borrow::Cow::Owned(#var.into_owned()) }
+                }
+                Strategy::Shrink => quote! {
+                    ::std::borrow::Cow::Owned({
+                        let mut owned = #var.into_owned();
+                        owned.shrink_to_fit();
+                        owned
+                    })
+                },
+                Strategy::Mirror => quote! { #var.into_owned() },
+                // the owned types without a replacement are converted into themselves
+                Strategy::MirrorInto => quote! { ::std::convert::From::from(#var.into_owned()) },
+                Strategy::Leak => {
+                    let leaked = leak(&quote! { #var.into_owned() });
+                    quote! { ::std::borrow::Cow::Borrowed(#leaked) }
+                }
+                Strategy::MapCows(_) => quote! { #var },
+            },
+            AssumedCow => match strategy {
+                Strategy::Leak => quote! { #var.leaked() },
+                Strategy::Intern(ref interner) => {
+                    quote! { #var.into_owned_interned(&mut *#interner) }
+                }
+                Strategy::Shrink => quote! { #var.into_owned_and_shrink() },
+                // through a trait object, so that recursive types do not instantiate `map_cows`
+                // for ever deeper `&mut &mut F`
+                Strategy::MapCows(ref f) => quote! {
+                    #var.map_cows(
+                        &mut #f
+                            as &mut dyn for<'c> ::std::ops::FnMut(
+                                ::std::borrow::Cow<'c, str>,
+                            ) -> ::std::borrow::Cow<'c, str>
+                    )
+                },
+                _ => quote! { #var.into_owned() },
+            },
+            ZeroVec | VarZeroVec | Known(_) if matches!(strategy, Strategy::MapCows(_)) => {
+                quote! { #var }
+            }
+            ZeroVec | VarZeroVec => quote! { #var.into_owned() },
+            Known(ref method) => quote! { #var.#method() },
+            Wrapper(ref method, ref inner) => {
+                let tokens = inner.walk_by_value(&quote! { val }, strategy);
+
+                quote! { #var.#method(|val| #tokens) }
+            }
+            OptField(levels, ref inner) => {
+                let next = format_ident!("val");
+                let next = quote! { #next };
+
+                let mut tokens = inner.walk_by_value(&next, strategy);
+
+                for _ in 0..(levels - 1) {
+                    tokens = quote! { #next.map(|#next| #tokens) };
+                }
+
+                quote! { #var.map(|#next| #tokens) }
+            }
+            IterableField(ref inner) => {
+                let next = format_ident!("x");
+                let next = quote! { #next };
+
+                let tokens = inner.walk_by_value(&next, strategy);
+
+                // spelled out for when the result is passed on to `From::from`, as in `OnceCell`
+                let collected =
+                    quote! { #var.into_iter().map(|x| #tokens).collect::<::std::vec::Vec<_>>() };
+
+                match strategy {
+                    // collecting reuses the allocation of the original vector
+                    Strategy::Shrink => quote! {
+                        {
+                            let mut collected = #collected;
+                            collected.shrink_to_fit();
+                            collected
+                        }
+                    },
+                    _ => collected,
+                }
+            }
+            SetField(ref inner) => {
+                let tokens = inner.walk_by_value(&quote! { x }, strategy);
+
+                quote! { #var.into_iter().map(|x| #tokens).collect() }
+            }
+            MapField(ref key, ref value) | DashMap(ref key, ref value) => {
+                let key = key.walk_by_value(&quote! { k }, strategy);
+                let value = value.walk_by_value(&quote! { v }, strategy);
+
+                quote! { #var.into_iter().map(|(k, v)| (#key, #value)).collect() }
+            }
+            Boxed(ref inner) => {
+                let tokens = inner.walk_by_value(&quote! { (*#var) }, strategy);
+
+                quote! { ::std::boxed::Box::new(#tokens) }
+            }
+            RcField(ref inner) => {
+                let tokens = inner.walk_by_value(&quote! { val }, strategy);
+                let val = unwrap_or_clone(&quote! { ::std::rc::Rc }, var);
+
+                quote! { ::std::rc::Rc::new({ let val = #val; #tokens }) }
+            }
+            Pinned(ref inner) => {
+                let unpinned = quote! { ::std::pin::Pin::into_inner(#var) };
+                let tokens = inner.walk_by_value(&unpinned, strategy);
+
+                quote! { ::std::pin::Pin::new(#tokens) }
+            }
+            ArcField(ref inner) => {
+                let tokens = inner.walk_by_value(&quote! { val }, strategy);
+                let val = unwrap_or_clone(&quote! { ::std::sync::Arc }, var);
+
+                quote! { ::std::sync::Arc::new({ let val = #val; #tokens }) }
+            }
+            Bound(ref inner) => {
+                let tokens = inner.walk_by_value(&quote! { val }, strategy);
+
+                // `Bound::map` needs Rust 1.77
+                quote! {
+                    match #var {
+                        ::std::ops::Bound::Included(val) => ::std::ops::Bound::Included(#tokens),
+                        ::std::ops::Bound::Excluded(val) => ::std::ops::Bound::Excluded(#tokens),
+                        ::std::ops::Bound::Unbounded => ::std::ops::Bound::Unbounded,
+                    }
+                }
+            }
+            OnceCell(ref inner) => {
+                let tokens = inner.walk_by_value(&quote! { val }, strategy);
+
+                quote! {
+                    match #var.into_inner() {
+                        ::std::option::Option::Some(val) => ::std::convert::From::from(#tokens),
+                        ::std::option::Option::None => ::std::default::Default::default(),
+                    }
+                }
+            }
+            TupleField(ref elems) => {
+                let names = tuple_names(elems.len());
+                let tokens = elems
+                    .iter()
+                    .zip(&names)
+                    .map(|(kind, name)| kind.walk_by_value(&quote! { #name }, strategy));
+
+                quote! { { let (#(#names,)*) = #var; (#(#tokens,)*) } }
+            }
+            // like plain references, the arena can only be left behind with the mirror type
+            BumpVec(ref inner) => match strategy {
+                Strategy::Mirror | Strategy::MirrorInto => {
+                    let tokens = inner.walk_by_value(&quote! { x }, strategy);
+
+                    quote! { #var.into_iter().map(|x| #tokens).collect::<::std::vec::Vec<_>>() }
+                }
+                _ => quote! { #var },
+            },
+            BumpString => match strategy {
+                Strategy::Mirror => quote! { ::std::string::String::from(#var.as_str()) },
+                Strategy::MirrorInto => quote! {
+                    ::std::convert::From::from(::std::string::String::from(#var.as_str()))
+                },
+                _ => quote! { #var },
+            },
+            Reference => match strategy {
+                Strategy::IntoOwned
+                | Strategy::Intern(_)
+                | Strategy::Shrink
+                | Strategy::MapCows(_) => quote! { #var },
+                Strategy::Mirror => quote! { #var.to_owned() },
+                Strategy::MirrorInto => {
+                    quote! { ::std::convert::From::from(::std::borrow::ToOwned::to_owned(#var)) }
+                }
+                Strategy::Leak => leak(&quote! { ::std::borrow::ToOwned::to_owned(#var) }),
+            },
+            JustMoved => quote! { #var },
+        }
+    }
+
+    /// A `bool` expression telling whether the field owns all of its data, or `None` when that
+    /// cannot be told, like for "Cow-alike" fields. `var` is expected to be a reference to the
+    /// field.
+    pub fn is_owned(&self, var: &proc_macro2::TokenStream) -> Option<proc_macro2::TokenStream> {
+        use self::FieldKind::*;
+
+        Some(match *self {
+            PlainCow | StrCow => quote! {
+                match *#var {
+                    ::std::borrow::Cow::Owned(_) => true,
+                    ::std::borrow::Cow::Borrowed(_) => false,
+                }
+            },
+            OptField(levels, ref inner) => {
+                let mut tokens = inner.is_owned(&quote! { val })?;
+
+                for _ in 0..levels {
+                    tokens = quote! {
+                        match *val {
+                            ::std::option::Option::Some(ref val) => #tokens,
+                            ::std::option::Option::None => true,
+                        }
+                    };
+                }
+
+                quote! { { let val = #var; #tokens } }
+            }
+            IterableField(ref inner) => {
+                let tokens = inner.is_owned(&quote! { x })?;
+
+                quote! { #var.iter().all(|x| #tokens) }
+            }
+            SetField(ref inner) => {
+                let tokens = inner.is_owned(&quote! { x })?;
+
+                quote! { #var.iter().all(|x| #tokens) }
+            }
+            MapField(ref key, ref value) => {
+                let key = key.is_owned(&quote! { k })?;
+                let value = value.is_owned(&quote! { v })?;
+
+                quote! { #var.iter().all(|(k, v)| #key && #value) }
+            }
+            DashMap(ref key, ref value) => {
+                let key = key.is_owned(&quote! { k })?;
+                let value = value.is_owned(&quote! { v })?;
+
+                // in parentheses as a `match` would end the statement
+                quote! {
+                    #var.iter().all(|entry| {
+                        let (k, v) = entry.pair();
+                        (#key) && #value
+                    })
+                }
+            }
+            Boxed(ref inner) | RcField(ref inner) | ArcField(ref inner) => {
+                inner.is_owned(&quote! { (&**#var) })?
+            }
+            // the pin dereferences to the boxed value like the box does
+            Pinned(ref inner) => inner.is_owned(var)?,
+            Bound(ref inner) => {
+                let tokens = inner.is_owned(&quote! { val })?;
+
+                quote! {
+                    match *#var {
+                        ::std::ops::Bound::Included(ref val)
+                        | ::std::ops::Bound::Excluded(ref val) => #tokens,
+                        ::std::ops::Bound::Unbounded => true,
+                    }
+                }
+            }
+            OnceCell(ref inner) => {
+                let tokens = inner.is_owned(&quote! { val })?;
+
+                quote! {
+                    match #var.get() {
+                        ::std::option::Option::Some(val) => #tokens,
+                        ::std::option::Option::None => true,
+                    }
+                }
+            }
+            TupleField(ref elems) => {
+                let tokens = elems
+                    .iter()
+                    .enumerate()
+                    .filter(|(_, kind)| **kind != JustMoved)
+                    .map(|(index, kind)| kind.is_owned(&tuple_element(var, index)))
+                    .collect::<Option<Vec<_>>>()?;
+
+                quote! { (#((#tokens))&&*) }
+            }
+            JustMoved => quote! { true },
+            // the payload of a wrapper can only be reached by value
+            AssumedCow | Known(_) | Wrapper(..) | ZeroVec | VarZeroVec | Reference | BumpVec(_)
+            | BumpString => return None,
+        })
+    }
+
+    /// An `Option` of a copy of the field with the same lifetimes, which is `Some` only when all
+    /// the `Cow`s within are `Cow::Borrowed`. The values whose ownership cannot be told, like
+    /// known types and wrappers, are cloned, and "Cow-alike" fields are expected to have a
+    /// `try_reborrow_original` method as well. `var` is expected to be a reference to the field.
+    pub fn try_reborrow(&self, var: &proc_macro2::TokenStream) -> proc_macro2::TokenStream {
+        use self::FieldKind::*;
+
+        match *self {
+            PlainCow | StrCow => quote! {
+                match *#var {
+                    ::std::borrow::Cow::Borrowed(val) => {
+                        ::std::option::Option::Some(::std::borrow::Cow::Borrowed(val))
+                    }
+                    ::std::borrow::Cow::Owned(_) => ::std::option::Option::None,
+                }
+            },
+            AssumedCow => quote! { #var.try_reborrow_original() },
+            OptField(levels, ref inner) => {
+                let mut tokens = inner.try_reborrow(&quote! { val });
+
+                for _ in 0..levels {
+                    tokens = quote! {
+                        match *val {
+                            ::std::option::Option::Some(ref val) => {
+                                (#tokens).map(::std::option::Option::Some)
+                            }
+                            ::std::option::Option::None => {
+                                ::std::option::Option::Some(::std::option::Option::None)
+                            }
+                        }
+                    };
+                }
+
+                quote! { { let val = #var; #tokens } }
+            }
+            IterableField(ref inner) => {
+                let tokens = inner.try_reborrow(&quote! { x });
+
+                quote! {
+                    #var.iter()
+                        .map(|x| #tokens)
+                        .collect::<::std::option::Option<::std::vec::Vec<_>>>()
+                }
+            }
+            SetField(ref inner) => {
+                let tokens = inner.try_reborrow(&quote! { x });
+
+                quote! { #var.iter().map(|x| #tokens).collect::<::std::option::Option<_>>() }
+            }
+            MapField(ref key, ref value) => {
+                let key = key.try_reborrow(&quote! { k });
+                let value = value.try_reborrow(&quote! { v });
+
+                quote! {
+                    #var.iter()
+                        .map(|(k, v)| ::std::option::Option::Some(((#key)?, (#value)?)))
+                        .collect::<::std::option::Option<_>>()
+                }
+            }
+            Boxed(ref inner) => {
+                let tokens = inner.try_reborrow(&quote! { (&**#var) });
+
+                quote! { (#tokens).map(::std::boxed::Box::new) }
+            }
+            Pinned(ref inner) => {
+                let tokens = inner.try_reborrow(var);
+
+                quote! { (#tokens).map(::std::pin::Pin::new) }
+            }
+            Bound(ref inner) => {
+                let tokens = inner.try_reborrow(&quote! { val });
+
+                quote! {
+                    match *#var {
+                        ::std::ops::Bound::Included(ref val) => {
+                            (#tokens).map(::std::ops::Bound::Included)
+                        }
+                        ::std::ops::Bound::Excluded(ref val) => {
+                            (#tokens).map(::std::ops::Bound::Excluded)
+                        }
+                        ::std::ops::Bound::Unbounded => {
+                            ::std::option::Option::Some(::std::ops::Bound::Unbounded)
+                        }
+                    }
+                }
+            }
+            OnceCell(ref inner) => {
+                let tokens = inner.try_reborrow(&quote! { val });
+
+                let tokens = quote! { (#tokens).map(::std::convert::From::from) };
+
+                quote! {
+                    match #var.get() {
+                        ::std::option::Option::Some(val) => #tokens,
+                        ::std::option::Option::None => {
+                            ::std::option::Option::Some(::std::default::Default::default())
+                        }
+                    }
+                }
+            }
+            TupleField(ref elems) => {
+                let names = tuple_names(elems.len());
+                let tokens = elems
+                    .iter()
+                    .enumerate()
+                    .map(|(index, kind)| kind.try_reborrow(&tuple_element(var, index)));
+
+                quote! {
+                    match (#(#tokens,)*) {
+                        (#(::std::option::Option::Some(#names),)*) => {
+                            ::std::option::Option::Some((#(#names,)*))
+                        }
+                        _ => ::std::option::Option::None,
+                    }
+                }
+            }
+            Reference => quote! { ::std::option::Option::Some(*#var) },
+            DashMap(..) => {
+                let cloned = self.borrow_or_clone(var);
+
+                quote! { ::std::option::Option::Some(#cloned) }
+            }
+            Known(_) | Wrapper(..) | ZeroVec | VarZeroVec | BumpVec(_) | BumpString
+            | RcField(_) | ArcField(_) | JustMoved => {
+                quote! { ::std::option::Option::Some(#var.clone()) }
+            }
+        }
+    }
+
+    /// Statements pushing every `Cow::Borrowed` string of the field into the `Vec<&str>` `out`, or
+    /// `None` when the field cannot hold any. "Cow-alike" fields are expected to have a
+    /// `borrowed_strs` method as well. `var` is expected to be a reference to the field.
+    pub fn push_borrowed_strs(
+        &self,
+        var: &proc_macro2::TokenStream,
+        out: &proc_macro2::TokenStream,
+    ) -> Option<proc_macro2::TokenStream> {
+        use self::FieldKind::*;
+
+        Some(match *self {
+            StrCow => quote! {
+                if let ::std::borrow::Cow::Borrowed(s) = *#var {
+                    #out.push(s);
+                }
+            },
+            AssumedCow => quote! { #out.extend(#var.borrowed_strs()); },
+            OptField(levels, ref inner) => {
+                let mut tokens = inner.push_borrowed_strs(&quote! { val }, out)?;
+
+                for _ in 0..levels {
+                    tokens = quote! {
+                        if let ::std::option::Option::Some(ref val) = *val {
+                            #tokens
+                        }
+                    };
+                }
+
+                quote! { { let val = #var; #tokens } }
+            }
+            IterableField(ref inner) | SetField(ref inner) => {
+                let tokens = inner.push_borrowed_strs(&quote! { x }, out)?;
+
+                quote! {
+                    for x in #var.iter() {
+                        #tokens
+                    }
+                }
+            }
+            MapField(ref key, ref value) => {
+                let key = key.push_borrowed_strs(&quote! { k }, out);
+                let value = value.push_borrowed_strs(&quote! { v }, out);
+                if key.is_none() && value.is_none() {
+                    return None;
+                }
+
+                quote! {
+                    for (k, v) in #var.iter() {
+                        #key
+                        #value
+                    }
+                }
+            }
+            Boxed(ref inner) | RcField(ref inner) | ArcField(ref inner) => {
+                inner.push_borrowed_strs(&quote! { (&**#var) }, out)?
+            }
+            Pinned(ref inner) => inner.push_borrowed_strs(var, out)?,
+            Bound(ref inner) => {
+                let tokens = inner.push_borrowed_strs(&quote! { val }, out)?;
+
+                quote! {
+                    if let ::std::ops::Bound::Included(ref val)
+                    | ::std::ops::Bound::Excluded(ref val) = *#var
+                    {
+                        #tokens
+                    }
+                }
+            }
+            BumpVec(ref inner) => {
+                let tokens = inner.push_borrowed_strs(&quote! { x }, out)?;
+
+                quote! {
+                    for x in #var.iter() {
+                        #tokens
+                    }
+                }
+            }
+            OnceCell(ref inner) => {
+                let tokens = inner.push_borrowed_strs(&quote! { val }, out)?;
+
+                quote! {
+                    if let ::std::option::Option::Some(val) = #var.get() {
+                        #tokens
+                    }
+                }
+            }
+            TupleField(ref elems) => {
+                let tokens = elems
+                    .iter()
+                    .enumerate()
+                    .filter_map(|(index, kind)| {
+                        kind.push_borrowed_strs(&tuple_element(var, index), out)
+                    })
+                    .collect::<Vec<_>>();
+                if tokens.is_empty() {
+                    return None;
+                }
+
+                quote! { #(#tokens)* }
+            }
+            // the strings of a `DashMap` cannot outlive the lock guards of its entries
+            PlainCow | Known(_) | Wrapper(..) | ZeroVec | VarZeroVec | DashMap(..) | Reference
+            | BumpString | JustMoved => return None,
+        })
+    }
+
+    /// An expression implementing `Debug` which prints the field like `Debug` does, except that
+    /// every `Cow` is prefixed with `[borrowed]` or `[owned]`. `wrapper` is a tuple struct
+    /// implementing `Debug` by calling the `Fn(&mut fmt::Formatter) -> fmt::Result` it holds.
+    /// "Cow-alike" fields are expected to have an `ownership_debug` method as well. `var` is
+    /// expected to be a reference to the field.
+    pub fn ownership_debug(
+        &self,
+        var: &proc_macro2::TokenStream,
+        wrapper: &proc_macro2::TokenStream,
+    ) -> proc_macro2::TokenStream {
+        use self::FieldKind::*;
+
+        let f = crate::helpers::hygienic("f");
+        let map = crate::helpers::hygienic("map");
+
+        match *self {
+            PlainCow | StrCow => quote! {
+                #wrapper(move |#f: &mut ::std::fmt::Formatter<'_>| {
+                    match *#var {
+                        ::std::borrow::Cow::Borrowed(_) => #f.write_str("[borrowed] ")?,
+                        ::std::borrow::Cow::Owned(_) => #f.write_str("[owned] ")?,
+                    }
+                    ::std::fmt::Debug::fmt(&**#var, #f)
+                })
+            },
+            AssumedCow => quote! { #var.ownership_debug() },
+            OptField(levels, ref inner) => {
+                let next = format_ident!("val");
+                let next = quote! { #next };
+
+                let mut tokens = inner.ownership_debug(&next, wrapper);
+
+                for _ in 0..(levels - 1) {
+                    tokens = quote! { #next.as_ref().map(|#next| #tokens) };
+                }
+
+                quote! { #var.as_ref().map(|#next| #tokens) }
+            }
+            IterableField(ref inner) => {
+                let tokens = inner.ownership_debug(&quote! { x }, wrapper);
+
+                quote! {
+                    #wrapper(move |#f: &mut ::std::fmt::Formatter<'_>| {
+                        #f.debug_list().entries(#var.iter().map(|x| #tokens)).finish()
+                    })
+                }
+            }
+            SetField(ref inner) => {
+                let tokens = inner.ownership_debug(&quote! { x }, wrapper);
+
+                quote! {
+                    #wrapper(move |#f: &mut ::std::fmt::Formatter<'_>| {
+                        #f.debug_set().entries(#var.iter().map(|x| #tokens)).finish()
+                    })
+                }
+            }
+            MapField(ref key, ref value) => {
+                let key = key.ownership_debug(&quote! { k }, wrapper);
+                let value = value.ownership_debug(&quote! { v }, wrapper);
+
+                quote! {
+                    #wrapper(move |#f: &mut ::std::fmt::Formatter<'_>| {
+                        #f.debug_map().entries(#var.iter().map(|(k, v)| (#key, #value))).finish()
+                    })
+                }
+            }
+            DashMap(ref key, ref value) => {
+                let key = key.ownership_debug(&quote! { k }, wrapper);
+                let value = value.ownership_debug(&quote! { v }, wrapper);
+
+                quote! {
+                    #wrapper(move |#f: &mut ::std::fmt::Formatter<'_>| {
+                        let mut #map = #f.debug_map();
+                        for entry in #var.iter() {
+                            let (k, v) = entry.pair();
+                            #map.entry(&#key, &#value);
+                        }
+                        #map.finish()
+                    })
+                }
+            }
+            Boxed(ref inner) | RcField(ref inner) | ArcField(ref inner) => {
+                inner.ownership_debug(&quote! { (&**#var) }, wrapper)
+            }
+            Pinned(ref inner) => inner.ownership_debug(var, wrapper),
+            Bound(ref inner) => {
+                let tokens = inner.ownership_debug(&quote! { val }, wrapper);
+
+                quote! {
+                    #wrapper(move |#f: &mut ::std::fmt::Formatter<'_>| match *#var {
+                        ::std::ops::Bound::Included(ref val) => {
+                            #f.debug_tuple("Included").field(&#tokens).finish()
+                        }
+                        ::std::ops::Bound::Excluded(ref val) => {
+                            #f.debug_tuple("Excluded").field(&#tokens).finish()
+                        }
+                        ::std::ops::Bound::Unbounded => #f.write_str("Unbounded"),
+                    })
+                }
+            }
+            OnceCell(ref inner) => {
+                let tokens = inner.ownership_debug(&quote! { val }, wrapper);
+
+                quote! {
+                    #wrapper(move |#f: &mut ::std::fmt::Formatter<'_>| {
+                        #f.debug_tuple("OnceCell").field(&#var.get().map(|val| #tokens)).finish()
+                    })
+                }
+            }
+            // without a name `debug_tuple` prints like the `Debug` of tuples
+            TupleField(ref elems) => {
+                let tokens = elems
+                    .iter()
+                    .enumerate()
+                    .map(|(index, kind)| kind.ownership_debug(&tuple_element(var, index), wrapper));
+
+                quote! {
+                    #wrapper(move |#f: &mut ::std::fmt::Formatter<'_>| {
+                        #f.debug_tuple("")#(.field(&#tokens))*.finish()
+                    })
+                }
+            }
+            Reference => quote! {
+                #wrapper(move |#f: &mut ::std::fmt::Formatter<'_>| {
+                    #f.write_str("[borrowed] ")?;
+                    ::std::fmt::Debug::fmt(&**#var, #f)
+                })
+            },
+            BumpVec(ref inner) => {
+                let tokens = inner.ownership_debug(&quote! { x }, wrapper);
+
+                quote! {
+                    #wrapper(move |#f: &mut ::std::fmt::Formatter<'_>| {
+                        #f.write_str("[borrowed] ")?;
+                        #f.debug_list().entries(#var.iter().map(|x| #tokens)).finish()
+                    })
+                }
+            }
+            BumpString => quote! {
+                #wrapper(move |#f: &mut ::std::fmt::Formatter<'_>| {
+                    #f.write_str("[borrowed] ")?;
+                    ::std::fmt::Debug::fmt(#var.as_str(), #f)
+                })
+            },
+            Known(_) | Wrapper(..) | ZeroVec | VarZeroVec | JustMoved => quote! { #var },
+        }
+    }
+
+    /// `var` is expected to be a reference to the field.
+    pub fn borrow_or_clone(&self, var: &proc_macro2::TokenStream) -> proc_macro2::TokenStream {
+        use self::FieldKind::*;
+
+        match *self {
+            PlainCow | StrCow => quote! { ::std::borrow::Cow::Borrowed(#var.as_ref()) },
+            AssumedCow | Known(_) => quote! { #var.borrowed() },
+            Wrapper(ref method, ref inner) => {
+                let tokens = inner.borrow_or_clone(&quote! { val });
+
+                quote! { #var.as_ref().#method(|val| #tokens) }
+            }
+            ZeroVec => quote! { #var.as_zerovec() },
+            VarZeroVec => quote! { #var.as_varzerovec() },
+            OptField(levels, ref inner) => {
+                let next = format_ident!("val");
+                let next = quote! { #next };
+
+                let mut tokens = inner.borrow_or_clone(&next);
+
+                for _ in 0..(levels - 1) {
+                    tokens = quote! { #next.as_ref().map(|#next| #tokens) };
+                }
+
+                quote! { #var.as_ref().map(|#next| #tokens) }
+            }
+            IterableField(ref inner) => {
+                let next = format_ident!("x");
+                let next = quote! { #next };
+
+                let tokens = inner.borrow_or_clone(&next);
+
+                quote! { #var.iter().map(|x| #tokens).collect::<::std::vec::Vec<_>>() }
+            }
+            SetField(ref inner) => {
+                let tokens = inner.borrow_or_clone(&quote! { x });
+
+                quote! { #var.iter().map(|x| #tokens).collect() }
+            }
+            MapField(ref key, ref value) => {
+                let key = key.borrow_or_clone(&quote! { k });
+                let value = value.borrow_or_clone(&quote! { v });
+
+                quote! { #var.iter().map(|(k, v)| (#key, #value)).collect() }
+            }
+            Boxed(ref inner) => {
+                let tokens = inner.borrow_or_clone(&quote! { (&**#var) });
+
+                quote! { ::std::boxed::Box::new(#tokens) }
+            }
+            // shared with the lifetimes shortened, instead of allocating a new `Rc` or `Arc`
+            RcField(_) => quote! { ::std::rc::Rc::clone(#var) },
+            Pinned(ref inner) => {
+                let tokens = inner.borrow_or_clone(var);
+
+                quote! { ::std::pin::Pin::new(#tokens) }
+            }
+            ArcField(_) => quote! { ::std::sync::Arc::clone(#var) },
+            Bound(ref inner) => {
+                let tokens = inner.borrow_or_clone(&quote! { val });
+
+                quote! {
+                    match *#var {
+                        ::std::ops::Bound::Included(ref val) => ::std::ops::Bound::Included(#tokens),
+                        ::std::ops::Bound::Excluded(ref val) => ::std::ops::Bound::Excluded(#tokens),
+                        ::std::ops::Bound::Unbounded => ::std::ops::Bound::Unbounded,
+                    }
+                }
+            }
+            OnceCell(ref inner) => {
+                let tokens = inner.borrow_or_clone(&quote! { val });
+
+                quote! {
+                    match #var.get() {
+                        ::std::option::Option::Some(val) => ::std::convert::From::from(#tokens),
+                        ::std::option::Option::None => ::std::default::Default::default(),
+                    }
+                }
+            }
+            DashMap(..) => quote! {
+                #var.iter()
+                    .map(|entry| (entry.key().clone(), entry.value().clone()))
+                    .collect()
+            },
+            TupleField(ref elems) => {
+                let tokens = elems
+                    .iter()
+                    .enumerate()
+                    .map(|(index, kind)| kind.borrow_or_clone(&tuple_element(var, index)));
+
+                quote! { (#(#tokens,)*) }
+            }
+            BumpVec(_) | BumpString | Reference | JustMoved => quote! { #var.clone() },
+        }
+    }
+
+    /// The type of the field in the owned mirror type, given the original field type `ty`.
+    pub fn mirror_type(&self, ty: &syn::Type, lifetimes: &[syn::Lifetime]) -> syn::Type {
+        self.mirror_type_with(ty, lifetimes, MirrorLeaves::default())
+    }
+
+    /// Like [`FieldKind::mirror_type`] but with the string and slice leaves replaced as chosen
+    /// with `leaves`, for converting with [`Strategy::MirrorInto`].
+    pub fn mirror_type_with(
+        &self,
+        ty: &syn::Type,
+        lifetimes: &[syn::Lifetime],
+        leaves: MirrorLeaves,
+    ) -> syn::Type {
+        use self::FieldKind::*;
+
+        let ty = ungroup(ty);
+        let mirror_type =
+            |kind: &FieldKind, ty: &syn::Type| kind.mirror_type_with(ty, lifetimes, leaves);
+
+        match *self {
+            PlainCow | StrCow => match last_type_argument(ty) {
+                Some(borrowed) => owned_type_of(&borrowed, lifetimes, leaves),
+                None => ty.clone(),
+            },
+            Reference => match *ty {
+                syn::Type::Reference(ref reference) => {
+                    owned_type_of(&reference.elem, lifetimes, leaves)
+                }
+                _ => ty.clone(),
+            },
+            BumpVec(ref inner) => match last_type_argument(ty) {
+                Some(elem) => {
+                    let elem = mirror_type(inner, &elem);
+                    syn::parse_quote! { ::std::vec::Vec<#elem> }
+                }
+                None => ty.clone(),
+            },
+            BumpString if leaves.arc => syn::parse_quote! { ::std::sync::Arc<str> },
+            BumpString => syn::parse_quote! { ::std::string::String },
+            OptField(levels, ref inner) => map_type_arguments(ty, |ty| {
+                if levels > 1 {
+                    mirror_type(&OptField(levels - 1, inner.clone()), ty)
+                } else {
+                    mirror_type(inner, ty)
+                }
+            }),
+            IterableField(ref inner)
+            | Boxed(ref inner)
+            | RcField(ref inner)
+            | ArcField(ref inner)
+            | Pinned(ref inner)
+            | Bound(ref inner)
+            | OnceCell(ref inner)
+            | Wrapper(_, ref inner) => map_type_arguments(ty, |ty| mirror_type(inner, ty)),
+            // the hasher of the hashed collections is kept as it is
+            SetField(ref inner) => {
+                let mut kinds = vec![inner].into_iter();
+                map_type_arguments(ty, |ty| match kinds.next() {
+                    Some(kind) => mirror_type(kind, ty),
+                    None => ty.clone(),
+                })
+            }
+            MapField(ref key, ref value) | DashMap(ref key, ref value) => {
+                let mut kinds = vec![key, value].into_iter();
+                map_type_arguments(ty, |ty| match kinds.next() {
+                    Some(kind) => mirror_type(kind, ty),
+                    None => ty.clone(),
+                })
+            }
+            TupleField(ref elems) => match *ty {
+                syn::Type::Tuple(ref tuple) => {
+                    let mut tuple = tuple.clone();
+                    for (ty, kind) in tuple.elems.iter_mut().zip(elems) {
+                        *ty = mirror_type(kind, ty);
+                    }
+                    syn::Type::Tuple(tuple)
+                }
+                _ => ty.clone(),
+            },
+            AssumedCow | Known(_) | ZeroVec | VarZeroVec | JustMoved => {
+                let mut ty = ty.clone();
+                StaticLifetimes(lifetimes).visit_type_mut(&mut ty);
+                ty
+            }
+        }
+    }
+
+    /// Compares the field `this` with the same field `mirror` of the mirror type, both expected
+    /// to be references, given the original field type `ty`. The maps are compared entry by
+    /// entry, so this is quadratic in their sizes.
+    pub fn eq_mirror(
+        &self,
+        ty: &syn::Type,
+        lifetimes: &[syn::Lifetime],
+        this: &proc_macro2::TokenStream,
+        mirror: &proc_macro2::TokenStream,
+    ) -> proc_macro2::TokenStream {
+        use self::FieldKind::*;
+
+        let ty = ungroup(ty);
+        let arguments = type_arguments(ty);
+        let eq = |kind: &FieldKind, ty: Option<&syn::Type>, this, mirror| match ty {
+            Some(ty) => kind.eq_mirror(ty, lifetimes, &this, &mirror),
+            None => quote! { *#this == *#mirror },
+        };
+        // the owned leaves all borrow as the borrowed type, with the lifetimes of the mirror
+        let leaf = |borrowed: &syn::Type, this| {
+            let mut borrowed = borrowed.clone();
+            StaticLifetimes(lifetimes).visit_type_mut(&mut borrowed);
+            quote! { #this == ::std::borrow::Borrow::<#borrowed>::borrow(#mirror) }
+        };
+
+        match *self {
+            PlainCow | StrCow => match last_type_argument(ty) {
+                Some(borrowed) => leaf(&borrowed, quote! { &**#this }),
+                None => quote! { *#this == *#mirror },
+            },
+            Reference => match *ty {
+                syn::Type::Reference(ref reference) => leaf(&reference.elem, quote! { *#this }),
+                _ => quote! { *#this == *#mirror },
+            },
+            BumpString => quote! {
+                #this.as_str() == ::std::borrow::Borrow::<str>::borrow(#mirror)
+            },
+            OptField(levels, ref inner) => {
+                let tokens = if levels > 1 {
+                    let inner = OptField(levels - 1, inner.clone());
+                    eq(&inner, arguments.first(), quote! { x }, quote! { y })
+                } else {
+                    eq(inner, arguments.first(), quote! { x }, quote! { y })
+                };
+
+                // in parentheses as a `match` would end the statement, the comparisons of the
+                // fields are chained with `&&`
+                quote! {
+                    (match (#this, #mirror) {
+                        (::std::option::Option::Some(x), ::std::option::Option::Some(y)) => #tokens,
+                        (::std::option::Option::None, ::std::option::Option::None) => true,
+                        _ => false,
+                    })
+                }
+            }
+            IterableField(ref inner) | BumpVec(ref inner) => {
+                let tokens = eq(inner, arguments.last(), quote! { x }, quote! { y });
+
+                quote! {
+                    #this.len() == #mirror.len()
+                        && #this.iter().zip(#mirror.iter()).all(|(x, y)| #tokens)
+                }
+            }
+            SetField(ref inner) => {
+                let tokens = eq(inner, arguments.first(), quote! { x }, quote! { y });
+
+                quote! {
+                    #this.len() == #mirror.len()
+                        && #this.iter().all(|x| #mirror.iter().any(|y| #tokens))
+                }
+            }
+            MapField(ref key, ref value) => {
+                let keys = eq(key, arguments.first(), quote! { k }, quote! { l });
+                let values = eq(value, arguments.get(1), quote! { v }, quote! { w });
+
+                quote! {
+                    #this.len() == #mirror.len()
+                        && #this.iter().all(|(k, v)| #mirror.iter().any(|(l, w)| #keys && #values))
+                }
+            }
+            DashMap(ref key, ref value) => {
+                let keys = eq(
+                    key,
+                    arguments.first(),
+                    quote! { e.key() },
+                    quote! { f.key() },
+                );
+                let values = eq(
+                    value,
+                    arguments.get(1),
+                    quote! { e.value() },
+                    quote! { f.value() },
+                );
+
+                quote! {
+                    #this.len() == #mirror.len()
+                        && #this.iter().all(|e| #mirror.iter().any(|f| #keys && #values))
+                }
+            }
+            Boxed(ref inner) | RcField(ref inner) | ArcField(ref inner) | Wrapper(_, ref inner) => {
+                eq(
+                    inner,
+                    arguments.first(),
+                    quote! { (&**#this) },
+                    quote! { (&**#mirror) },
+                )
+            }
+            Pinned(ref inner) => eq(
+                inner,
+                arguments.first(),
+                quote! { #this },
+                quote! { #mirror },
+            ),
+            Bound(ref inner) => {
+                let tokens = eq(inner, arguments.first(), quote! { x }, quote! { y });
+
+                // in parentheses like the options
+                quote! {
+                    (match (#this, #mirror) {
+                        (::std::ops::Bound::Included(x), ::std::ops::Bound::Included(y))
+                        | (::std::ops::Bound::Excluded(x), ::std::ops::Bound::Excluded(y)) => #tokens,
+                        (::std::ops::Bound::Unbounded, ::std::ops::Bound::Unbounded) => true,
+                        _ => false,
+                    })
+                }
+            }
+            OnceCell(ref inner) => {
+                let tokens = eq(inner, arguments.first(), quote! { x }, quote! { y });
+
+                quote! {
+                    (match (#this.get(), #mirror.get()) {
+                        (::std::option::Option::Some(x), ::std::option::Option::Some(y)) => #tokens,
+                        (::std::option::Option::None, ::std::option::Option::None) => true,
+                        _ => false,
+                    })
+                }
+            }
+            TupleField(ref elems) => {
+                let types = contained_types(ty);
+                let tokens = elems.iter().enumerate().map(|(index, kind)| {
+                    eq(
+                        kind,
+                        types.get(index),
+                        tuple_element(this, index),
+                        tuple_element(mirror, index),
+                    )
+                });
+
+                quote! { (#(#tokens)&&*) }
+            }
+            AssumedCow | Known(_) | ZeroVec | VarZeroVec | JustMoved => {
+                quote! { *#this == *#mirror }
+            }
+        }
+    }
+}
+
+/// The classification in the form used by `#[into_owned(expect = "...")]`, like
+/// `option(iterable(cow))`.
+impl std::fmt::Display for FieldKind {
+    fn fmt(&self, fmt: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        use self::FieldKind::*;
+
+        match *self {
+            PlainCow | StrCow => write!(fmt, "cow"),
+            AssumedCow => write!(fmt, "cow_alike"),
+            Known(_) => write!(fmt, "known"),
+            Wrapper(_, ref inner) => write!(fmt, "wrapper({})", inner),
+            ZeroVec => write!(fmt, "zerovec"),
+            VarZeroVec => write!(fmt, "varzerovec"),
+            OptField(levels, ref inner) => {
+                for _ in 0..levels {
+                    write!(fmt, "option(")?;
+                }
+                write!(fmt, "{}", inner)?;
+                for _ in 0..levels {
+                    write!(fmt, ")")?;
+                }
+                Ok(())
+            }
+            IterableField(ref inner) => write!(fmt, "iterable({})", inner),
+            SetField(ref inner) => write!(fmt, "set({})", inner),
+            MapField(ref key, ref value) => write!(fmt, "map({}, {})", key, value),
+            DashMap(ref key, ref value) => write!(fmt, "dashmap({}, {})", key, value),
+            Boxed(ref inner) => write!(fmt, "box({})", inner),
+            RcField(ref inner) => write!(fmt, "rc({})", inner),
+            ArcField(ref inner) => write!(fmt, "arc({})", inner),
+            Pinned(ref inner) => write!(fmt, "pin({})", inner),
+            Bound(ref inner) => write!(fmt, "bound({})", inner),
+            OnceCell(ref inner) => write!(fmt, "once_cell({})", inner),
+            TupleField(ref elems) => {
+                write!(fmt, "tuple(")?;
+                for (index, elem) in elems.iter().enumerate() {
+                    if index > 0 {
+                        write!(fmt, ", ")?;
+                    }
+                    write!(fmt, "{}", elem)?;
+                }
+                write!(fmt, ")")
+            }
+            Reference => write!(fmt, "reference"),
+            BumpVec(ref inner) => write!(fmt, "bump_vec({})", inner),
+            BumpString => write!(fmt, "bump_string"),
+            JustMoved => write!(fmt, "moved"),
+        }
+    }
+}
+
+/// The type arguments of `ty`, or the elements if it is a tuple.
+fn contained_types(ty: &syn::Type) -> Vec<syn::Type> {
+    match *ungroup(ty) {
+        syn::Type::Tuple(ref tuple) => tuple.elems.iter().cloned().collect(),
+        ref ty => type_arguments(ty),
+    }
+}
+
+/// The names the elements of a tuple are bound to while walking it by value.
+fn tuple_names(len: usize) -> Vec<syn::Ident> {
+    (0..len).map(|index| format_ident!("t{}", index)).collect()
+}
+
+/// A reference to the element at `index` of the tuple `var` refers to.
+fn tuple_element(var: &proc_macro2::TokenStream, index: usize) -> proc_macro2::TokenStream {
+    let index = syn::Index::from(index);
+    quote! { (&#var.#index) }
+}
+
+/// Leaks the `owned` value, returning a `&'static` reference to its borrowed form.
+fn leak(owned: &proc_macro2::TokenStream) -> proc_macro2::TokenStream {
+    quote! {
+        ::std::borrow::Borrow::borrow(&*::std::boxed::Box::leak(::std::boxed::Box::new(#owned)))
+    }
+}
+
+/// Takes the value out of the `Rc` or `Arc` given as the `pointer` type, cloning it when it is
+/// shared.
+fn unwrap_or_clone(
+    pointer: &proc_macro2::TokenStream,
+    var: &proc_macro2::TokenStream,
+) -> proc_macro2::TokenStream {
+    // `Rc::unwrap_or_clone` needs Rust 1.76
+    quote! {
+        #pointer::try_unwrap(#var).unwrap_or_else(|shared| ::std::clone::Clone::clone(&*shared))
+    }
+}
+
+/// The owned type for a `Cow<'a, T>` or `&'a T` given `T`, with the string and slice leaves
+/// replaced as chosen with `leaves`.
+fn owned_type_of(
+    borrowed: &syn::Type,
+    lifetimes: &[syn::Lifetime],
+    leaves: MirrorLeaves,
+) -> syn::Type {
+    let mut borrowed = ungroup(borrowed).clone();
+    StaticLifetimes(lifetimes).visit_type_mut(&mut borrowed);
+
+    match borrowed {
+        syn::Type::Slice(syn::TypeSlice { ref elem, .. })
+            if leaves.bytes
+                && matches!(**elem, syn::Type::Path(ref p) if p.path.is_ident("u8")) =>
+        {
+            syn::parse_quote! { ::bytes::Bytes }
+        }
+        syn::Type::Path(ref p) if leaves.arc && p.qself.is_none() && p.path.is_ident("str") => {
+            syn::parse_quote! { ::std::sync::Arc<str> }
+        }
+        syn::Type::Slice(_) if leaves.arc => syn::parse_quote! { ::std::sync::Arc<#borrowed> },
+        syn::Type::Path(ref p) if p.qself.is_none() && p.path.is_ident("str") => {
+            syn::parse_quote! { ::std::string::String }
+        }
+        syn::Type::Slice(syn::TypeSlice { ref elem, .. }) => {
+            syn::parse_quote! { ::std::vec::Vec<#elem> }
+        }
+        // spelled out for the C APIs the mirror types are passed to
+        syn::Type::Path(ref p) if p.qself.is_none() && is_c_str(&collect_segments(&p.path)) => {
+            syn::parse_quote! { ::std::ffi::CString }
+        }
+        _ => syn::parse_quote! { <#borrowed as ::std::borrow::ToOwned>::Owned },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Classifier, FieldKind, KnownType, MirrorLeaves};
+    use quote::{format_ident, quote};
+
+    fn resolve(ty: &str) -> FieldKind {
+        resolve_with(ty, &Classifier::default())
+    }
+
+    fn resolve_with(ty: &str, classifier: &Classifier) -> FieldKind {
+        FieldKind::resolve(&syn::parse_str(ty).unwrap(), classifier)
+    }
+
+    fn mirror_type(ty: &str) -> String {
+        let ty = syn::parse_str(ty).unwrap();
+        let lifetime = syn::Lifetime::new("'a", proc_macro2::Span::call_site());
+        let mirror = FieldKind::resolve(&ty, &Classifier::default()).mirror_type(&ty, &[lifetime]);
+        quote!(#mirror).to_string()
+    }
+
+    fn mirror_type_with(ty: &str, leaves: MirrorLeaves) -> String {
+        let ty = syn::parse_str(ty).unwrap();
+        let lifetime = syn::Lifetime::new("'a", proc_macro2::Span::call_site());
+        let mirror = FieldKind::resolve(&ty, &Classifier::default()).mirror_type_with(
+            &ty,
+            &[lifetime],
+            leaves,
+        );
+        quote!(#mirror).to_string()
+    }
+
+    #[test]
+    fn cows() {
+        assert_eq!(resolve("Cow<'a, str>"), FieldKind::StrCow);
+        assert_eq!(resolve("std::borrow::Cow<'a, [u8]>"), FieldKind::PlainCow);
+        assert_eq!(resolve("::std::borrow::Cow<'a, Path>"), FieldKind::PlainCow);
+        assert_eq!(resolve("Foo<'a>"), FieldKind::AssumedCow);
+    }
+
+    #[test]
+    fn just_moved() {
+        assert_eq!(resolve("u32"), FieldKind::JustMoved);
+        assert_eq!(resolve("String"), FieldKind::JustMoved);
+        assert_eq!(resolve("Vec<u8>"), FieldKind::JustMoved);
+        assert_eq!(resolve("Option<Box<u8>>"), FieldKind::JustMoved);
+        assert_eq!(resolve("HashMap<String, u32>"), FieldKind::JustMoved);
+        assert_eq!(resolve("&'static str"), FieldKind::JustMoved);
+        assert_eq!(resolve("&'a mut str"), FieldKind::JustMoved);
+    }
+
+    #[test]
+    fn containers() {
+        assert_eq!(
+            resolve("Option<Option<Cow<'a, str>>>"),
+            FieldKind::OptField(2, Box::new(FieldKind::StrCow))
+        );
+        assert_eq!(
+            resolve("Vec<Vec<Foo<'a>>>"),
+            FieldKind::IterableField(Box::new(FieldKind::IterableField(Box::new(
+                FieldKind::AssumedCow
+            ))))
+        );
+        assert_eq!(
+            resolve("Box<Cow<'a, str>>"),
+            FieldKind::Boxed(Box::new(FieldKind::StrCow))
+        );
+        assert_eq!(
+            resolve("std::boxed::Box<Cow<'a, [u8]>>"),
+            FieldKind::Boxed(Box::new(FieldKind::PlainCow))
+        );
+        assert_eq!(
+            resolve("Rc<Foo<'a>>"),
+            FieldKind::JustMoved,
+            "only with `unwrap_or_clone`"
+        );
+        assert_eq!(
+            resolve("Option<Box<Foo<'a>>>"),
+            FieldKind::OptField(
+                1,
+                Box::new(FieldKind::Boxed(Box::new(FieldKind::AssumedCow)))
+            )
+        );
+        assert_eq!(
+            resolve("HashMap<Cow<'a, str>, u32>"),
+            FieldKind::MapField(Box::new(FieldKind::StrCow), Box::new(FieldKind::JustMoved))
+        );
+        assert_eq!(
+            resolve("HashMap<u32, Cow<'a, str>, FxBuildHasher>"),
+            FieldKind::MapField(Box::new(FieldKind::JustMoved), Box::new(FieldKind::StrCow))
+        );
+        assert_eq!(
+            resolve("HashSet<Foo<'a>, ahash::RandomState>"),
+            FieldKind::SetField(Box::new(FieldKind::AssumedCow))
+        );
+        assert_eq!(
+            resolve("BTreeMap<u32, Cow<'a, str>, Extra>"),
+            FieldKind::JustMoved
+        );
+        assert_eq!(
+            resolve("HashSet<Cow<'a, str>>"),
+            FieldKind::SetField(Box::new(FieldKind::StrCow))
+        );
+        assert_eq!(
+            resolve("std::collections::BTreeSet<Option<Foo<'a>>>").to_string(),
+            "set(option(cow_alike))"
+        );
+        assert_eq!(
+            resolve("BinaryHeap<Cow<'a, str>>"),
+            FieldKind::SetField(Box::new(FieldKind::StrCow))
+        );
+        assert_eq!(
+            resolve("std::collections::BTreeMap<Cow<'a, str>, Vec<Cow<'a, [u8]>>>").to_string(),
+            "map(cow, iterable(cow))"
+        );
+        for ty in [
+            "OnceCell<Cow<'a, str>>",
+            "once_cell::sync::OnceCell<Cow<'a, str>>",
+            "std::sync::OnceLock<Cow<'a, str>>",
+        ] {
+            assert_eq!(
+                resolve(ty),
+                FieldKind::OnceCell(Box::new(FieldKind::StrCow)),
+                "{}",
+                ty
+            );
+        }
+        assert_eq!(resolve("OnceCell<String>"), FieldKind::JustMoved);
+        for ty in ["Bound<Cow<'a, str>>", "std::ops::Bound<Cow<'a, str>>"] {
+            assert_eq!(
+                resolve(ty),
+                FieldKind::Bound(Box::new(FieldKind::StrCow)),
+                "{}",
+                ty
+            );
+        }
+        assert_eq!(resolve("Bound<u64>"), FieldKind::JustMoved);
+    }
+
+    #[test]
+    fn groups() {
+        // `$ty:ty` fragments of `macro_rules!` arrive wrapped in invisible groups
+        let cow: proc_macro2::TokenStream = "Cow<'a, str>".parse().unwrap();
+        let group = proc_macro2::Group::new(proc_macro2::Delimiter::None, cow);
+        let ty: syn::Type = syn::parse2(quote! { Vec<#group> }).unwrap();
+
+        assert_eq!(
+            FieldKind::resolve(&ty, &Classifier::default()),
+            FieldKind::IterableField(Box::new(FieldKind::StrCow))
+        );
+        assert_eq!(resolve("(Cow<'a, [u8]>)"), FieldKind::PlainCow);
+    }
+
+    #[test]
+    fn references() {
+        assert_eq!(resolve("&'a str"), FieldKind::Reference);
+        assert!(resolve("Vec<&'a [u8]>").has_references());
+        assert!(!resolve("Vec<Cow<'a, [u8]>>").has_references());
+    }
+
+    #[test]
+    fn display() {
+        for (ty, expected) in [
+            ("Cow<'a, str>", "cow"),
+            (
+                "Option<Option<Vec<Foo<'a>>>>",
+                "option(option(iterable(cow_alike)))",
+            ),
+            (
+                "HashMap<Cow<'a, str>, Box<Bar<'a>>>",
+                "map(cow, box(cow_alike))",
+            ),
+            ("Bound<Cow<'a, str>>", "bound(cow)"),
+            ("Vec<(String, Cow<'a, str>)>", "iterable(tuple(moved, cow))"),
+            ("(u32, (&'a str,))", "tuple(moved, tuple(reference))"),
+            ("(u32, String)", "moved"),
+            ("u32", "moved"),
+        ] {
+            assert_eq!(resolve(ty).to_string(), expected);
+        }
+    }
+
+    #[test]
+    fn fallbacks() {
+        let fallback = |ty: &str| {
+            FieldKind::find_fallback(&syn::parse_str(ty).unwrap(), &Classifier::default())
+                .map(|ty| quote!(#ty).to_string())
+        };
+
+        assert_eq!(fallback("u32"), None);
+        assert_eq!(fallback("Vec<Cow<'a, str>>"), None);
+        assert_eq!(fallback("Cell<&'static str>"), None);
+        assert_eq!(
+            fallback("Cell<&'a str>"),
+            Some(String::from("Cell < & 'a str >"))
+        );
+        assert_eq!(
+            fallback("Option<Vec<Cell<&str>>>"),
+            Some(String::from("Cell < & str >"))
+        );
+        assert_eq!(fallback("Vec<&'a str>"), Some(String::from("& 'a str")));
+        assert_eq!(
+            fallback("Vec<(u8, Cell<&'a str>)>"),
+            Some(String::from("Cell < & 'a str >"))
+        );
+    }
+
+    #[test]
+    fn cloned_types() {
+        let cloned = |ty: &str| {
+            FieldKind::cloned_types(&syn::parse_str(ty).unwrap(), &Classifier::default())
+                .iter()
+                .map(|ty| quote!(#ty).to_string())
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(cloned("T"), ["T"]);
+        assert_eq!(cloned("Vec<T>"), ["Vec < T >"]);
+        assert!(cloned("Cow<'a, T>").is_empty());
+        assert!(cloned("&'a T").is_empty());
+        assert_eq!(cloned("Option<Box<T>>"), ["Option < Box < T > >"]);
+        assert_eq!(
+            cloned("Option<Vec<HashMap<Cow<'a, str>, Box<T>>>>"),
+            ["Box < T >"]
+        );
+        assert_eq!(cloned("HashMap<Cow<'a, str>, T>"), ["T"]);
+        assert_eq!(cloned("Vec<(T, Cow<'a, str>)>"), ["T"]);
+    }
+
+    #[test]
+    fn cow_alike_types() {
+        let cow_alike = |ty: &str| {
+            FieldKind::cow_alike_types(&syn::parse_str(ty).unwrap(), &Classifier::default())
+                .iter()
+                .map(|ty| quote!(#ty).to_string())
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(cow_alike("Foo<'a>"), ["Foo < 'a >"]);
+        assert_eq!(cow_alike("Option<Vec<Box<Foo<'a>>>>"), ["Foo < 'a >"]);
+        assert_eq!(cow_alike("HashMap<Cow<'a, str>, Bar<'a>>"), ["Bar < 'a >"]);
+        assert!(cow_alike("Cow<'a, str>").is_empty());
+        assert!(cow_alike("Vec<T>").is_empty());
+        assert_eq!(cow_alike("Option<(u8, Foo<'a>)>"), ["Foo < 'a >"]);
+    }
+
+    #[test]
+    fn nesting() {
+        assert_eq!(resolve("Cow<'a, str>").nesting(), 0);
+        assert_eq!(resolve("Option<Option<Cow<'a, str>>>").nesting(), 2);
+        assert_eq!(resolve("Vec<Box<Foo<'a>>>").nesting(), 2);
+        assert_eq!(
+            resolve("HashMap<Cow<'a, str>, Vec<Cow<'a, str>>>").nesting(),
+            2
+        );
+    }
+
+    #[test]
+    fn wrappers() {
+        let classifier = Classifier {
+            wrappers: vec![KnownType {
+                path: syn::parse_str("ast::Spanned").unwrap(),
+                method: format_ident!("map"),
+            }],
+            ..Classifier::default()
+        };
+
+        assert_eq!(
+            resolve_with("Spanned<Cow<'a, str>>", &classifier),
+            FieldKind::Wrapper(format_ident!("map"), Box::new(FieldKind::StrCow))
+        );
+        assert_eq!(
+            resolve_with("Vec<ast::Spanned<Option<Foo<'a>>>>", &classifier).to_string(),
+            "iterable(wrapper(option(cow_alike)))"
+        );
+        assert_eq!(
+            resolve_with("Spanned<u32>", &classifier),
+            FieldKind::JustMoved
+        );
+    }
+
+    #[test]
+    fn pinned_boxes() {
+        assert_eq!(
+            resolve("Pin<Box<Foo<'a>>>"),
+            FieldKind::Pinned(Box::new(FieldKind::Boxed(Box::new(FieldKind::AssumedCow))))
+        );
+        assert_eq!(
+            resolve("Option<std::pin::Pin<Box<Cow<'a, str>>>>").to_string(),
+            "option(pin(box(cow)))"
+        );
+        assert_eq!(resolve("Pin<&'a mut Foo<'a>>"), FieldKind::JustMoved);
+        assert_eq!(resolve("Pin<Box<u8>>"), FieldKind::JustMoved);
+    }
+
+    #[test]
+    fn unwrap_or_clone() {
+        let classifier = Classifier {
+            unwrap_or_clone: true,
+            ..Classifier::default()
+        };
+
+        assert_eq!(
+            resolve_with("Rc<Foo<'a>>", &classifier),
+            FieldKind::RcField(Box::new(FieldKind::AssumedCow))
+        );
+        assert_eq!(
+            resolve_with("Vec<std::rc::Rc<Cow<'a, str>>>", &classifier).to_string(),
+            "iterable(rc(cow))"
+        );
+        assert_eq!(
+            resolve_with("Option<std::sync::Arc<Foo<'a>>>", &classifier).to_string(),
+            "option(arc(cow_alike))"
+        );
+        assert_eq!(resolve_with("Rc<str>", &classifier), FieldKind::JustMoved);
+        assert_eq!(
+            resolve_with("Arc<dyn Visitor<'a> + 'a>", &classifier),
+            FieldKind::JustMoved
+        );
+    }
+
+    #[test]
+    fn known_types_take_precedence() {
+        let classifier = Classifier {
+            known: vec![KnownType {
+                path: syn::parse_str("other::Cow").unwrap(),
+                method: format_ident!("into_static"),
+            }],
+            ..Classifier::default()
+        };
+
+        assert_eq!(
+            resolve_with("Cow<'a, str>", &classifier),
+            FieldKind::Known(format_ident!("into_static"))
+        );
+        assert_eq!(
+            resolve_with("Option<other::Cow<'a, str>>", &classifier),
+            FieldKind::OptField(1, Box::new(FieldKind::Known(format_ident!("into_static"))))
+        );
+        assert_eq!(
+            resolve_with("unrelated::Cow<'a, str>", &classifier),
+            FieldKind::AssumedCow
+        );
+    }
+
+    #[test]
+    fn ownership_checks() {
+        let var = quote! { field };
+
+        assert!(resolve("Vec<Option<Cow<'a, str>>>")
+            .is_owned(&var)
+            .is_some());
+        assert_eq!(resolve("u32").is_owned(&var).unwrap().to_string(), "true");
+        assert!(resolve("Foo<'a>").is_owned(&var).is_none());
+        assert!(resolve("Vec<&'a str>").is_owned(&var).is_none());
+    }
+
+    #[test]
+    fn borrowed_strs() {
+        let (var, out) = (quote! { field }, quote! { out });
+
+        for ty in ["Cow<'a, str>", "HashMap<Cow<'a, str>, u32>", "Box<Foo<'a>>"] {
+            assert!(
+                resolve(ty).push_borrowed_strs(&var, &out).is_some(),
+                "{}",
+                ty
+            );
+        }
+        for ty in ["u32", "Cow<'a, [u8]>", "Vec<&'a str>"] {
+            assert!(
+                resolve(ty).push_borrowed_strs(&var, &out).is_none(),
+                "{}",
+                ty
+            );
+        }
+    }
+
+    #[test]
+    fn mirror_types() {
+        for (ty, expected) in [
+            ("Cow<'a, str>", "::std::string::String"),
+            ("Option<Cow<'a, [u8]>>", "Option<::std::vec::Vec<u8>>"),
+            ("Foo<'a>", "Foo<'static>"),
+            ("&'a str", "::std::string::String"),
+            ("Cow<'a, CStr>", "::std::ffi::CString"),
+            ("&'a std::ffi::CStr", "::std::ffi::CString"),
+            ("Bound<Cow<'a, str>>", "Bound<::std::string::String>"),
+            (
+                "Vec<(String, Cow<'a, str>)>",
+                "Vec<(String, ::std::string::String)>",
+            ),
+        ] {
+            let expected: syn::Type = syn::parse_str(expected).unwrap();
+            assert_eq!(mirror_type(ty), quote!(#expected).to_string(), "{}", ty);
+        }
+    }
+
+    #[test]
+    fn hashers_are_kept() {
+        assert_eq!(
+            mirror_type("HashMap<Cow<'a, str>, u32, FxBuildHasher>"),
+            "HashMap < :: std :: string :: String , u32 , FxBuildHasher >"
+        );
+        assert_eq!(
+            mirror_type("HashSet<Cow<'a, str>, FxBuildHasher>"),
+            "HashSet < :: std :: string :: String , FxBuildHasher >"
+        );
+    }
+
+    #[test]
+    fn arc_mirror_types() {
+        for (ty, expected) in [
+            ("Cow<'a, str>", "::std::sync::Arc<str>"),
+            ("Option<Cow<'a, [u8]>>", "Option<::std::sync::Arc<[u8]>>"),
+            ("Vec<&'a str>", "Vec<::std::sync::Arc<str>>"),
+            ("Cow<'a, Path>", "<Path as ::std::borrow::ToOwned>::Owned"),
+            ("Foo<'a>", "Foo<'static>"),
+        ] {
+            let expected: syn::Type = syn::parse_str(expected).unwrap();
+            let leaves = MirrorLeaves {
+                arc: true,
+                ..MirrorLeaves::default()
+            };
+            assert_eq!(
+                mirror_type_with(ty, leaves),
+                quote!(#expected).to_string(),
+                "{}",
+                ty
+            );
+        }
+    }
+
+    #[test]
+    fn bytes_mirror_types() {
+        for (ty, arc, expected) in [
+            ("Cow<'a, [u8]>", false, "::bytes::Bytes"),
+            ("Option<&'a [u8]>", true, "Option<::bytes::Bytes>"),
+            ("Cow<'a, str>", false, "::std::string::String"),
+            ("Cow<'a, str>", true, "::std::sync::Arc<str>"),
+            ("Cow<'a, [u16]>", false, "::std::vec::Vec<u16>"),
+        ] {
+            let expected: syn::Type = syn::parse_str(expected).unwrap();
+            let leaves = MirrorLeaves { arc, bytes: true };
+            assert_eq!(
+                mirror_type_with(ty, leaves),
+                quote!(#expected).to_string(),
+                "{}",
+                ty
+            );
+        }
+    }
+}
@@ -0,0 +1,137 @@
+//! The [`BodyGenerator`] abstraction shared by the `IntoOwned` and `Borrowed` derives.
+
+use quote::quote;
+
+use crate::helpers::generic_arguments;
+
+/// Generates a method body for each shape of type, which [`impl_with_generator`] then puts
+/// together into an `impl` block.
+///
+/// The type parameter lists default to the ones needed for `impl<'a, T> Foo<'a, T>` returning
+/// `Foo<'static, T>`, override them to return something else.
+pub trait BodyGenerator {
+    /// The parameters of the `impl<...>`, by default all the parameters with their bounds but
+    /// without their defaults, like `ImplGenerics` of [`syn::Generics::split_for_impl`].
+    fn quote_borrowed_params(&self, ast: &syn::DeriveInput) -> Vec<proc_macro2::TokenStream> {
+        ast.generics
+            .params
+            .iter()
+            .map(|param| {
+                let mut param = param.clone();
+                match param {
+                    syn::GenericParam::Type(ref mut ty) => {
+                        ty.eq_token = None;
+                        ty.default = None;
+                    }
+                    syn::GenericParam::Const(ref mut constant) => {
+                        constant.eq_token = None;
+                        constant.default = None;
+                    }
+                    syn::GenericParam::Lifetime(_) => {}
+                }
+                quote! { #param }
+            })
+            .collect()
+    }
+
+    /// The parameters of the type the `impl` is for, by default all the parameters.
+    fn quote_type_params(&self, ast: &syn::DeriveInput) -> Vec<proc_macro2::TokenStream> {
+        generic_arguments(&ast.generics, None)
+    }
+
+    /// The parameters of the returned type, by default `'static` for every lifetime parameter.
+    fn quote_rhs_params(&self, ast: &syn::DeriveInput) -> Vec<proc_macro2::TokenStream> {
+        generic_arguments(&ast.generics, Some(&syn::parse_quote!('static)))
+    }
+
+    /// The fields of the value constructed from `self` for a struct, like `{ a: .. }` or
+    /// `( .. )`, without the name of the type.
+    fn visit_struct(&self, data: &syn::DataStruct) -> proc_macro2::TokenStream;
+
+    /// A match arm converting the enum `variant`, which is the path to the variant like
+    /// `Foo::Bar`.
+    fn visit_enum_data(
+        &self,
+        variant: proc_macro2::TokenStream,
+        data: &syn::Variant,
+    ) -> proc_macro2::TokenStream;
+
+    /// The body for an enum without any variants, by default `match self {}` which works when
+    /// `self` is taken by value. Methods taking `&self` need `match *self {}` instead.
+    fn visit_empty_enum(&self) -> proc_macro2::TokenStream {
+        quote! { match self {} }
+    }
+
+    /// The body for a union. Unions are only supported with `Copy` and `'static` fields, so the
+    /// value needs no conversion and is by default returned as `self`. Methods taking `&self`
+    /// need `*self` instead.
+    fn visit_union(&self, _data: &syn::DataUnion) -> proc_macro2::TokenStream {
+        quote! { self }
+    }
+
+    /// Puts the generated `body` into the final `impl #borrows #name #rhs_params #where_clause`
+    /// block, where `owned` is the parameter list of the returned type. `where_clause` is the
+    /// `where` clause of the type, or empty.
+    fn combine_impl(
+        &self,
+        borrows: proc_macro2::TokenStream,
+        name: &syn::Ident,
+        rhs_params: proc_macro2::TokenStream,
+        where_clause: proc_macro2::TokenStream,
+        owned: proc_macro2::TokenStream,
+        body: proc_macro2::TokenStream,
+    ) -> proc_macro2::TokenStream;
+}
+
+/// Generates the `impl` block for the type in `ast` using `gen`.
+pub fn impl_with_generator<G: BodyGenerator>(
+    ast: &syn::DeriveInput,
+    gen: G,
+) -> proc_macro2::TokenStream {
+    // this is based heavily on https://github.com/asajeffrey/deep-clone/blob/master/deep-clone-derive/lib.rs
+    let name = &ast.ident;
+
+    let borrowed_params = gen.quote_borrowed_params(ast);
+    let borrowed = if borrowed_params.is_empty() {
+        quote! {}
+    } else {
+        quote! { < #(#borrowed_params),* > }
+    };
+
+    let params = gen.quote_type_params(ast);
+    let params = if params.is_empty() {
+        quote! {}
+    } else {
+        quote! { < #(#params),* > }
+    };
+
+    let owned_params = gen.quote_rhs_params(ast);
+    let owned = if owned_params.is_empty() {
+        quote! {}
+    } else {
+        quote! { < #(#owned_params),* > }
+    };
+
+    let where_clause = &ast.generics.where_clause;
+    let where_clause = quote! { #where_clause };
+
+    let body = match ast.data {
+        syn::Data::Struct(ref body) => {
+            let inner = gen.visit_struct(body);
+            quote! { #name #inner }
+        }
+        syn::Data::Enum(ref body) if body.variants.is_empty() => gen.visit_empty_enum(),
+        syn::Data::Enum(ref body) => {
+            let cases = body.variants.iter().map(|variant| {
+                let unqualified_ident = &variant.ident;
+                let ident = quote! { #name::#unqualified_ident };
+
+                gen.visit_enum_data(ident, variant)
+            });
+            quote! { match self { #(#cases),* } }
+        }
+        syn::Data::Union(ref body) => gen.visit_union(body),
+    };
+
+    gen.combine_impl(borrowed, name, params, where_clause, owned, body)
+}
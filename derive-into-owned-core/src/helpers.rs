@@ -0,0 +1,1054 @@
+//! Matchers for the paths of field types and other helpers for working with `syn` types.
+
+pub fn has_lifetime_arguments(segments: &[syn::PathSegment]) -> bool {
+    if let Some(syn::PathArguments::AngleBracketed(generics)) =
+        segments.last().map(|x| &x.arguments)
+    {
+        generics
+            .args
+            .iter()
+            .any(|f| matches!(f, syn::GenericArgument::Lifetime(_)))
+    } else {
+        false
+    }
+}
+
+pub fn number_of_type_arguments(segments: &[syn::PathSegment]) -> usize {
+    if let Some(syn::PathArguments::AngleBracketed(generics)) =
+        segments.last().map(|x| &x.arguments)
+    {
+        generics
+            .args
+            .iter()
+            .filter(|f| matches!(f, syn::GenericArgument::Type(_)))
+            .count()
+    } else {
+        0
+    }
+}
+
+pub fn has_binding_arguments(segments: &[syn::PathSegment]) -> bool {
+    if let Some(syn::PathArguments::AngleBracketed(generics)) =
+        segments.last().map(|x| &x.arguments)
+    {
+        generics
+            .args
+            .iter()
+            .any(|f| matches!(f, syn::GenericArgument::Binding(_)))
+    } else {
+        false
+    }
+}
+
+fn type_hopefully_is(segments: &[syn::PathSegment], expected: &str) -> bool {
+    let expected = expected
+        .split("::")
+        .map(|x| quote::format_ident!("{}", x))
+        .collect::<Vec<_>>();
+
+    segments_hopefully_are(segments, &expected.iter().collect::<Vec<_>>())
+}
+
+/// True if the segments are a suffix of the expected path, as in `Span` for `my_crate::Span`.
+fn segments_hopefully_are(segments: &[syn::PathSegment], expected: &[&syn::Ident]) -> bool {
+    if segments.len() > expected.len() {
+        return false;
+    }
+
+    let segments = segments.iter().map(|x| &x.ident).collect::<Vec<_>>();
+
+    for len in 0..expected.len() {
+        if segments[..] == expected[expected.len() - len - 1..] {
+            return true;
+        }
+    }
+
+    false
+}
+
+/// True if the type is the `expected` type listed in `#[into_owned(known(...))]`.
+pub fn is_known(segments: &[syn::PathSegment], expected: &syn::Path) -> bool {
+    let expected = expected
+        .segments
+        .iter()
+        .map(|segment| &segment.ident)
+        .collect::<Vec<_>>();
+
+    segments_hopefully_are(segments, &expected)
+}
+
+pub fn is_cow(segments: &[syn::PathSegment]) -> bool {
+    type_hopefully_is(segments, "std::borrow::Cow")
+}
+
+pub fn is_cow_alike(segments: &[syn::PathSegment]) -> bool {
+    if let Some(syn::PathArguments::AngleBracketed(_data)) = segments.last().map(|x| &x.arguments) {
+        has_lifetime_arguments(segments)
+    } else {
+        false
+    }
+}
+
+/// `zerovec::ZeroVec<'a, T>`, only recognized with the `zerovec` feature.
+pub fn is_zerovec(segments: &[syn::PathSegment]) -> bool {
+    cfg!(feature = "zerovec")
+        && type_hopefully_is(segments, "zerovec::ZeroVec")
+        && has_lifetime_arguments(segments)
+}
+
+/// `zerovec::VarZeroVec<'a, T>`, only recognized with the `zerovec` feature.
+pub fn is_varzerovec(segments: &[syn::PathSegment]) -> bool {
+    cfg!(feature = "zerovec")
+        && type_hopefully_is(segments, "zerovec::VarZeroVec")
+        && has_lifetime_arguments(segments)
+}
+
+/// `bumpalo::collections::Vec<'bump, T>`, only recognized with the `bumpalo` feature.
+pub fn is_bump_vec(segments: &[syn::PathSegment]) -> Option<syn::Type> {
+    if !cfg!(feature = "bumpalo")
+        || !type_hopefully_is(segments, "bumpalo::collections::Vec")
+        || !has_lifetime_arguments(segments)
+        || number_of_type_arguments(segments) != 1
+    {
+        return None;
+    }
+
+    match segments.last().map(|x| &x.arguments) {
+        Some(syn::PathArguments::AngleBracketed(data)) => {
+            data.args.iter().find_map(|arg| match arg {
+                syn::GenericArgument::Type(ty) => Some(ty.clone()),
+                _ => None,
+            })
+        }
+        _ => None,
+    }
+}
+
+/// `bumpalo::collections::String<'bump>`, only recognized with the `bumpalo` feature.
+pub fn is_bump_string(segments: &[syn::PathSegment]) -> bool {
+    cfg!(feature = "bumpalo")
+        && type_hopefully_is(segments, "bumpalo::collections::String")
+        && has_lifetime_arguments(segments)
+}
+
+pub fn collect_segments(path: &syn::Path) -> Vec<syn::PathSegment> {
+    path.segments.iter().cloned().collect::<Vec<_>>()
+}
+
+/// Returns the type arguments of a path which looks like `expected` with exactly `count` type
+/// arguments.
+fn type_arguments_of(
+    segments: &[syn::PathSegment],
+    expected: &str,
+    count: usize,
+) -> Option<Vec<syn::Type>> {
+    if !type_hopefully_is(segments, expected) {
+        return None;
+    }
+
+    if has_lifetime_arguments(segments) || has_binding_arguments(segments) {
+        // don't know about data bindings, let the compiler complain
+        return None;
+    }
+
+    if number_of_type_arguments(segments) != count {
+        // Option<A, B> probably means some other, movable option
+        return None;
+    }
+
+    if let Some(syn::PathArguments::AngleBracketed(data)) = segments.last().map(|x| &x.arguments) {
+        let types = data
+            .args
+            .iter()
+            .filter_map(|arg| match arg {
+                syn::GenericArgument::Type(ty) => Some(ty.clone()),
+                _ => None,
+            })
+            .collect();
+        Some(types)
+    } else {
+        None
+    }
+}
+
+fn single_type_argument_of(segments: &[syn::PathSegment], expected: &str) -> Option<syn::Type> {
+    type_arguments_of(segments, expected, 1).and_then(|mut types| types.pop())
+}
+
+/// The type arguments of a hashed collection with `count` of them besides the hasher, like the
+/// `K` and `V` of `HashMap<K, V, S>`. The collections are rebuilt by collecting, so the hasher
+/// needs to implement `Default`.
+fn with_hasher(
+    segments: &[syn::PathSegment],
+    expected: &str,
+    count: usize,
+) -> Option<Vec<syn::Type>> {
+    type_arguments_of(segments, expected, count + 1).map(|mut types| {
+        types.truncate(count);
+        types
+    })
+}
+
+pub fn is_opt(segments: &[syn::PathSegment]) -> Option<syn::Type> {
+    single_type_argument_of(segments, "std::option::Option")
+}
+
+pub fn is_c_str(segments: &[syn::PathSegment]) -> bool {
+    type_hopefully_is(segments, "std::ffi::CStr") || type_hopefully_is(segments, "core::ffi::CStr")
+}
+
+pub fn is_string(segments: &[syn::PathSegment]) -> bool {
+    type_hopefully_is(segments, "std::string::String")
+        && segments
+            .last()
+            .map_or(false, |segment| segment.arguments.is_empty())
+}
+
+pub fn is_vec(segments: &[syn::PathSegment]) -> Option<syn::Type> {
+    // this should be easy to do for arrays as well..
+    single_type_argument_of(segments, "std::vec::Vec")
+}
+
+/// `HashSet<T>`, possibly with a hasher like `HashSet<T, S>`, `BTreeSet<T>` or `BinaryHeap<T>`.
+pub fn is_set(segments: &[syn::PathSegment]) -> Option<syn::Type> {
+    single_type_argument_of(segments, "std::collections::HashSet")
+        .or_else(|| single_type_argument_of(segments, "std::collections::BTreeSet"))
+        .or_else(|| single_type_argument_of(segments, "std::collections::BinaryHeap"))
+        .or_else(|| with_hasher(segments, "std::collections::HashSet", 1)?.pop())
+}
+
+pub fn is_box(segments: &[syn::PathSegment]) -> Option<syn::Type> {
+    single_type_argument_of(segments, "std::boxed::Box")
+}
+
+/// `Rc<T>` of `std::rc` or `alloc::rc`.
+pub fn is_rc(segments: &[syn::PathSegment]) -> Option<syn::Type> {
+    single_type_argument_of(segments, "std::rc::Rc")
+        .or_else(|| single_type_argument_of(segments, "alloc::rc::Rc"))
+}
+
+/// `Arc<T>` of `std::sync` or `alloc::sync`.
+pub fn is_arc(segments: &[syn::PathSegment]) -> Option<syn::Type> {
+    single_type_argument_of(segments, "std::sync::Arc")
+        .or_else(|| single_type_argument_of(segments, "alloc::sync::Arc"))
+}
+
+/// `Pin<P>` of `std::pin` or `core::pin`.
+pub fn is_pin(segments: &[syn::PathSegment]) -> Option<syn::Type> {
+    single_type_argument_of(segments, "std::pin::Pin")
+        .or_else(|| single_type_argument_of(segments, "core::pin::Pin"))
+}
+
+/// `Bound<T>` of `std::ops` or `core::ops`.
+pub fn is_bound(segments: &[syn::PathSegment]) -> Option<syn::Type> {
+    single_type_argument_of(segments, "std::ops::Bound")
+        .or_else(|| single_type_argument_of(segments, "core::ops::Bound"))
+}
+
+/// `OnceCell<T>` of `std` or `once_cell`, or `OnceLock<T>`.
+pub fn is_once_cell(segments: &[syn::PathSegment]) -> Option<syn::Type> {
+    [
+        "once_cell::unsync::OnceCell",
+        "once_cell::sync::OnceCell",
+        "std::cell::OnceCell",
+        "std::sync::OnceLock",
+    ]
+    .iter()
+    .find_map(|expected| single_type_argument_of(segments, expected))
+}
+
+/// `HashMap<K, V>`, possibly with a hasher like `HashMap<K, V, S>`, or `BTreeMap<K, V>`.
+pub fn is_map(segments: &[syn::PathSegment]) -> Option<(syn::Type, syn::Type)> {
+    // KVMap is the alias quick-protobuf generated code uses for HashMap
+    [
+        "std::collections::HashMap",
+        "std::collections::BTreeMap",
+        "KVMap",
+    ]
+    .iter()
+    .find_map(|expected| type_arguments_of(segments, expected, 2))
+    .or_else(|| with_hasher(segments, "std::collections::HashMap", 2))
+    .and_then(|types| match types.as_slice() {
+        [key, value] => Some((key.clone(), value.clone())),
+        _ => None,
+    })
+}
+
+/// `dashmap::DashMap<K, V>`, possibly with a hasher like `DashMap<K, V, S>`, only recognized
+/// with the `dashmap` feature.
+pub fn is_dashmap(segments: &[syn::PathSegment]) -> Option<(syn::Type, syn::Type)> {
+    if !cfg!(feature = "dashmap") {
+        return None;
+    }
+
+    type_arguments_of(segments, "dashmap::DashMap", 2)
+        .or_else(|| with_hasher(segments, "dashmap::DashMap", 2))
+        .and_then(|types| match types.as_slice() {
+            [key, value] => Some((key.clone(), value.clone())),
+            _ => None,
+        })
+}
+
+/// True if `ty` is a well known type which allocates on the heap, like `String` or `Vec<T>`, or
+/// an `Option` of such.
+pub fn is_heap_allocated(ty: &syn::Type) -> bool {
+    const ALLOCATING: &[&str] = &[
+        "std::string::String",
+        "std::vec::Vec",
+        "std::boxed::Box",
+        "std::collections::HashMap",
+        "std::collections::HashSet",
+        "std::collections::BTreeMap",
+        "std::collections::BTreeSet",
+        "std::collections::VecDeque",
+        "std::collections::BinaryHeap",
+        "std::collections::LinkedList",
+        "std::path::PathBuf",
+        "std::ffi::OsString",
+        "std::ffi::CString",
+        "dashmap::DashMap",
+    ];
+
+    match ungroup(ty) {
+        syn::Type::Path(syn::TypePath { ref path, .. }) => {
+            let segments = collect_segments(path);
+
+            match is_opt(&segments) {
+                Some(inner) => is_heap_allocated(&inner),
+                None => ALLOCATING
+                    .iter()
+                    .any(|expected| type_hopefully_is(&segments, expected)),
+            }
+        }
+        syn::Type::Array(ref array) => is_heap_allocated(&array.elem),
+        _ => false,
+    }
+}
+
+/// Looks through the invisible groups around types passed to `macro_rules!` macros as `$ty:ty`
+/// and through parentheses, like `(Cow<'a, str>)`.
+pub fn ungroup(mut ty: &syn::Type) -> &syn::Type {
+    loop {
+        match ty {
+            syn::Type::Group(syn::TypeGroup { ref elem, .. })
+            | syn::Type::Paren(syn::TypeParen { ref elem, .. }) => ty = elem,
+            _ => return ty,
+        }
+    }
+}
+
+/// Returns the type arguments of the path type `ty`, like `K` and `V` for `HashMap<K, V>`.
+pub fn type_arguments(ty: &syn::Type) -> Vec<syn::Type> {
+    if let syn::Type::Path(syn::TypePath { ref path, .. }) = ungroup(ty) {
+        if let Some(syn::PathArguments::AngleBracketed(data)) =
+            path.segments.last().map(|x| &x.arguments)
+        {
+            return data
+                .args
+                .iter()
+                .filter_map(|arg| match arg {
+                    syn::GenericArgument::Type(ty) => Some(ty.clone()),
+                    _ => None,
+                })
+                .collect();
+        }
+    }
+
+    Vec::new()
+}
+
+/// Returns the last type argument of the path type `ty`, like `T` for `Cow<'a, T>`.
+pub fn last_type_argument(ty: &syn::Type) -> Option<syn::Type> {
+    type_arguments(ty).pop()
+}
+
+/// True if `ty` mentions any lifetime other than `'static`, or is a reference with an elided one.
+/// The lifetimes of function pointers and `Fn` traits which are higher-ranked, like in
+/// `for<'x> fn(&'x str)` or `fn(&str) -> &str`, do not count, and neither do the ones of their
+/// arguments, as `fn(&'a str)` is also a `fn(&'static str)`.
+pub fn has_non_static_lifetimes(ty: &syn::Type) -> bool {
+    struct Lifetimes<'ast> {
+        higher_ranked: Vec<&'ast syn::Ident>,
+        in_fn: bool,
+        found: bool,
+    }
+
+    impl<'ast> Lifetimes<'ast> {
+        fn visit_fn_output(
+            &mut self,
+            bound: Option<&'ast syn::BoundLifetimes>,
+            output: &'ast syn::ReturnType,
+        ) {
+            let (len, in_fn) = (self.higher_ranked.len(), self.in_fn);
+            if let Some(bound) = bound {
+                let lifetimes = bound.lifetimes.iter().map(|def| &def.lifetime.ident);
+                self.higher_ranked.extend(lifetimes);
+            }
+            self.in_fn = true;
+            syn::visit::Visit::visit_return_type(self, output);
+            self.higher_ranked.truncate(len);
+            self.in_fn = in_fn;
+        }
+    }
+
+    impl<'ast> syn::visit::Visit<'ast> for Lifetimes<'ast> {
+        fn visit_lifetime(&mut self, lifetime: &'ast syn::Lifetime) {
+            self.found |=
+                lifetime.ident != "static" && !self.higher_ranked.contains(&&lifetime.ident);
+        }
+
+        fn visit_type_reference(&mut self, reference: &'ast syn::TypeReference) {
+            // elided in function signatures means higher-ranked
+            self.found |= reference.lifetime.is_none() && !self.in_fn;
+            syn::visit::visit_type_reference(self, reference);
+        }
+
+        fn visit_type_bare_fn(&mut self, bare_fn: &'ast syn::TypeBareFn) {
+            self.visit_fn_output(bare_fn.lifetimes.as_ref(), &bare_fn.output);
+        }
+
+        fn visit_trait_bound(&mut self, bound: &'ast syn::TraitBound) {
+            let len = self.higher_ranked.len();
+            if let Some(ref lifetimes) = bound.lifetimes {
+                let lifetimes = lifetimes.lifetimes.iter().map(|def| &def.lifetime.ident);
+                self.higher_ranked.extend(lifetimes);
+            }
+            syn::visit::visit_trait_bound(self, bound);
+            self.higher_ranked.truncate(len);
+        }
+
+        fn visit_parenthesized_generic_arguments(
+            &mut self,
+            arguments: &'ast syn::ParenthesizedGenericArguments,
+        ) {
+            self.visit_fn_output(None, &arguments.output);
+        }
+    }
+
+    let mut visitor = Lifetimes {
+        higher_ranked: Vec::new(),
+        in_fn: false,
+        found: false,
+    };
+    syn::visit::Visit::visit_type(&mut visitor, ty);
+    visitor.found
+}
+
+/// The first function pointer within `ty` which names a lifetime other than `'static` in its
+/// return type, when `output` is true, or in its arguments otherwise. `IntoOwned` cannot make
+/// the former `'static` and `Borrowed` cannot shorten the lifetimes of the latter. The
+/// higher-ranked lifetimes, like in `for<'x> fn(&'x str) -> &'x str`, do not count.
+pub fn find_borrowing_fn(ty: &syn::Type, output: bool) -> Option<&syn::TypeBareFn> {
+    struct Find<'ast>(bool, Option<&'ast syn::TypeBareFn>);
+
+    impl<'ast> syn::visit::Visit<'ast> for Find<'ast> {
+        fn visit_type_bare_fn(&mut self, bare_fn: &'ast syn::TypeBareFn) {
+            let higher_ranked = bare_fn
+                .lifetimes
+                .iter()
+                .flat_map(|bound| bound.lifetimes.iter().map(|def| &def.lifetime.ident))
+                .collect::<Vec<_>>();
+            let mut named = Named(higher_ranked, false);
+
+            if self.0 {
+                syn::visit::Visit::visit_return_type(&mut named, &bare_fn.output);
+            } else {
+                for input in &bare_fn.inputs {
+                    syn::visit::Visit::visit_bare_fn_arg(&mut named, input);
+                }
+            }
+
+            if named.1 && self.1.is_none() {
+                self.1 = Some(bare_fn);
+            }
+        }
+    }
+
+    struct Named<'ast>(Vec<&'ast syn::Ident>, bool);
+
+    impl<'ast> syn::visit::Visit<'ast> for Named<'ast> {
+        fn visit_lifetime(&mut self, lifetime: &'ast syn::Lifetime) {
+            self.1 |= lifetime.ident != "static" && !self.0.contains(&&lifetime.ident);
+        }
+    }
+
+    let mut visitor = Find(output, None);
+    syn::visit::Visit::visit_type(&mut visitor, ty);
+    visitor.1
+}
+
+/// True if the type parameter `param` is used as a type within `ty`, like in `Vec<T>` but not in
+/// `T::Item`, whose bounds are up to the trait of `T`.
+pub fn uses_type_param(ty: &syn::Type, param: &syn::Ident) -> bool {
+    struct Uses<'p>(&'p syn::Ident, bool);
+
+    impl<'ast> syn::visit::Visit<'ast> for Uses<'_> {
+        fn visit_type_path(&mut self, ty: &'ast syn::TypePath) {
+            self.1 |= ty.qself.is_none() && ty.path.is_ident(self.0);
+            syn::visit::visit_type_path(self, ty);
+        }
+    }
+
+    let mut visitor = Uses(param, false);
+    syn::visit::Visit::visit_type(&mut visitor, ty);
+    visitor.1
+}
+
+/// The first shared reference with a lifetime other than `'static` within `ty`, like `&'a str` in
+/// `HashMap<&'a str, u32>`, which can only be owned through the mirror type. The arguments of
+/// function pointers and `Fn` traits are not looked into.
+pub fn find_reference(ty: &syn::Type) -> Option<&syn::TypeReference> {
+    find_reference_with(ty, false)
+}
+
+/// The first mutable reference with a lifetime other than `'static` within `ty`, like
+/// `&'a mut T` in `Option<&'a mut T>`, which neither derive can do anything with. The arguments
+/// of function pointers and `Fn` traits are not looked into.
+pub fn find_mut_reference(ty: &syn::Type) -> Option<&syn::TypeReference> {
+    find_reference_with(ty, true)
+}
+
+fn find_reference_with(ty: &syn::Type, mutable: bool) -> Option<&syn::TypeReference> {
+    struct Find<'ast>(bool, Option<&'ast syn::TypeReference>);
+
+    impl<'ast> syn::visit::Visit<'ast> for Find<'ast> {
+        fn visit_type_reference(&mut self, reference: &'ast syn::TypeReference) {
+            let is_static = reference
+                .lifetime
+                .as_ref()
+                .map_or(false, |lifetime| lifetime.ident == "static");
+
+            if reference.mutability.is_some() == self.0 && !is_static && self.1.is_none() {
+                self.1 = Some(reference);
+            }
+            syn::visit::visit_type_reference(self, reference);
+        }
+
+        fn visit_type_bare_fn(&mut self, _: &'ast syn::TypeBareFn) {}
+
+        fn visit_parenthesized_generic_arguments(
+            &mut self,
+            _: &'ast syn::ParenthesizedGenericArguments,
+        ) {
+        }
+    }
+
+    let mut visitor = Find(mutable, None);
+    syn::visit::Visit::visit_type(&mut visitor, ty);
+    visitor.1
+}
+
+/// The first trait object within `ty` which names a lifetime other than `'static`, like
+/// `dyn Visitor<'a> + 'a` in `Arc<dyn Visitor<'a> + 'a>`. Such trait objects can only be made
+/// `'static` by the trait itself. Lifetimes introduced with `for<'x>` do not count, and neither do
+/// trait objects within function pointers.
+pub fn find_borrowing_trait_object(ty: &syn::Type) -> Option<&syn::TypeTraitObject> {
+    struct Find<'ast>(Option<&'ast syn::TypeTraitObject>);
+
+    impl<'ast> syn::visit::Visit<'ast> for Find<'ast> {
+        fn visit_type_trait_object(&mut self, object: &'ast syn::TypeTraitObject) {
+            let mut named = Named(Vec::new(), false);
+            syn::visit::visit_type_trait_object(&mut named, object);
+
+            if named.1 && self.0.is_none() {
+                self.0 = Some(object);
+            }
+            syn::visit::visit_type_trait_object(self, object);
+        }
+
+        fn visit_type_bare_fn(&mut self, _: &'ast syn::TypeBareFn) {}
+    }
+
+    /// Whether any lifetime other than `'static` and the ones of the enclosing `for<...>` is
+    /// named.
+    struct Named<'ast>(Vec<&'ast syn::Ident>, bool);
+
+    impl<'ast> syn::visit::Visit<'ast> for Named<'ast> {
+        fn visit_trait_bound(&mut self, bound: &'ast syn::TraitBound) {
+            let len = self.0.len();
+            if let Some(ref lifetimes) = bound.lifetimes {
+                self.0
+                    .extend(lifetimes.lifetimes.iter().map(|def| &def.lifetime.ident));
+            }
+            syn::visit::visit_trait_bound(self, bound);
+            self.0.truncate(len);
+        }
+
+        fn visit_lifetime(&mut self, lifetime: &'ast syn::Lifetime) {
+            self.1 |= lifetime.ident != "static" && !self.0.contains(&&lifetime.ident);
+        }
+    }
+
+    let mut visitor = Find(None);
+    syn::visit::Visit::visit_type(&mut visitor, ty);
+    visitor.0
+}
+
+/// Returns a copy of the path type `ty` with `f` applied to each of its type arguments.
+pub fn map_type_arguments(ty: &syn::Type, mut f: impl FnMut(&syn::Type) -> syn::Type) -> syn::Type {
+    let mut ty = ungroup(ty).clone();
+
+    if let syn::Type::Path(syn::TypePath { ref mut path, .. }) = ty {
+        if let Some(syn::PathArguments::AngleBracketed(data)) =
+            path.segments.last_mut().map(|x| &mut x.arguments)
+        {
+            for arg in data.args.iter_mut() {
+                if let syn::GenericArgument::Type(inner) = arg {
+                    *inner = f(inner);
+                }
+            }
+        }
+    }
+
+    ty
+}
+
+/// Replaces any of the given lifetimes with `'static` when used as a `syn::visit_mut::VisitMut`.
+pub struct StaticLifetimes<'a>(pub &'a [syn::Lifetime]);
+
+impl syn::visit_mut::VisitMut for StaticLifetimes<'_> {
+    fn visit_lifetime_mut(&mut self, lifetime: &mut syn::Lifetime) {
+        if self.0.contains(lifetime) {
+            *lifetime = syn::Lifetime::new("'static", lifetime.apostrophe);
+        }
+    }
+}
+
+/// Replaces any of the given lifetimes with another one when used as a
+/// `syn::visit_mut::VisitMut`.
+struct ReplaceLifetimes<'a>(&'a [syn::Lifetime], &'a syn::Lifetime);
+
+impl syn::visit_mut::VisitMut for ReplaceLifetimes<'_> {
+    fn visit_lifetime_mut(&mut self, lifetime: &mut syn::Lifetime) {
+        if self.0.contains(lifetime) {
+            *lifetime = self.1.clone();
+        }
+    }
+}
+
+/// The arguments naming the parameters of `generics` in their order, like `'a, T, N` for
+/// `<'a, T: Clone, const N: usize>`, with every lifetime replaced by `lifetime` if given.
+pub fn generic_arguments(
+    generics: &syn::Generics,
+    lifetime: Option<&syn::Lifetime>,
+) -> Vec<proc_macro2::TokenStream> {
+    generics
+        .params
+        .iter()
+        .map(|param| match *param {
+            syn::GenericParam::Lifetime(ref def) => {
+                let lifetime = lifetime.unwrap_or(&def.lifetime);
+                quote::quote! { #lifetime }
+            }
+            syn::GenericParam::Type(ref ty) => {
+                let ident = &ty.ident;
+                quote::quote! { #ident }
+            }
+            syn::GenericParam::Const(ref constant) => {
+                let ident = &constant.ident;
+                quote::quote! { #ident }
+            }
+        })
+        .collect()
+}
+
+/// Returns `generics` without the lifetime parameters, replacing their other uses with `'static`.
+pub fn without_lifetimes(generics: &syn::Generics) -> syn::Generics {
+    with_lifetimes_replaced(
+        generics,
+        &syn::Lifetime::new("'static", proc_macro2::Span::call_site()),
+    )
+}
+
+/// Returns `generics` without the lifetime parameters, replacing their other uses with
+/// `lifetime`. Declaring `lifetime` is left to the caller.
+pub fn with_lifetimes_replaced(
+    generics: &syn::Generics,
+    lifetime: &syn::Lifetime,
+) -> syn::Generics {
+    use syn::visit_mut::VisitMut;
+
+    let lifetimes = generics
+        .lifetimes()
+        .map(|def| def.lifetime.clone())
+        .collect::<Vec<_>>();
+
+    let mut generics = generics.clone();
+
+    generics.params = generics
+        .params
+        .into_iter()
+        .filter(|param| !matches!(param, syn::GenericParam::Lifetime(_)))
+        .collect();
+
+    if let Some(ref mut where_clause) = generics.where_clause {
+        where_clause.predicates = where_clause
+            .predicates
+            .clone()
+            .into_iter()
+            .filter(|predicate| !matches!(predicate, syn::WherePredicate::Lifetime(_)))
+            .collect();
+    }
+
+    ReplaceLifetimes(&lifetimes, lifetime).visit_generics_mut(&mut generics);
+
+    generics
+}
+
+/// The trait bounds of the type parameters and the where clause of `generics` which name its
+/// lifetime parameters, like `Into<Cow<'a, str>>` in `T: Into<Cow<'a, str>>`. The types with
+/// other lifetimes in their place, like the owned type, would need the trait for those lifetimes
+/// instead, which cannot be required next to the original bound without making them ambiguous.
+pub fn bounds_naming_lifetimes(generics: &syn::Generics) -> Vec<&syn::TraitBound> {
+    struct Names<'g>(&'g [syn::Lifetime], bool);
+
+    impl<'ast> syn::visit::Visit<'ast> for Names<'_> {
+        fn visit_lifetime(&mut self, lifetime: &'ast syn::Lifetime) {
+            self.1 |= self.0.contains(lifetime);
+        }
+    }
+
+    let lifetimes = generics
+        .lifetimes()
+        .map(|def| def.lifetime.clone())
+        .collect::<Vec<_>>();
+
+    let inline = generics.type_params().flat_map(|param| param.bounds.iter());
+    let predicates = generics
+        .where_clause
+        .iter()
+        .flat_map(|where_clause| where_clause.predicates.iter())
+        .flat_map(|predicate| match *predicate {
+            syn::WherePredicate::Type(ref predicate) => Some(predicate.bounds.iter()),
+            _ => None,
+        })
+        .flatten();
+
+    inline
+        .chain(predicates)
+        .filter_map(|bound| match *bound {
+            syn::TypeParamBound::Trait(ref bound) => Some(bound),
+            syn::TypeParamBound::Lifetime(_) => None,
+        })
+        .filter(|bound| {
+            let mut names = Names(&lifetimes, false);
+            syn::visit::Visit::visit_trait_bound(&mut names, bound);
+            names.1
+        })
+        .collect()
+}
+
+/// The predicates of the where clause of `generics` which bound a type naming its lifetime
+/// parameters by a trait, like `&'a T: IntoIterator`. They need the trait for other lifetimes
+/// like [`bounds_naming_lifetimes`] do.
+pub fn bounded_types_naming_lifetimes(generics: &syn::Generics) -> Vec<&syn::PredicateType> {
+    struct Names<'g>(&'g [syn::Lifetime], bool);
+
+    impl<'ast> syn::visit::Visit<'ast> for Names<'_> {
+        fn visit_lifetime(&mut self, lifetime: &'ast syn::Lifetime) {
+            self.1 |= self.0.contains(lifetime);
+        }
+    }
+
+    let lifetimes = generics
+        .lifetimes()
+        .map(|def| def.lifetime.clone())
+        .collect::<Vec<_>>();
+
+    generics
+        .where_clause
+        .iter()
+        .flat_map(|where_clause| where_clause.predicates.iter())
+        .filter_map(|predicate| match *predicate {
+            syn::WherePredicate::Type(ref predicate) => Some(predicate),
+            _ => None,
+        })
+        .filter(|predicate| {
+            let has_trait = predicate
+                .bounds
+                .iter()
+                .any(|bound| matches!(bound, syn::TypeParamBound::Trait(_)));
+            let mut names = Names(&lifetimes, false);
+            syn::visit::Visit::visit_type(&mut names, &predicate.bounded_ty);
+            has_trait && names.1
+        })
+        .collect()
+}
+
+/// The lifetime `'name` for the signatures of the generated methods, or `'name1`, `'name2` and
+/// so on when `generics` already declare a lifetime of that name.
+pub fn fresh_lifetime(generics: &syn::Generics, name: &str) -> syn::Lifetime {
+    let taken = |candidate: &str| {
+        generics
+            .lifetimes()
+            .any(|def| def.lifetime.ident == candidate)
+    };
+    let name = (0..)
+        .map(|index| match index {
+            0 => name.to_string(),
+            _ => format!("{}{}", name, index),
+        })
+        .find(|candidate| !taken(candidate))
+        .expect("the generics declare finitely many lifetimes");
+
+    syn::Lifetime::new(&format!("'{}", name), proc_macro2::Span::call_site())
+}
+
+/// The type parameter `name` for the signatures of the generated methods, or `name1`, `name2`
+/// and so on when `generics` already declare a type or const parameter of that name. Like the
+/// [`hygienic`] variables it has a mixed-site span.
+pub fn fresh_type_param(generics: &syn::Generics, name: &str) -> syn::Ident {
+    let taken = |candidate: &str| {
+        generics.params.iter().any(|param| match *param {
+            syn::GenericParam::Type(ref param) => param.ident == candidate,
+            syn::GenericParam::Const(ref param) => param.ident == candidate,
+            syn::GenericParam::Lifetime(_) => false,
+        })
+    };
+    let name = (0..)
+        .map(|index| match index {
+            0 => name.to_string(),
+            _ => format!("{}{}", name, index),
+        })
+        .find(|candidate| !taken(candidate))
+        .expect("the generics declare finitely many parameters");
+
+    syn::Ident::new(&name, proc_macro2::Span::mixed_site())
+}
+
+/// An identifier for a variable of the generated code, like the closure passed to `map_cows`.
+/// It only resolves within the generated code, so the fields of the type bound by their names
+/// in the patterns can neither shadow nor be shadowed by it.
+pub fn hygienic(name: &str) -> syn::Ident {
+    syn::Ident::new(name, proc_macro2::Span::mixed_site())
+}
+
+/// Returns `ty` with any of the given `lifetimes` replaced by `lifetime`.
+pub fn replace_lifetimes(
+    ty: &syn::Type,
+    lifetimes: &[syn::Lifetime],
+    lifetime: &syn::Lifetime,
+) -> syn::Type {
+    use syn::visit_mut::VisitMut;
+
+    let mut ty = ty.clone();
+    ReplaceLifetimes(lifetimes, lifetime).visit_type_mut(&mut ty);
+    ty
+}
+
+/// Constructs the struct or variant at `path` shaped like `fields` from the expressions of its
+/// `fields`, which are `name: value` for named fields.
+pub fn construct(
+    fields: &syn::Fields,
+    path: proc_macro2::TokenStream,
+    values: &[proc_macro2::TokenStream],
+) -> proc_macro2::TokenStream {
+    match *fields {
+        syn::Fields::Named(_) => quote::quote! { #path { #(#values),* } },
+        syn::Fields::Unnamed(_) => quote::quote! { #path ( #(#values),* ) },
+        syn::Fields::Unit => path,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use quote::quote;
+
+    fn segments(ty: &str) -> Vec<syn::PathSegment> {
+        super::collect_segments(&syn::parse_str(ty).unwrap())
+    }
+
+    #[test]
+    fn known_matches_suffixes() {
+        let known = syn::parse_str("my_crate::lexer::Span").unwrap();
+
+        assert!(super::is_known(&segments("Span<'a>"), &known));
+        assert!(super::is_known(&segments("lexer::Span<'a>"), &known));
+        assert!(super::is_known(&segments("my_crate::lexer::Span"), &known));
+        assert!(!super::is_known(&segments("parser::Span<'a>"), &known));
+        assert!(!super::is_known(
+            &segments("other::my_crate::lexer::Span"),
+            &known
+        ));
+    }
+
+    #[test]
+    fn options_need_single_type_argument() {
+        assert!(super::is_opt(&segments("Option<u8>")).is_some());
+        assert!(super::is_opt(&segments("std::option::Option<u8>")).is_some());
+        assert!(super::is_opt(&segments("Option<u8, u16>")).is_none());
+        assert!(super::is_opt(&segments("Option<'a, u8>")).is_none());
+        assert!(super::is_opt(&segments("other::Option<u8>")).is_none());
+    }
+
+    #[test]
+    fn type_param_uses() {
+        let uses = |ty: &str| {
+            super::uses_type_param(&syn::parse_str(ty).unwrap(), &quote::format_ident!("T"))
+        };
+
+        assert!(uses("T"));
+        assert!(uses("Vec<Option<T>>"));
+        assert!(uses("PhantomData<fn() -> T>"));
+        assert!(!uses("T::Item"));
+        assert!(!uses("Vec<U>"));
+        assert!(!uses("other::T"));
+    }
+
+    #[test]
+    fn mut_references() {
+        let find = |ty: &str| {
+            let ty: syn::Type = syn::parse_str(ty).unwrap();
+            super::find_mut_reference(&ty).map(|reference| quote::quote!(#reference).to_string())
+        };
+
+        assert_eq!(find("&'a mut str").as_deref(), Some("& 'a mut str"));
+        assert_eq!(find("Option<&mut [u8]>").as_deref(), Some("& mut [u8]"));
+        assert_eq!(find("&'a str"), None);
+        assert_eq!(find("&'static mut str"), None);
+        assert_eq!(find("fn(&mut String)"), None);
+        assert_eq!(find("Box<dyn FnMut(&mut String) + 'a>"), None);
+
+        let ty: syn::Type = syn::parse_str("HashMap<&'a str, Vec<&'a mut u8>>").unwrap();
+        let reference = super::find_reference(&ty).unwrap();
+        assert_eq!(quote::quote!(#reference).to_string(), "& 'a str");
+    }
+
+    #[test]
+    fn borrowing_trait_objects() {
+        let find = |ty: &str| {
+            let ty: syn::Type = syn::parse_str(ty).unwrap();
+            super::find_borrowing_trait_object(&ty).map(|object| quote::quote!(#object).to_string())
+        };
+
+        assert_eq!(
+            find("Arc<dyn Visitor<'a> + 'a>").as_deref(),
+            Some("dyn Visitor < 'a > + 'a")
+        );
+        assert_eq!(
+            find("Option<Rc<dyn Any + 'a>>").as_deref(),
+            Some("dyn Any + 'a")
+        );
+        assert_eq!(
+            find("Box<dyn Fn(&'a str)>").as_deref(),
+            Some("dyn Fn (& 'a str)")
+        );
+        assert_eq!(find("Box<dyn Fn(&str) + Send>"), None);
+        assert_eq!(find("Box<dyn for<'x> Visitor<'x> + 'static>"), None);
+        assert_eq!(find("Arc<dyn Any>"), None);
+        assert_eq!(find("fn(&dyn Visitor<'a>)"), None);
+        assert_eq!(find("&'a dyn Any"), None);
+    }
+
+    #[test]
+    fn generic_arguments() {
+        let generics: syn::Generics =
+            syn::parse_quote! { <'a, 'b: 'a, T: Clone = u8, const N: usize = 4> };
+
+        let arguments = |lifetime: Option<syn::Lifetime>| {
+            let arguments = super::generic_arguments(&generics, lifetime.as_ref());
+            quote!(#(#arguments),*).to_string()
+        };
+
+        assert_eq!(arguments(None), "'a , 'b , T , N");
+        assert_eq!(
+            arguments(Some(syn::parse_quote!('static))),
+            "'static , 'static , T , N"
+        );
+    }
+
+    #[test]
+    fn bounds_naming_lifetimes() {
+        let generics: syn::DeriveInput = syn::parse_quote! {
+            struct Foo<'a, T: Clone + Into<Cow<'a, str>> + 'a, U: for<'x> Visitor<'x>>
+            where
+                U: Visitor<'a> + 'a,
+                &'a U: IntoIterator,
+                Cow<'a, str>: 'a,
+                for<'x> &'x U: IntoIterator,
+                'a: 'a,
+            {}
+        };
+
+        let bounds = super::bounds_naming_lifetimes(&generics.generics);
+
+        assert_eq!(
+            quote!(#(#bounds),*).to_string(),
+            "Into < Cow < 'a , str > > , Visitor < 'a >"
+        );
+
+        let predicates = super::bounded_types_naming_lifetimes(&generics.generics);
+
+        assert_eq!(
+            quote!(#(#predicates),*).to_string(),
+            "& 'a U : IntoIterator"
+        );
+    }
+
+    #[test]
+    fn fresh_lifetimes() {
+        let fresh = |generics: &str| {
+            let generics: syn::Generics = syn::parse_str(generics).unwrap();
+            super::fresh_lifetime(&generics, "b").to_string()
+        };
+
+        assert_eq!(fresh("<'a, T>"), "'b");
+        assert_eq!(fresh("<'a, 'b: 'a>"), "'b1");
+        assert_eq!(fresh("<'b, 'b1, B>"), "'b2");
+    }
+
+    #[test]
+    fn fresh_type_params() {
+        let fresh = |generics: &str| {
+            let generics: syn::Generics = syn::parse_str(generics).unwrap();
+            super::fresh_type_param(&generics, "I").to_string()
+        };
+
+        assert_eq!(fresh("<'a, T>"), "I");
+        assert_eq!(fresh("<'I, I: Clone>"), "I1");
+        assert_eq!(fresh("<I, const I1: usize, F>"), "I2");
+    }
+
+    #[test]
+    fn lifetimes_of_functions() {
+        let has = |ty: &str| super::has_non_static_lifetimes(&syn::parse_str(ty).unwrap());
+
+        assert!(has("Foo<'a>"));
+        assert!(has("fn() -> &'a str"));
+        assert!(has("Box<dyn Fn() -> Cow<'a, str>>"));
+        assert!(!has("for<'x> fn(&'x str) -> usize"));
+        assert!(!has("Option<fn(&str) -> &str>"));
+        assert!(!has("fn(&'a str)"));
+        assert!(!has("Box<dyn for<'x> Fn(&'x str) -> &'x str>"));
+
+        let find = |ty: &str, output: bool| {
+            let ty: syn::Type = syn::parse_str(ty).unwrap();
+            super::find_borrowing_fn(&ty, output).map(|f| quote!(#f).to_string())
+        };
+
+        assert_eq!(
+            find("Vec<fn(u8) -> &'a str>", true).as_deref(),
+            Some("fn (u8) -> & 'a str")
+        );
+        assert_eq!(find("fn(&'a str)", false).as_deref(), Some("fn (& 'a str)"));
+        assert_eq!(find("fn(&'a str)", true), None);
+        assert_eq!(find("for<'x> fn(&'x str) -> &'x str", true), None);
+        assert_eq!(find("for<'x> fn(&'x str) -> &'x str", false), None);
+        assert_eq!(find("fn(&str) -> &str", true), None);
+    }
+
+    #[test]
+    fn without_lifetimes() {
+        let generics: syn::DeriveInput =
+            syn::parse_quote! { struct Foo<'a, T: 'a> where T: Into<&'a str>, 'a: 'a {} };
+
+        let generics = super::without_lifetimes(&generics.generics);
+        let (_, ty_generics, where_clause) = generics.split_for_impl();
+
+        assert_eq!(quote!(#ty_generics).to_string(), "< T >");
+        assert_eq!(
+            quote!(#where_clause).to_string(),
+            "where T : Into < & 'static str >"
+        );
+    }
+}
@@ -0,0 +1,104 @@
+//! The runtime side of `#[into_owned(intern)]`.
+
+use std::borrow::Cow;
+use std::collections::HashSet;
+use std::iter::FromIterator;
+
+/// Deduplicates strings while converting values with the generated `into_owned_interned`.
+///
+/// The generated code refers to this trait as `::derive_into_owned_core::Interner`, so the crate
+/// using the derive needs `derive-into-owned-core` as a dependency.
+pub trait Interner {
+    /// Returns a string equal to `s`, borrowing a `'static` one where possible and copying `s`
+    /// otherwise.
+    fn intern(&mut self, s: &str) -> Cow<'static, str>;
+}
+
+/// Leaks every string not seen before and never frees it, so the memory used grows with the
+/// number of distinct strings for as long as the program runs. Only suitable for small, bounded
+/// sets of strings like the keys of a configuration; use [`Vocabulary`] for strings coming from
+/// untrusted or unbounded input.
+impl Interner for HashSet<&'static str> {
+    fn intern(&mut self, s: &str) -> Cow<'static, str> {
+        match self.get(s) {
+            Some(interned) => Cow::Borrowed(interned),
+            None => {
+                let interned: &'static str = Box::leak(s.to_owned().into_boxed_str());
+                self.insert(interned);
+                Cow::Borrowed(interned)
+            }
+        }
+    }
+}
+
+/// An [`Interner`] over a fixed set of `'static` strings, like country codes or the field names
+/// of a schema, which never leaks.
+///
+/// The strings of the vocabulary are borrowed, all other strings are copied like `into_owned`
+/// would.
+///
+/// ```
+/// use derive_into_owned_core::{Interner, Vocabulary};
+/// use std::borrow::Cow;
+///
+/// let mut vocabulary: Vocabulary = ["fi", "se"].into_iter().collect();
+///
+/// assert!(matches!(vocabulary.intern("fi"), Cow::Borrowed("fi")));
+/// assert!(matches!(vocabulary.intern("no"), Cow::Owned(_)));
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct Vocabulary {
+    strings: HashSet<&'static str>,
+}
+
+impl Vocabulary {
+    /// Returns `true` if `s` is borrowed instead of copied.
+    pub fn contains(&self, s: &str) -> bool {
+        self.strings.contains(s)
+    }
+}
+
+impl FromIterator<&'static str> for Vocabulary {
+    fn from_iter<I: IntoIterator<Item = &'static str>>(iter: I) -> Self {
+        Vocabulary {
+            strings: iter.into_iter().collect(),
+        }
+    }
+}
+
+impl Interner for Vocabulary {
+    fn intern(&mut self, s: &str) -> Cow<'static, str> {
+        match self.strings.get(s) {
+            Some(interned) => Cow::Borrowed(interned),
+            None => Cow::Owned(s.to_owned()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn interns_equal_strings_once() {
+        let mut interner = HashSet::new();
+
+        let first = interner.intern("foo");
+        let second = interner.intern(&String::from("foo"));
+
+        assert_eq!(first.as_ptr(), second.as_ptr());
+        assert_eq!(interner.len(), 1);
+    }
+
+    #[test]
+    fn vocabulary_copies_unknown_strings() {
+        let mut vocabulary: Vocabulary = ["foo"].into_iter().collect();
+
+        let known = vocabulary.intern(&String::from("foo"));
+        let unknown = vocabulary.intern("bar");
+
+        assert!(matches!(known, Cow::Borrowed("foo")));
+        assert!(matches!(unknown, Cow::Owned(ref s) if s == "bar"));
+        assert!(!vocabulary.contains("bar"));
+    }
+}
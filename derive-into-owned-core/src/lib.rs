@@ -0,0 +1,109 @@
+//! # derive_into_owned_core
+//!
+//! The field classification and code generation behind the `derive-into-owned` derives, for
+//! building similar derives on top of them.
+//!
+//! [`FieldKind::resolve`] decides from the type of a field whether it is a [`Cow`], a
+//! "Cow-alike", a container of such types or something which is just moved or cloned. The
+//! [`FieldKind`] then generates the code for converting or borrowing the field.
+//!
+//! A derive is written by implementing [`BodyGenerator`] and calling [`impl_with_generator`] with
+//! it:
+//!
+//! ```
+//! use derive_into_owned_core::{impl_with_generator, BodyGenerator, Classifier, FieldKind};
+//! use quote::quote;
+//!
+//! /// Generates `fn detach(self) -> Foo<'static>` which only converts named struct fields.
+//! struct Detach(Classifier);
+//!
+//! impl BodyGenerator for Detach {
+//!     fn visit_struct(&self, data: &syn::DataStruct) -> proc_macro2::TokenStream {
+//!         let fields = data.fields.iter().map(|field| {
+//!             let ident = field.ident.as_ref().unwrap();
+//!             let code = FieldKind::resolve(&field.ty, &self.0)
+//!                 .move_or_clone_field(&quote! { self.#ident });
+//!             quote! { #ident: #code }
+//!         });
+//!         quote! { { #(#fields),* } }
+//!     }
+//!
+//!     fn visit_enum_data(
+//!         &self,
+//!         variant: proc_macro2::TokenStream,
+//!         data: &syn::Variant,
+//!     ) -> proc_macro2::TokenStream {
+//!         // reported at every variant when the generated code is compiled
+//!         let error = syn::Error::new_spanned(&data.ident, "`detach` only supports structs")
+//!             .to_compile_error();
+//!         quote! { #variant { .. } => #error }
+//!     }
+//!
+//!     fn combine_impl(
+//!         &self,
+//!         borrows: proc_macro2::TokenStream,
+//!         name: &syn::Ident,
+//!         rhs_params: proc_macro2::TokenStream,
+//!         where_clause: proc_macro2::TokenStream,
+//!         owned: proc_macro2::TokenStream,
+//!         body: proc_macro2::TokenStream,
+//!     ) -> proc_macro2::TokenStream {
+//!         quote! {
+//!             impl #borrows #name #rhs_params #where_clause {
+//!                 pub fn detach(self) -> #name #owned { #body }
+//!             }
+//!         }
+//!     }
+//! }
+//!
+//! let ast: syn::DeriveInput = syn::parse_quote! {
+//!     struct Foo<'a> {
+//!         name: std::borrow::Cow<'a, str>,
+//!     }
+//! };
+//!
+//! let expanded = impl_with_generator(&ast, Detach(Classifier::default()));
+//!
+//! assert!(expanded.to_string().contains("pub fn detach (self) -> Foo < 'static >"));
+//!
+//! let ast: syn::DeriveInput = syn::parse_quote! {
+//!     enum Bar<'a> {
+//!         Name(std::borrow::Cow<'a, str>),
+//!     }
+//! };
+//!
+//! let expanded = impl_with_generator(&ast, Detach(Classifier::default()));
+//!
+//! assert!(expanded.to_string().contains("compile_error !"));
+//! ```
+//!
+//! The code generation needs `syn`, `quote` and `proc-macro2`, which are behind the default
+//! `codegen` feature. Crates which only use the items the generated code refers to, like
+//! [`Interner`], [`IntoOwned`] or [`FieldInfo`], can depend on this crate with
+//! `default-features = false` to keep them out of their dependencies.
+//!
+//! [`Cow`]: https://doc.rust-lang.org/std/borrow/enum.Cow.html
+
+#[cfg(feature = "codegen")]
+pub mod diagnostics;
+mod field_info;
+#[cfg(feature = "codegen")]
+mod field_kind;
+#[cfg(feature = "codegen")]
+mod generator;
+#[cfg(feature = "codegen")]
+pub mod helpers;
+mod interner;
+mod owned;
+#[cfg(feature = "futures")]
+mod stream;
+
+pub use field_info::FieldInfo;
+#[cfg(feature = "codegen")]
+pub use field_kind::{Classifier, FieldKind, KnownType, MirrorLeaves, Strategy};
+#[cfg(feature = "codegen")]
+pub use generator::{impl_with_generator, BodyGenerator};
+pub use interner::{Interner, Vocabulary};
+pub use owned::IntoOwned;
+#[cfg(feature = "futures")]
+pub use stream::{OwnedItems, OwnedItemsStream};
@@ -0,0 +1,41 @@
+//! The trait implemented by the `IntoOwned` derive with `#[into_owned(trait_impl)]`.
+
+/// Values which can be converted into versions owning all of their data, implemented by the
+/// `IntoOwned` derive with `#[into_owned(trait_impl)]`.
+///
+/// The generated code refers to this trait as `::derive_into_owned_core::IntoOwned`, so the crate
+/// using the derive needs `derive-into-owned-core` as a dependency.
+pub trait IntoOwned {
+    /// The `'static` version of the type, like `Foo<'static>` for `Foo<'a>`
+    type Owned;
+
+    /// Returns a version of `self` with all fields converted to owning versions.
+    fn into_owned(self) -> Self::Owned;
+}
+
+/// Converts every element, so that `Vec<Foo<'a>>` becomes `Vec<Foo<'static>>` with a single
+/// `into_owned()`. `From` cannot be implemented for these, as `Vec` is not a local type and the
+/// conversion would overlap with `impl From<T> for T` for `Foo<'static>`.
+impl<T: IntoOwned> IntoOwned for Vec<T> {
+    type Owned = Vec<T::Owned>;
+
+    fn into_owned(self) -> Self::Owned {
+        self.into_iter().map(IntoOwned::into_owned).collect()
+    }
+}
+
+impl<T: IntoOwned> IntoOwned for Option<T> {
+    type Owned = Option<T::Owned>;
+
+    fn into_owned(self) -> Self::Owned {
+        self.map(IntoOwned::into_owned)
+    }
+}
+
+impl<T: IntoOwned> IntoOwned for Box<T> {
+    type Owned = Box<T::Owned>;
+
+    fn into_owned(self) -> Self::Owned {
+        Box::new((*self).into_owned())
+    }
+}
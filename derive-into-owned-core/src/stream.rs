@@ -0,0 +1,56 @@
+//! Owning the items of a `futures` stream, with the `futures` feature.
+
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+use futures_core::Stream;
+
+use crate::IntoOwned;
+
+/// Extension trait for streams of borrowing items, for converting every item before it crosses a
+/// spawn boundary.
+pub trait OwnedItems: Stream + Sized
+where
+    Self::Item: IntoOwned,
+{
+    /// Converts every item of the stream with [`IntoOwned::into_owned`].
+    fn owned_items(self) -> OwnedItemsStream<Self> {
+        OwnedItemsStream { stream: self }
+    }
+}
+
+impl<S> OwnedItems for S
+where
+    S: Stream,
+    S::Item: IntoOwned,
+{
+}
+
+pin_project_lite::pin_project! {
+    /// The stream returned by [`OwnedItems::owned_items`].
+    #[must_use = "streams do nothing unless polled"]
+    #[derive(Debug)]
+    pub struct OwnedItemsStream<S> {
+        #[pin]
+        stream: S,
+    }
+}
+
+impl<S> Stream for OwnedItemsStream<S>
+where
+    S: Stream,
+    S::Item: IntoOwned,
+{
+    type Item = <S::Item as IntoOwned>::Owned;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        self.project()
+            .stream
+            .poll_next(cx)
+            .map(|item| item.map(IntoOwned::into_owned))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.stream.size_hint()
+    }
+}
@@ -0,0 +1,33 @@
+//! Types deriving with `#[into_owned(msrv = "1.56")]`, built by CI with Rust 1.56 so that both
+//! the derives themselves and the code they generate are checked against the documented minimum.
+
+use std::borrow::Cow;
+use std::collections::HashMap;
+
+use derive_into_owned::{Borrowed, IntoOwned, OwnershipDebug};
+
+#[derive(IntoOwned, Borrowed, OwnershipDebug, Debug, PartialEq)]
+#[into_owned(msrv = "1.56", ownership, mirror(derive(Debug, PartialEq)))]
+pub struct Message<'a, T> {
+    pub topic: Cow<'a, str>,
+    pub body: Option<Cow<'a, [u8]>>,
+    pub tags: Vec<Cow<'a, str>>,
+    pub headers: HashMap<String, Cow<'a, str>>,
+    pub status: Status<'a>,
+    pub extra: T,
+}
+
+#[derive(IntoOwned, Borrowed, OwnershipDebug, Debug, PartialEq)]
+#[into_owned(msrv = "1.56")]
+pub enum Status<'a> {
+    Ok,
+    Fault(Cow<'a, str>),
+    Nested(Box<Status<'a>>),
+}
+
+#[derive(IntoOwned, Borrowed, Clone, Debug, PartialEq)]
+#[into_owned(msrv = "1.56")]
+pub struct Point {
+    pub x: i32,
+    pub y: i32,
+}
@@ -0,0 +1,45 @@
+use std::borrow::Cow;
+use std::collections::HashMap;
+
+use derive_into_owned_msrv_test::{Message, MessageOwned, Point, Status};
+
+fn message(text: &str) -> Message<'_, Point> {
+    let mut headers = HashMap::new();
+    headers.insert(String::from("host"), Cow::Borrowed(text));
+
+    Message {
+        topic: Cow::Borrowed(text),
+        body: Some(Cow::Borrowed(text.as_bytes())),
+        tags: vec![Cow::Borrowed(text)],
+        headers,
+        status: Status::Nested(Box::new(Status::Fault(Cow::Borrowed(text)))),
+        extra: Point { x: 1, y: 2 },
+    }
+}
+
+#[test]
+fn into_owned() {
+    let text = String::from("text");
+    let borrowed = message(&text);
+
+    let owned: Message<'static, Point> = borrowed.into_owned();
+    drop(text);
+
+    assert!(matches!(owned.topic, Cow::Owned(ref s) if s == "text"));
+    assert!(matches!(owned.headers["host"], Cow::Owned(_)));
+    assert_eq!(owned.extra.into_owned(), Point { x: 1, y: 2 });
+}
+
+#[test]
+fn borrowed_and_mirror() {
+    let text = String::from("text");
+    let message = message(&text);
+
+    let borrowed = message.borrowed();
+    assert_eq!(borrowed, message);
+    assert!(!message.ownership().topic);
+
+    let mirror = MessageOwned::from(message);
+    assert_eq!(mirror.topic, "text");
+    assert_eq!(mirror.tags, ["text"]);
+}
@@ -0,0 +1,36 @@
+//! Types deserialized with postcard by borrowing from the received buffer, as on embedded
+//! targets, and then owned for queuing. The crate is `no_std` so that building it checks the code
+//! generated with `#[into_owned(no_std)]`.
+
+#![no_std]
+
+extern crate alloc;
+
+use alloc::borrow::Cow;
+use alloc::boxed::Box;
+use alloc::vec::Vec;
+
+use derive_into_owned::{Borrowed, IntoOwned, OwnershipDebug};
+use serde::{Deserialize, Serialize};
+
+#[derive(IntoOwned, Borrowed, OwnershipDebug, Serialize, Deserialize, Debug, PartialEq)]
+#[into_owned(no_std, ownership, mirror(derive(Debug, PartialEq)))]
+pub struct Reading<'a> {
+    #[serde(borrow)]
+    pub sensor: Cow<'a, str>,
+    #[serde(borrow)]
+    pub raw: Cow<'a, [u8]>,
+    #[serde(borrow)]
+    pub unit: Option<Cow<'a, str>>,
+    pub samples: Vec<i32>,
+    #[serde(borrow)]
+    pub status: Status<'a>,
+}
+
+#[derive(IntoOwned, Borrowed, OwnershipDebug, Serialize, Deserialize, Debug, PartialEq)]
+#[into_owned(no_std)]
+pub enum Status<'a> {
+    Ok,
+    Fault(#[serde(borrow)] Cow<'a, str>),
+    Nested(#[serde(borrow)] Box<Status<'a>>),
+}
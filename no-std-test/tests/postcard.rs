@@ -0,0 +1,49 @@
+use std::borrow::Cow;
+
+use derive_into_owned_no_std_test::{Reading, ReadingOwned, Status};
+
+fn encode() -> Vec<u8> {
+    let reading = Reading {
+        sensor: Cow::Borrowed("thermometer"),
+        raw: Cow::Borrowed(&[0x12, 0x34]),
+        unit: Some(Cow::Borrowed("celsius")),
+        samples: vec![21, 22],
+        status: Status::Fault(Cow::Borrowed("drift")),
+    };
+
+    postcard::to_allocvec(&reading).unwrap()
+}
+
+#[test]
+fn deserialize_borrowing_then_into_owned() {
+    let bytes = encode();
+
+    let reading: Reading<'_> = postcard::from_bytes(&bytes).unwrap();
+    assert!(matches!(reading.sensor, Cow::Borrowed("thermometer")));
+    assert!(matches!(reading.raw, Cow::Borrowed(&[0x12, 0x34])));
+    assert!(!reading.ownership().sensor);
+
+    let owned: Reading<'static> = reading.into_owned();
+    drop(bytes);
+
+    assert!(owned.ownership().sensor);
+    assert_eq!(owned.unit.as_deref(), Some("celsius"));
+    assert_eq!(owned.status, Status::Fault(Cow::Borrowed("drift")));
+
+    let borrowed = owned.borrowed();
+    assert!(matches!(borrowed.sensor, Cow::Borrowed("thermometer")));
+    assert!(format!("{:?}", borrowed.ownership_debug()).contains("[borrowed] \"thermometer\""));
+}
+
+#[test]
+fn deserialize_borrowing_into_mirror() {
+    let bytes = encode();
+
+    let reading: Reading<'_> = postcard::from_bytes(&bytes).unwrap();
+    let mirror = ReadingOwned::from(reading);
+    drop(bytes);
+
+    assert_eq!(mirror.sensor, "thermometer");
+    assert_eq!(mirror.raw, [0x12, 0x34]);
+    assert_eq!(mirror.status, Status::Fault(Cow::Borrowed("drift")));
+}
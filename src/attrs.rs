@@ -0,0 +1,842 @@
+//! Parsing of the `#[into_owned(...)]` attributes.
+
+use derive_into_owned_core::{diagnostics, Classifier, KnownType};
+
+/// Options given for the whole type with `#[into_owned(...)]`.
+#[derive(Default)]
+pub struct ContainerAttrs {
+    /// `mirror` or `mirror(...)` generates an owned mirror type
+    pub mirror: Option<MirrorAttrs>,
+    /// `yoke` generates a constructor for `yoke::Yoke<Foo<'static>, C>`, requires the `yoke`
+    /// feature
+    pub yoke: bool,
+    /// `self_owned` generates `FooSelfOwned` which keeps `Foo` together with the buffer it
+    /// borrows from, requires the `yoke` feature
+    pub self_owned: bool,
+    /// `known(my_crate::Span, other::Token = "to_static")` lists the lifetime carrying types and
+    /// the method converting them, defaulting to `into_owned`, and
+    /// `wrapper(ast::Spanned, other::Node = "map_value")` the generic wrappers and the method
+    /// mapping their payload, defaulting to `map`, and `unwrap_or_clone` converts the values within
+    /// `Rc<T>` and `Arc<T>`
+    pub classifier: Classifier,
+    /// `leak` generates `leaked` instead of `into_owned`, turning the value into `'static` by
+    /// leaking
+    pub leak: bool,
+    /// `strict` makes fields which would be silently moved as is into errors
+    pub strict: bool,
+    /// `intern` generates `into_owned_interned` in addition to `into_owned`
+    pub intern: bool,
+    /// `shrink` generates `into_owned_and_shrink` in addition to `into_owned`
+    pub shrink: bool,
+    /// `boxed` generates `into_owned_boxed` in addition to `into_owned`
+    pub boxed: bool,
+    /// `arc` generates `into_owned_arc` in addition to `into_owned`, with the `arc` feature
+    pub arc: bool,
+    /// `blocking` generates the async `into_owned_blocking` in addition to `into_owned`, with the
+    /// `tokio` feature
+    pub blocking: bool,
+    /// `take_owned` generates `take_owned` in addition to `into_owned`, leaving the default value
+    /// behind
+    pub take_owned: bool,
+    /// `ownership` generates `FooOwnership` reporting which fields own their data
+    pub ownership: bool,
+    /// `borrowed_strs` generates `borrowed_strs` listing the strings which are still borrowed
+    pub borrowed_strs: bool,
+    /// `try_reborrow_original` generates `try_reborrow_original` copying the value when all of its
+    /// `Cow`s are borrowed
+    pub try_reborrow_original: bool,
+    /// `field_info` generates `BORROW_FIELDS` describing the fields which are converted
+    pub field_info: bool,
+    /// `transparent` marks a newtype, which only gets a minimal `into_owned` delegating to its
+    /// single field
+    pub transparent: bool,
+    /// `free_fn` or `free_fn = "name"` generates a free function `foo_into_owned` instead of the
+    /// `into_owned` method
+    pub free_fn: Option<Option<syn::Ident>>,
+    /// `bound = "S: Storage<'static>"` adds where predicates to the `into_owned` impls, for types
+    /// generic over how they store their data
+    pub bounds: Vec<syn::WherePredicate>,
+    /// `map_cows` generates `map_cows` passing every `Cow<str>` through a closure
+    pub map_cows: bool,
+    /// `msrv = "1.56"` restricts the generated code to what the given Rust version supports
+    pub msrv: Option<(u32, u32)>,
+    /// `skip_bounds` leaves out the bounds added automatically, like `T: Clone` for `borrowed`
+    pub skip_bounds: bool,
+    /// `try_into_owned` generates `try_into_owned` instead of `into_owned` for enums with
+    /// variants holding plain references
+    pub try_into_owned: bool,
+    /// `no_std` makes the generated code refer to `core` and `alloc` instead of `std`
+    pub no_std: bool,
+    /// `assert_send_sync` checks that the fields of `Foo<'static>` are `Send + Sync + 'static`
+    pub assert_send_sync: bool,
+    /// `trait_impl` implements the `IntoOwned` trait of `derive-into-owned-core`
+    pub trait_impl: bool,
+}
+
+/// Options given for a type parameter with `#[into_owned(...)]`.
+#[derive(Default)]
+pub struct ParamAttrs {
+    /// `skip_bounds` leaves out the bounds added automatically for the parameter
+    pub skip_bounds: bool,
+}
+
+/// Options given for a single field with `#[into_owned(...)]`.
+#[cfg(feature = "into_owned")]
+#[derive(Default)]
+pub struct FieldAttrs {
+    /// `expect = "iterable(cow)"` asserts how the field is classified
+    pub expect: Option<syn::LitStr>,
+    /// `mirror(...)` customizes the field of the owned mirror type
+    pub mirror: Option<MirrorFieldAttrs>,
+    /// `with = "path::to_owned"` converts the field with the given function instead, `stringify`
+    /// with a function converting the `to_string()` of the field with `From` and
+    /// `clone_box = "Trait::clone_box"` with one converting the box returned by the function for
+    /// the pointee
+    pub with: Option<syn::Expr>,
+    /// `skip` replaces the field with `Default::default()` instead of converting it
+    pub skip: bool,
+}
+
+/// Options for a field of the mirror type given with `#[into_owned(mirror(...))]`.
+#[cfg(feature = "into_owned")]
+#[derive(Default)]
+pub struct MirrorFieldAttrs {
+    /// `name = "other"` renames the field in the mirror type
+    pub name: Option<syn::Ident>,
+    /// `ty = "Box<str>"` overrides the owned type, converted into with `Into`
+    pub ty: Option<syn::Type>,
+    /// `skip` leaves the field out of the mirror type
+    pub skip: bool,
+}
+
+/// Options given for the whole type with `#[borrowed(...)]`.
+#[cfg(feature = "borrowed")]
+#[derive(Default)]
+pub struct BorrowedAttrs {
+    /// `warn_clones` warns about fields of heap allocating types which `borrowed` deep clones
+    pub warn_clones: bool,
+    /// `view` or `view(...)` generates a borrowed view type, taking the same options as `mirror`
+    pub view: Option<MirrorAttrs>,
+}
+
+/// Options given for a single field with `#[borrowed(...)]`.
+#[cfg(feature = "borrowed")]
+#[derive(Default)]
+pub struct BorrowedFieldAttrs {
+    /// `clone` accepts the cost of cloning the field with `warn_clones`
+    pub clone: bool,
+    /// `as_ref` or `as_ref = "str"` rebuilds the field with `From::from(field.as_ref())`, the
+    /// type is given when there are several `AsRef` implementations
+    pub as_ref: Option<Option<syn::Type>>,
+    /// `view` uses the view type of the field's type in the view type, like `BarRef<'b>` for `Bar`
+    pub view: bool,
+}
+
+/// Options for the owned mirror type given with `#[into_owned(mirror(...))]`, also used for the
+/// view type given with `#[borrowed(view(...))]`.
+#[derive(Default)]
+pub struct MirrorAttrs {
+    /// `name = "FooOwned"`, defaults to the name of the type suffixed with `Owned`
+    pub name: Option<syn::Ident>,
+    /// `derive(...)` for the derives of the mirror type
+    pub derives: Vec<syn::Path>,
+    /// `attr(...)` for any other attributes of the mirror type, like `attr(archive(check_bytes))`
+    pub attrs: Vec<syn::NestedMeta>,
+    /// `arc` uses `Arc<str>` and `Arc<[T]>` instead of `String` and `Vec<T>` for the owned
+    /// leaves, only for `mirror`
+    pub arc: Option<syn::Path>,
+    /// `bytes` uses `bytes::Bytes` instead of `Vec<u8>` for the owned leaves, only for `mirror`
+    /// and requires the `bytes` feature
+    pub bytes: Option<syn::Path>,
+    /// `minicbor` derives `minicbor::Encode` and `minicbor::Decode` for the mirror type, keeping
+    /// the `#[n(..)]` and `#[cbor(..)]` attributes, only for `mirror` and requires the `minicbor`
+    /// feature
+    pub minicbor: Option<syn::Path>,
+    /// `partial_eq` implements `PartialEq` between the type and the mirror type both ways, only
+    /// for `mirror`
+    pub partial_eq: Option<syn::Path>,
+}
+
+impl ContainerAttrs {
+    pub fn parse(attrs: &[syn::Attribute]) -> syn::Result<Self> {
+        let mut parsed = ContainerAttrs::default();
+
+        for nested in nested_metas(attrs, "into_owned")? {
+            match nested {
+                syn::NestedMeta::Meta(syn::Meta::Path(ref path)) if path.is_ident("mirror") => {
+                    parsed.mirror.get_or_insert_with(MirrorAttrs::default);
+                }
+                syn::NestedMeta::Meta(syn::Meta::List(ref list))
+                    if list.path.is_ident("mirror") =>
+                {
+                    parsed.mirror = Some(MirrorAttrs::parse(list)?);
+                }
+                syn::NestedMeta::Meta(syn::Meta::Path(ref path)) if path.is_ident("yoke") => {
+                    require_yoke_feature(path)?;
+                    parsed.yoke = true;
+                }
+                syn::NestedMeta::Meta(syn::Meta::Path(ref path)) if path.is_ident("self_owned") => {
+                    require_yoke_feature(path)?;
+                    parsed.self_owned = true;
+                }
+                syn::NestedMeta::Meta(syn::Meta::Path(ref path)) if path.is_ident("leak") => {
+                    if parsed.try_into_owned {
+                        return Err(diagnostics::CONFLICTING_OPTIONS
+                            .error(path, "`leak` cannot be combined with `try_into_owned`"));
+                    }
+                    if parsed.trait_impl {
+                        return Err(diagnostics::CONFLICTING_OPTIONS
+                            .error(path, "`leak` cannot be combined with `trait_impl`"));
+                    }
+                    check_extras(&parsed, "leak", path)?;
+                    parsed.leak = true;
+                }
+                syn::NestedMeta::Meta(syn::Meta::Path(ref path))
+                    if path.is_ident("try_into_owned") =>
+                {
+                    if parsed.leak {
+                        return Err(diagnostics::CONFLICTING_OPTIONS
+                            .error(path, "`try_into_owned` cannot be combined with `leak`"));
+                    }
+                    if parsed.trait_impl {
+                        return Err(diagnostics::CONFLICTING_OPTIONS.error(
+                            path,
+                            "`try_into_owned` cannot be combined with `trait_impl`",
+                        ));
+                    }
+                    check_extras(&parsed, "try_into_owned", path)?;
+                    parsed.try_into_owned = true;
+                }
+                syn::NestedMeta::Meta(syn::Meta::Path(ref path)) if path.is_ident("strict") => {
+                    parsed.strict = true;
+                }
+                syn::NestedMeta::Meta(syn::Meta::Path(ref path))
+                    if path.is_ident("unwrap_or_clone") =>
+                {
+                    parsed.classifier.unwrap_or_clone = true;
+                }
+                syn::NestedMeta::Meta(syn::Meta::Path(ref path)) if path.is_ident("intern") => {
+                    if parsed.leak {
+                        return Err(diagnostics::CONFLICTING_OPTIONS
+                            .error(path, "`intern` cannot be combined with `leak`"));
+                    }
+                    if parsed.no_std {
+                        return Err(diagnostics::CONFLICTING_OPTIONS
+                            .error(path, "`intern` cannot be combined with `no_std`"));
+                    }
+                    parsed.intern = true;
+                }
+                syn::NestedMeta::Meta(syn::Meta::Path(ref path)) if path.is_ident("shrink") => {
+                    if parsed.leak {
+                        return Err(diagnostics::CONFLICTING_OPTIONS
+                            .error(path, "`shrink` cannot be combined with `leak`"));
+                    }
+                    parsed.shrink = true;
+                }
+                syn::NestedMeta::Meta(syn::Meta::Path(ref path)) if path.is_ident("boxed") => {
+                    if parsed.free_fn.is_some() {
+                        return Err(diagnostics::CONFLICTING_OPTIONS
+                            .error(path, "`boxed` cannot be combined with `free_fn`"));
+                    }
+                    parsed.boxed = true;
+                }
+                syn::NestedMeta::Meta(syn::Meta::Path(ref path)) if path.is_ident("arc") => {
+                    check_extra(&parsed, "arc", path)?;
+                    require_feature(path, cfg!(feature = "arc"), "arc")?;
+                    parsed.arc = true;
+                }
+                syn::NestedMeta::Meta(syn::Meta::Path(ref path)) if path.is_ident("blocking") => {
+                    check_extra(&parsed, "blocking", path)?;
+                    require_feature(path, cfg!(feature = "tokio"), "tokio")?;
+                    parsed.blocking = true;
+                }
+                syn::NestedMeta::Meta(syn::Meta::Path(ref path)) if path.is_ident("take_owned") => {
+                    if parsed.free_fn.is_some() {
+                        return Err(diagnostics::CONFLICTING_OPTIONS
+                            .error(path, "`take_owned` cannot be combined with `free_fn`"));
+                    }
+                    parsed.take_owned = true;
+                }
+                syn::NestedMeta::Meta(syn::Meta::Path(ref path)) if path.is_ident("ownership") => {
+                    parsed.ownership = true;
+                }
+                syn::NestedMeta::Meta(syn::Meta::Path(ref path))
+                    if path.is_ident("borrowed_strs") =>
+                {
+                    parsed.borrowed_strs = true;
+                }
+                syn::NestedMeta::Meta(syn::Meta::Path(ref path))
+                    if path.is_ident("try_reborrow_original") =>
+                {
+                    parsed.try_reborrow_original = true;
+                }
+                syn::NestedMeta::Meta(syn::Meta::Path(ref path)) if path.is_ident("field_info") => {
+                    if parsed.no_std {
+                        return Err(diagnostics::CONFLICTING_OPTIONS
+                            .error(path, "`field_info` cannot be combined with `no_std`"));
+                    }
+                    parsed.field_info = true;
+                }
+                syn::NestedMeta::Meta(syn::Meta::Path(ref path)) if path.is_ident("no_std") => {
+                    check_no_std(&parsed, path)?;
+                    check_extras(&parsed, "no_std", path)?;
+                    parsed.no_std = true;
+                }
+                syn::NestedMeta::Meta(syn::Meta::Path(ref path))
+                    if path.is_ident("assert_send_sync") =>
+                {
+                    parsed.assert_send_sync = true;
+                }
+                syn::NestedMeta::Meta(syn::Meta::Path(ref path)) if path.is_ident("trait_impl") => {
+                    // neither generates the `into_owned` the trait delegates to
+                    if parsed.leak || parsed.try_into_owned {
+                        let other = if parsed.leak {
+                            "leak"
+                        } else {
+                            "try_into_owned"
+                        };
+                        return Err(diagnostics::CONFLICTING_OPTIONS.error(
+                            path,
+                            format!("`trait_impl` cannot be combined with `{}`", other),
+                        ));
+                    }
+                    if parsed.no_std {
+                        return Err(diagnostics::CONFLICTING_OPTIONS
+                            .error(path, "`trait_impl` cannot be combined with `no_std`"));
+                    }
+                    parsed.trait_impl = true;
+                }
+                syn::NestedMeta::Meta(syn::Meta::Path(ref path)) if path.is_ident("map_cows") => {
+                    parsed.map_cows = true;
+                }
+                syn::NestedMeta::Meta(syn::Meta::Path(ref path)) if path.is_ident("free_fn") => {
+                    check_free_fn(&parsed, path)?;
+                    parsed.free_fn = Some(None);
+                }
+                syn::NestedMeta::Meta(syn::Meta::NameValue(ref nv))
+                    if nv.path.is_ident("free_fn") =>
+                {
+                    check_free_fn(&parsed, &nv.path)?;
+                    parsed.free_fn = Some(Some(parse_lit_str(&nv.lit)?));
+                }
+                syn::NestedMeta::Meta(syn::Meta::Path(ref path))
+                    if path.is_ident("transparent") =>
+                {
+                    check_extras(&parsed, "transparent", path)?;
+                    parsed.transparent = true;
+                }
+                syn::NestedMeta::Meta(syn::Meta::Path(ref path))
+                    if path.is_ident("skip_bounds") =>
+                {
+                    parsed.skip_bounds = true;
+                }
+                syn::NestedMeta::Meta(syn::Meta::NameValue(ref nv))
+                    if nv.path.is_ident("bound") =>
+                {
+                    parsed.bounds.extend(parse_bounds(&nv.lit)?);
+                }
+                syn::NestedMeta::Meta(syn::Meta::NameValue(ref nv)) if nv.path.is_ident("msrv") => {
+                    parsed.msrv = Some(parse_msrv(&nv.lit)?);
+                }
+                syn::NestedMeta::Meta(syn::Meta::List(ref list)) if list.path.is_ident("known") => {
+                    for known in &list.nested {
+                        parsed
+                            .classifier
+                            .known
+                            .push(parse_known_type(known, "into_owned")?);
+                    }
+                }
+                syn::NestedMeta::Meta(syn::Meta::List(ref list))
+                    if list.path.is_ident("wrapper") =>
+                {
+                    for wrapper in &list.nested {
+                        parsed
+                            .classifier
+                            .wrappers
+                            .push(parse_known_type(wrapper, "map")?);
+                    }
+                }
+                other => return Err(unknown_option(&other, "into_owned")),
+            }
+        }
+
+        // types listed on the type itself take precedence over the project wide ones
+        parsed
+            .classifier
+            .known
+            .extend(crate::config::known_types()?);
+
+        Ok(parsed)
+    }
+
+    /// True if the generated code may use features stabilized in Rust `major.minor`.
+    pub fn allows_rust(&self, major: u32, minor: u32) -> bool {
+        self.msrv.map_or(true, |msrv| msrv >= (major, minor))
+    }
+}
+
+impl ParamAttrs {
+    pub fn parse(attrs: &[syn::Attribute]) -> syn::Result<Self> {
+        let mut parsed = ParamAttrs::default();
+
+        for nested in nested_metas(attrs, "into_owned")? {
+            match nested {
+                syn::NestedMeta::Meta(syn::Meta::Path(ref path))
+                    if path.is_ident("skip_bounds") =>
+                {
+                    parsed.skip_bounds = true;
+                }
+                other => return Err(unknown_option(&other, "type parameter")),
+            }
+        }
+
+        Ok(parsed)
+    }
+}
+
+#[cfg(feature = "into_owned")]
+impl FieldAttrs {
+    pub fn parse(attrs: &[syn::Attribute]) -> syn::Result<Self> {
+        let mut parsed = FieldAttrs::default();
+        // the option which already decided how the field is converted
+        let mut conversion = None;
+
+        for nested in nested_metas(attrs, "into_owned")? {
+            match nested {
+                syn::NestedMeta::Meta(syn::Meta::NameValue(ref nv))
+                    if nv.path.is_ident("expect") =>
+                {
+                    match nv.lit {
+                        syn::Lit::Str(ref s) => parsed.expect = Some(s.clone()),
+                        ref other => {
+                            return Err(diagnostics::MALFORMED_OPTION
+                                .error(other, "expected a string literal"))
+                        }
+                    }
+                }
+                syn::NestedMeta::Meta(syn::Meta::List(ref list))
+                    if list.path.is_ident("mirror") =>
+                {
+                    parsed.mirror = Some(MirrorFieldAttrs::parse(list)?);
+                }
+                syn::NestedMeta::Meta(syn::Meta::NameValue(ref nv)) if nv.path.is_ident("with") => {
+                    check_conversion(&mut conversion, "with", nv)?;
+                    parsed.with = Some(parse_lit_str(&nv.lit)?);
+                }
+                syn::NestedMeta::Meta(syn::Meta::Path(ref path)) if path.is_ident("skip") => {
+                    check_conversion(&mut conversion, "skip", path)?;
+                    parsed.skip = true;
+                }
+                syn::NestedMeta::Meta(syn::Meta::Path(ref path)) if path.is_ident("stringify") => {
+                    check_conversion(&mut conversion, "stringify", path)?;
+                    // for `Box<dyn Error + 'a>` and other types which are `From<String>`
+                    parsed.with = Some(syn::parse_quote! {
+                        |value| ::std::convert::From::from(::std::string::ToString::to_string(&value))
+                    });
+                }
+                syn::NestedMeta::Meta(syn::Meta::NameValue(ref nv))
+                    if nv.path.is_ident("clone_box") =>
+                {
+                    check_conversion(&mut conversion, "clone_box", nv)?;
+                    // `Box<T>`, `Rc<T>` and `Arc<T>` are all `From<Box<T>>`
+                    let clone_box = parse_lit_str::<syn::Path>(&nv.lit)?;
+                    parsed.with = Some(syn::parse_quote! {
+                        |value| ::std::convert::From::from(#clone_box(::std::ops::Deref::deref(&value)))
+                    });
+                }
+                other => return Err(unknown_option(&other, "field")),
+            }
+        }
+
+        Ok(parsed)
+    }
+}
+
+#[cfg(feature = "borrowed")]
+impl BorrowedAttrs {
+    pub fn parse(attrs: &[syn::Attribute]) -> syn::Result<Self> {
+        let mut parsed = BorrowedAttrs::default();
+
+        for nested in nested_metas(attrs, "borrowed")? {
+            match nested {
+                syn::NestedMeta::Meta(syn::Meta::Path(ref path))
+                    if path.is_ident("warn_clones") =>
+                {
+                    parsed.warn_clones = true;
+                }
+                syn::NestedMeta::Meta(syn::Meta::Path(ref path)) if path.is_ident("view") => {
+                    parsed.view.get_or_insert_with(MirrorAttrs::default);
+                }
+                syn::NestedMeta::Meta(syn::Meta::List(ref list)) if list.path.is_ident("view") => {
+                    let view = MirrorAttrs::parse(list)?;
+                    let mirror_only = view
+                        .arc
+                        .as_ref()
+                        .or(view.bytes.as_ref())
+                        .or(view.minicbor.as_ref())
+                        .or(view.partial_eq.as_ref());
+                    if let Some(ref option) = mirror_only {
+                        let message = format!(
+                            "`{}` is only supported by `mirror`",
+                            quote::ToTokens::to_token_stream(option)
+                        );
+                        return Err(diagnostics::INAPPLICABLE_OPTION.error(option, message));
+                    }
+                    parsed.view = Some(view);
+                }
+                other => return Err(unknown_option(&other, "borrowed")),
+            }
+        }
+
+        Ok(parsed)
+    }
+}
+
+#[cfg(feature = "borrowed")]
+impl BorrowedFieldAttrs {
+    pub fn parse(attrs: &[syn::Attribute]) -> syn::Result<Self> {
+        let mut parsed = BorrowedFieldAttrs::default();
+
+        for nested in nested_metas(attrs, "borrowed")? {
+            match nested {
+                syn::NestedMeta::Meta(syn::Meta::Path(ref path)) if path.is_ident("clone") => {
+                    parsed.clone = true;
+                }
+                syn::NestedMeta::Meta(syn::Meta::Path(ref path)) if path.is_ident("as_ref") => {
+                    parsed.as_ref = Some(None);
+                }
+                syn::NestedMeta::Meta(syn::Meta::NameValue(ref nv))
+                    if nv.path.is_ident("as_ref") =>
+                {
+                    parsed.as_ref = Some(Some(parse_lit_str(&nv.lit)?));
+                }
+                syn::NestedMeta::Meta(syn::Meta::Path(ref path)) if path.is_ident("view") => {
+                    parsed.view = true;
+                }
+                other => return Err(unknown_option(&other, "borrowed field")),
+            }
+        }
+
+        Ok(parsed)
+    }
+}
+
+impl MirrorAttrs {
+    /// The owned types chosen for the leaves of the mirror type.
+    #[cfg(feature = "into_owned")]
+    pub fn leaves(&self) -> derive_into_owned_core::MirrorLeaves {
+        derive_into_owned_core::MirrorLeaves {
+            arc: self.arc.is_some(),
+            bytes: self.bytes.is_some(),
+        }
+    }
+
+    fn parse(list: &syn::MetaList) -> syn::Result<Self> {
+        let mut parsed = MirrorAttrs::default();
+
+        for nested in &list.nested {
+            match nested {
+                syn::NestedMeta::Meta(syn::Meta::NameValue(ref nv)) if nv.path.is_ident("name") => {
+                    parsed.name = Some(parse_lit_str(&nv.lit)?);
+                }
+                syn::NestedMeta::Meta(syn::Meta::List(ref list))
+                    if list.path.is_ident("derive") =>
+                {
+                    for derive in &list.nested {
+                        match derive {
+                            syn::NestedMeta::Meta(syn::Meta::Path(path)) => {
+                                parsed.derives.push(path.clone())
+                            }
+                            other => {
+                                return Err(
+                                    diagnostics::MALFORMED_OPTION.error(other, "expected a derive")
+                                )
+                            }
+                        }
+                    }
+                }
+                syn::NestedMeta::Meta(syn::Meta::List(ref list)) if list.path.is_ident("attr") => {
+                    parsed.attrs.extend(list.nested.iter().cloned());
+                }
+                syn::NestedMeta::Meta(syn::Meta::Path(ref path)) if path.is_ident("arc") => {
+                    parsed.arc = Some(path.clone());
+                }
+                syn::NestedMeta::Meta(syn::Meta::Path(ref path)) if path.is_ident("bytes") => {
+                    require_bytes_feature(path)?;
+                    parsed.bytes = Some(path.clone());
+                }
+                syn::NestedMeta::Meta(syn::Meta::Path(ref path)) if path.is_ident("minicbor") => {
+                    require_minicbor_feature(path)?;
+                    parsed.minicbor = Some(path.clone());
+                }
+                syn::NestedMeta::Meta(syn::Meta::Path(ref path)) if path.is_ident("partial_eq") => {
+                    parsed.partial_eq = Some(path.clone());
+                }
+                other => {
+                    // shared by `mirror(...)` and `view(...)`
+                    let within = quote::ToTokens::to_token_stream(&list.path).to_string();
+                    return Err(unknown_option(other, &within));
+                }
+            }
+        }
+
+        Ok(parsed)
+    }
+}
+
+#[cfg(feature = "into_owned")]
+impl MirrorFieldAttrs {
+    fn parse(list: &syn::MetaList) -> syn::Result<Self> {
+        let mut parsed = MirrorFieldAttrs::default();
+
+        for nested in &list.nested {
+            match nested {
+                syn::NestedMeta::Meta(syn::Meta::NameValue(ref nv)) if nv.path.is_ident("name") => {
+                    parsed.name = Some(parse_lit_str(&nv.lit)?);
+                }
+                syn::NestedMeta::Meta(syn::Meta::NameValue(ref nv)) if nv.path.is_ident("ty") => {
+                    parsed.ty = Some(parse_lit_str(&nv.lit)?);
+                }
+                syn::NestedMeta::Meta(syn::Meta::Path(ref path)) if path.is_ident("skip") => {
+                    parsed.skip = true;
+                }
+                other => return Err(unknown_option(other, "mirror field")),
+            }
+        }
+
+        Ok(parsed)
+    }
+}
+
+fn require_bytes_feature(path: &syn::Path) -> syn::Result<()> {
+    if cfg!(feature = "bytes") {
+        Ok(())
+    } else {
+        Err(diagnostics::MISSING_FEATURE.error(
+            path,
+            "bytes support requires the `bytes` feature of derive-into-owned",
+        ))
+    }
+}
+
+fn require_minicbor_feature(path: &syn::Path) -> syn::Result<()> {
+    if cfg!(feature = "minicbor") {
+        Ok(())
+    } else {
+        Err(diagnostics::MISSING_FEATURE.error(
+            path,
+            "minicbor support requires the `minicbor` feature of derive-into-owned",
+        ))
+    }
+}
+
+fn require_feature(option: &syn::Path, enabled: bool, feature: &str) -> syn::Result<()> {
+    if enabled {
+        Ok(())
+    } else {
+        Err(diagnostics::MISSING_FEATURE.error(
+            option,
+            format!(
+                "`{}` requires the `{}` feature of derive-into-owned",
+                quote::ToTokens::to_token_stream(option),
+                feature
+            ),
+        ))
+    }
+}
+
+fn require_yoke_feature(path: &syn::Path) -> syn::Result<()> {
+    if cfg!(feature = "yoke") {
+        Ok(())
+    } else {
+        Err(diagnostics::MISSING_FEATURE.error(
+            path,
+            "yoke support requires the `yoke` feature of derive-into-owned",
+        ))
+    }
+}
+
+fn parse_known_type(nested: &syn::NestedMeta, default_method: &str) -> syn::Result<KnownType> {
+    match nested {
+        syn::NestedMeta::Meta(syn::Meta::Path(ref path)) => Ok(KnownType {
+            path: path.clone(),
+            method: quote::format_ident!("{}", default_method),
+        }),
+        syn::NestedMeta::Meta(syn::Meta::NameValue(ref nv)) => Ok(KnownType {
+            path: nv.path.clone(),
+            method: parse_lit_str(&nv.lit)?,
+        }),
+        other => {
+            Err(diagnostics::MALFORMED_OPTION
+                .error(other, "expected a type or `Type = \"method\"`"))
+        }
+    }
+}
+
+/// Collects the contents of all `#[name(...)]` attributes.
+fn nested_metas(attrs: &[syn::Attribute], name: &str) -> syn::Result<Vec<syn::NestedMeta>> {
+    let mut nested = Vec::new();
+
+    for attr in attrs.iter().filter(|attr| attr.path.is_ident(name)) {
+        match attr.parse_meta()? {
+            syn::Meta::List(list) => nested.extend(list.nested),
+            other => {
+                return Err(diagnostics::MALFORMED_OPTION
+                    .error(other, format!("expected #[{}(...)]", name)))
+            }
+        }
+    }
+
+    Ok(nested)
+}
+
+/// The oldest Rust version the generated code supports, the first one with the 2021 edition.
+const MSRV_FLOOR: (u32, u32) = (1, 56);
+
+/// Parses `"1.56"` or `"1.56.0"` into `(1, 56)`.
+fn parse_msrv(lit: &syn::Lit) -> syn::Result<(u32, u32)> {
+    let s = match lit {
+        syn::Lit::Str(s) => s,
+        other => return Err(diagnostics::MSRV.error(other, "expected a string literal")),
+    };
+
+    let value = s.value();
+    let mut parts = value.split('.').map(str::parse::<u32>);
+    let version = match (parts.next(), parts.next(), parts.next(), parts.next()) {
+        (Some(Ok(major)), Some(Ok(minor)), None | Some(Ok(_)), None) => (major, minor),
+        _ => return Err(diagnostics::MSRV.error(s, "expected a Rust version like \"1.56\"")),
+    };
+
+    if version < MSRV_FLOOR {
+        return Err(diagnostics::MSRV.error(
+            s,
+            format!(
+                "the generated code requires at least Rust {}.{}",
+                MSRV_FLOOR.0, MSRV_FLOOR.1
+            ),
+        ));
+    }
+
+    Ok(version)
+}
+
+/// Errors if another option already decided how the field is converted, as `with`, `skip`,
+/// `stringify` and `clone_box` all do.
+#[cfg(feature = "into_owned")]
+fn check_conversion(
+    conversion: &mut Option<&'static str>,
+    option: &'static str,
+    tokens: impl quote::ToTokens,
+) -> syn::Result<()> {
+    match conversion.replace(option) {
+        Some(other) => Err(diagnostics::CONFLICTING_OPTIONS.error(
+            tokens,
+            format!("`{}` cannot be combined with `{}`", option, other),
+        )),
+        None => Ok(()),
+    }
+}
+
+/// Errors for the options generating methods, as there is no method to attach them to when the
+/// conversion is a free function.
+fn check_free_fn(parsed: &ContainerAttrs, tokens: impl quote::ToTokens) -> syn::Result<()> {
+    let other = if parsed.boxed {
+        "boxed"
+    } else if parsed.take_owned {
+        "take_owned"
+    } else {
+        return Ok(());
+    };
+
+    Err(diagnostics::CONFLICTING_OPTIONS.error(
+        tokens,
+        format!("`free_fn` cannot be combined with `{}`", other),
+    ))
+}
+
+/// Errors if `option` leaves out the plain `into_owned` method which the opted in extras of the
+/// Cargo features build on, or the `std` they need.
+fn check_extras(
+    parsed: &ContainerAttrs,
+    option: &'static str,
+    tokens: impl quote::ToTokens,
+) -> syn::Result<()> {
+    let extra = if parsed.arc {
+        "arc"
+    } else if parsed.blocking {
+        "blocking"
+    } else {
+        return Ok(());
+    };
+
+    Err(diagnostics::CONFLICTING_OPTIONS.error(
+        tokens,
+        format!("`{}` cannot be combined with `{}`", option, extra),
+    ))
+}
+
+/// Like [`check_extras`], for the `extra` being parsed.
+fn check_extra(
+    parsed: &ContainerAttrs,
+    extra: &'static str,
+    tokens: impl quote::ToTokens,
+) -> syn::Result<()> {
+    let other = if parsed.leak {
+        "leak"
+    } else if parsed.try_into_owned {
+        "try_into_owned"
+    } else if parsed.transparent {
+        "transparent"
+    } else if parsed.no_std {
+        "no_std"
+    } else {
+        return Ok(());
+    };
+
+    Err(diagnostics::CONFLICTING_OPTIONS.error(
+        tokens,
+        format!("`{}` cannot be combined with `{}`", extra, other),
+    ))
+}
+
+/// Errors for the options referring to the items of `derive_into_owned_core`, which needs `std`.
+fn check_no_std(parsed: &ContainerAttrs, tokens: impl quote::ToTokens) -> syn::Result<()> {
+    let other = if parsed.intern {
+        "intern"
+    } else if parsed.field_info {
+        "field_info"
+    } else if parsed.trait_impl {
+        "trait_impl"
+    } else {
+        return Ok(());
+    };
+
+    Err(diagnostics::CONFLICTING_OPTIONS.error(
+        tokens,
+        format!("`no_std` cannot be combined with `{}`", other),
+    ))
+}
+
+fn parse_lit_str<T: syn::parse::Parse>(lit: &syn::Lit) -> syn::Result<T> {
+    match lit {
+        syn::Lit::Str(s) => s.parse(),
+        other => Err(diagnostics::MALFORMED_OPTION.error(other, "expected a string literal")),
+    }
+}
+
+/// Parses `"S: Storage<'static>, T: Clone"` into the separate predicates.
+fn parse_bounds(lit: &syn::Lit) -> syn::Result<Vec<syn::WherePredicate>> {
+    use syn::punctuated::Punctuated;
+
+    match lit {
+        syn::Lit::Str(s) => Ok(s
+            .parse_with(Punctuated::<syn::WherePredicate, syn::Token![,]>::parse_terminated)?
+            .into_iter()
+            .collect()),
+        other => Err(diagnostics::MALFORMED_OPTION.error(other, "expected a string literal")),
+    }
+}
+
+fn unknown_option(nested: &syn::NestedMeta, within: &str) -> syn::Error {
+    diagnostics::MALFORMED_OPTION.error(nested, format!("unknown {} option", within))
+}
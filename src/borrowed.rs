@@ -0,0 +1,349 @@
+//! The `Borrowed` derive, generating `borrowed`.
+
+use quote::{quote, quote_spanned};
+use syn::parse_quote;
+use syn::spanned::Spanned;
+
+use derive_into_owned_core::helpers::{
+    fresh_lifetime, fresh_type_param, generic_arguments, replace_lifetimes, uses_type_param,
+};
+use derive_into_owned_core::{diagnostics, impl_with_generator, BodyGenerator, FieldKind};
+
+use crate::attrs::{BorrowedAttrs, BorrowedFieldAttrs, ContainerAttrs, ParamAttrs};
+use crate::{
+    all_fields, check_lifetime_bounds, check_mut_references, check_union, clones, combine_errors,
+    config, no_std, view, without_param_attrs,
+};
+
+pub fn derive_borrowed(ast: &syn::DeriveInput) -> syn::Result<proc_macro2::TokenStream> {
+    let attrs = ContainerAttrs::parse(&ast.attrs)?;
+    let borrowed_attrs = BorrowedAttrs::parse(&ast.attrs)?;
+
+    check_union(ast, "Borrowed")?;
+
+    // report the errors here, the generator cannot return them
+    for (_, field) in all_fields(ast) {
+        BorrowedFieldAttrs::parse(&field.attrs)?;
+    }
+    check_mut_references(
+        ast,
+        |_| false,
+        "`borrowed` cannot reborrow through a shared reference; hold the data by value or in a \
+         `Cow` instead",
+    )?;
+
+    check_fn_pointers(ast)?;
+    check_lifetime_bounds(ast, "Borrowed", "the lifetime of the borrow")?;
+
+    // only `borrowed` needs the bounds, the view type does not clone anything
+    let bounded = without_param_attrs(&with_clone_bounds(ast, &attrs)?)?;
+    let ast = &without_param_attrs(ast)?;
+
+    // types without lifetime parameters do not borrow anything, they are simply cloned
+    let unchanged = ast.generics.lifetimes().next().is_none()
+        && all_fields(ast).into_iter().all(|(_, field)| {
+            BorrowedFieldAttrs::parse(&field.attrs).map_or(false, |attrs| attrs.as_ref.is_none())
+        });
+
+    // named only where `'_` would not do, and then not clashing with the lifetimes of the type
+    let lifetime = fresh_lifetime(&ast.generics, "b");
+
+    let mut expanded = if unchanged {
+        proc_macro2::TokenStream::new()
+    } else {
+        probes(&bounded, &attrs, &lifetime)?
+    };
+    expanded.extend(impl_with_generator(
+        &bounded,
+        BorrowedGen {
+            attrs: &attrs,
+            unchanged,
+            arguments: generic_arguments(&ast.generics, Some(&lifetime)),
+            lifetime,
+            output: fresh_type_param(&ast.generics, "R"),
+        },
+    ));
+
+    if let Some(ref view) = borrowed_attrs.view {
+        expanded.extend(view::generate(ast, &attrs, view)?);
+    }
+
+    if borrowed_attrs.warn_clones {
+        expanded.extend(clones::warnings(&all_fields(ast), &attrs)?);
+    }
+
+    expanded.extend(config::track());
+
+    if attrs.no_std {
+        expanded = no_std::rewrite(expanded);
+    }
+
+    Ok(expanded)
+}
+
+fn check_fn_pointers(ast: &syn::DeriveInput) -> syn::Result<()> {
+    let errors = all_fields(ast).into_iter().filter_map(|(name, field)| {
+        let bare_fn = derive_into_owned_core::helpers::find_borrowing_fn(&field.ty, false)?;
+        Some(diagnostics::FN_POINTER_FIELD.error(
+            bare_fn,
+            format!(
+                "field `{}` holds the function pointer `{}` taking borrowed arguments, which \
+                 cannot be called with the shorter borrows of `borrowed`; take the arguments for \
+                 any lifetime with `for<'x> fn(&'x ...)` instead",
+                name,
+                quote!(#bare_fn),
+            ),
+        ))
+    });
+
+    combine_errors(errors)
+}
+
+/// `ast` with `T: Clone` added to its where clause for the type parameters used in the fields
+/// which `borrowed` clones, unless marked with `#[into_owned(skip_bounds)]` on the type or the
+/// parameter.
+fn with_clone_bounds(
+    ast: &syn::DeriveInput,
+    attrs: &ContainerAttrs,
+) -> syn::Result<syn::DeriveInput> {
+    let mut cloned = Vec::new();
+    for (_, field) in all_fields(ast) {
+        if BorrowedFieldAttrs::parse(&field.attrs)?.as_ref.is_none() {
+            cloned.extend(FieldKind::cloned_types(&field.ty, &attrs.classifier));
+        }
+    }
+
+    let mut bounds: Vec<syn::WherePredicate> = Vec::new();
+    for param in ast.generics.type_params() {
+        if attrs.skip_bounds || ParamAttrs::parse(&param.attrs)?.skip_bounds {
+            continue;
+        }
+
+        let ident = &param.ident;
+        if cloned.iter().any(|ty| uses_type_param(ty, ident)) {
+            bounds.push(parse_quote! { #ident: ::std::clone::Clone });
+        }
+    }
+
+    let mut ast = ast.clone();
+    if !bounds.is_empty() {
+        let where_clause = ast.generics.make_where_clause();
+        where_clause.predicates.extend(bounds);
+    }
+    Ok(ast)
+}
+
+/// Checks that the "Cow-alike" parts of the fields have a fitting `borrowed` method, reporting
+/// the missing ones at the fields instead of in the generated `borrowed`.
+///
+/// Inherent methods take precedence over trait methods, so the call in the probe only resolves
+/// to the fallback trait, whose method requires an unimplemented trait, when the method is
+/// missing. The unimplemented trait then names the field in its error.
+fn probes(
+    ast: &syn::DeriveInput,
+    attrs: &ContainerAttrs,
+    lifetime: &syn::Lifetime,
+) -> syn::Result<proc_macro2::TokenStream> {
+    let lifetimes = ast
+        .generics
+        .lifetimes()
+        .map(|def| def.lifetime.clone())
+        .collect::<Vec<_>>();
+
+    let mut generics = ast.generics.clone();
+    generics.params.insert(0, parse_quote!(#lifetime));
+    let (impl_generics, _, where_clause) = generics.split_for_impl();
+
+    let mut probes = proc_macro2::TokenStream::new();
+    for (name, field) in all_fields(ast) {
+        if BorrowedFieldAttrs::parse(&field.attrs)?.as_ref.is_some() {
+            continue;
+        }
+
+        for ty in FieldKind::cow_alike_types(&field.ty, &attrs.classifier) {
+            let borrowed = replace_lifetimes(&ty, &lifetimes, lifetime);
+            let span = ty.span().resolved_at(proc_macro2::Span::call_site());
+            let call = quote_spanned!(span=> field.borrowed());
+
+            let on_unimplemented = if attrs.allows_rust(1, 78) {
+                let message = diagnostics::MISSING_BORROWED.message(format_args!(
+                    "field `{}` of `{}` is not Cow-alike, `{{Self}}` has no `borrowed` method",
+                    name, ast.ident
+                ));
+                quote! {
+                    #[diagnostic::on_unimplemented(
+                        message = #message,
+                        label = "`Borrowed` calls `borrowed()` on this field",
+                        note = "the field type needs `fn borrowed<'b>(&'b self) -> {Self}` with \
+                                `'b` in place of its lifetimes, like `#[derive(Borrowed)]` generates"
+                    )]
+                }
+            } else {
+                quote!()
+            };
+
+            probes.extend(quote! {
+                const _: () = {
+                    #on_unimplemented
+                    trait HasBorrowed {}
+                    trait Fallback {
+                        fn borrowed(&self) -> ! where Self: HasBorrowed { loop {} }
+                    }
+                    impl<T: ?::std::marker::Sized> Fallback for T {}
+
+                    #[allow(dead_code)]
+                    fn probe #impl_generics (field: &#lifetime #ty) -> #borrowed #where_clause {
+                        #call
+                    }
+                };
+            });
+        }
+    }
+    Ok(probes)
+}
+
+struct BorrowedGen<'a> {
+    attrs: &'a ContainerAttrs,
+    /// The type has no lifetime parameters, so `borrowed` clones `self`
+    unchanged: bool,
+    /// The lifetime of the borrow in `with_borrowed`, where it cannot be elided
+    lifetime: syn::Lifetime,
+    /// The generic arguments of the type passed on by `with_borrowed`, with `lifetime` in place
+    /// of the lifetime parameters
+    arguments: Vec<proc_macro2::TokenStream>,
+    /// The type parameter for the value returned by the closure of `with_borrowed`
+    output: syn::Ident,
+}
+
+impl BorrowedGen<'_> {
+    /// `var` is expected to be a reference to the field.
+    fn borrow_field(
+        &self,
+        field: &syn::Field,
+        var: &proc_macro2::TokenStream,
+    ) -> proc_macro2::TokenStream {
+        let field_attrs =
+            BorrowedFieldAttrs::parse(&field.attrs).expect("field attributes were checked already");
+
+        match field_attrs.as_ref {
+            Some(Some(target)) => quote! {
+                ::std::convert::From::from(<_ as ::std::convert::AsRef<#target>>::as_ref(#var))
+            },
+            Some(None) => quote! {
+                ::std::convert::From::from(::std::convert::AsRef::as_ref(#var))
+            },
+            None => FieldKind::resolve(&field.ty, &self.attrs.classifier).borrow_or_clone(var),
+        }
+    }
+}
+
+impl BodyGenerator for BorrowedGen<'_> {
+    fn quote_rhs_params(&self, ast: &syn::DeriveInput) -> Vec<proc_macro2::TokenStream> {
+        generic_arguments(&ast.generics, Some(&parse_quote!('_)))
+    }
+
+    fn visit_struct(&self, data: &syn::DataStruct) -> proc_macro2::TokenStream {
+        match data.fields {
+            syn::Fields::Named(_) => {
+                let fields = data.fields.iter().map(|field| {
+                    let ident = field.ident.as_ref().expect("this fields has no ident (4)");
+                    let field_ref = quote! { (&self.#ident) };
+                    let code = self.borrow_field(field, &field_ref);
+                    quote! { #ident: #code }
+                });
+                quote! { { #(#fields),* } }
+            }
+            syn::Fields::Unnamed(_) => {
+                let fields = data.fields.iter().enumerate().map(|(index, field)| {
+                    let index = syn::Index::from(index);
+                    self.borrow_field(field, &quote! { (&self.#index) })
+                });
+                quote! { ( #(#fields),* ) }
+            }
+            syn::Fields::Unit => quote! {},
+        }
+    }
+
+    fn visit_enum_data(
+        &self,
+        ident: proc_macro2::TokenStream,
+        variant: &syn::Variant,
+    ) -> proc_macro2::TokenStream {
+        if variant.fields.is_empty() {
+            return quote!(#ident => #ident);
+        }
+
+        let fields_are_named = variant.fields.iter().any(|field| field.ident.is_some());
+        if fields_are_named {
+            let idents = variant
+                .fields
+                .iter()
+                .map(|field| field.ident.as_ref().expect("this fields has no ident (5)"));
+            let cloned = variant.fields.iter().map(|field| {
+                let ident = field.ident.as_ref().expect("this fields has no ident (6)");
+                let ident = quote! { #ident };
+                let code = self.borrow_field(field, &ident);
+                quote! { #ident: #code }
+            });
+            quote! { #ident { #(ref #idents),* } => #ident { #(#cloned),* } }
+        } else {
+            let idents = (0..variant.fields.len())
+                .map(|index| quote::format_ident!("x{}", index))
+                .collect::<Vec<_>>();
+            let cloned = idents
+                .iter()
+                .zip(variant.fields.iter())
+                .map(|(ident, field)| self.borrow_field(field, &quote! { #ident }))
+                .collect::<Vec<_>>();
+            quote! { #ident ( #(ref #idents),* ) => #ident ( #(#cloned),* ) }
+        }
+    }
+
+    fn visit_empty_enum(&self) -> proc_macro2::TokenStream {
+        quote! { match *self {} }
+    }
+
+    fn visit_union(&self, _data: &syn::DataUnion) -> proc_macro2::TokenStream {
+        quote! { *self }
+    }
+
+    fn combine_impl(
+        &self,
+        borrowed: proc_macro2::TokenStream,
+        name: &syn::Ident,
+        params: proc_macro2::TokenStream,
+        where_clause: proc_macro2::TokenStream,
+        owned: proc_macro2::TokenStream,
+        body: proc_macro2::TokenStream,
+    ) -> proc_macro2::TokenStream {
+        let body = if self.unchanged {
+            quote! { ::std::clone::Clone::clone(self) }
+        } else {
+            body
+        };
+
+        let lifetime = &self.lifetime;
+        let output = &self.output;
+        let arguments = &self.arguments;
+        let named = if arguments.is_empty() {
+            quote! {}
+        } else {
+            quote! { < #(#arguments),* > }
+        };
+
+        quote! {
+            impl #borrowed #name #params #where_clause {
+                /// Returns a clone of `self` that shares all the "Cow-alike" data with `self`.
+                pub fn borrowed(&self) -> #name #owned { #body }
+
+                /// Passes the value returned by `borrowed` to `f`, without naming its lifetime.
+                pub fn with_borrowed<#lifetime, #output>(
+                    &#lifetime self,
+                    f: impl ::std::ops::FnOnce(#name #named) -> #output,
+                ) -> #output {
+                    f(self.borrowed())
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,57 @@
+//! The warnings enabled with `#[borrowed(warn_clones)]`.
+
+use quote::{format_ident, quote, quote_spanned};
+use syn::spanned::Spanned;
+
+use derive_into_owned_core::diagnostics;
+use derive_into_owned_core::helpers::is_heap_allocated;
+use derive_into_owned_core::FieldKind;
+
+use crate::attrs::{BorrowedFieldAttrs, ContainerAttrs};
+
+/// Warns about the fields which `borrowed` deep clones even though they allocate, unless marked
+/// with `#[borrowed(clone)]`.
+///
+/// Proc macros cannot emit warnings directly, so this uses a deprecated item instead.
+pub fn warnings(
+    fields: &[(String, &syn::Field)],
+    attrs: &ContainerAttrs,
+) -> syn::Result<proc_macro2::TokenStream> {
+    let mut warnings = quote! {};
+
+    for (index, (name, field)) in fields.iter().enumerate() {
+        let field_attrs = BorrowedFieldAttrs::parse(&field.attrs)?;
+        if field_attrs.clone || field_attrs.as_ref.is_some() {
+            continue;
+        }
+
+        let kind = FieldKind::resolve(&field.ty, &attrs.classifier);
+        let is_cloned = matches!(kind, FieldKind::JustMoved | FieldKind::DashMap(..));
+        if !is_cloned || !is_heap_allocated(&field.ty) {
+            continue;
+        }
+
+        let ty = &field.ty;
+        let note = diagnostics::CLONED_FIELD.message(format_args!(
+            "field `{}` of type `{}` is deep cloned by `borrowed()`, consider using a `Cow` or \
+             accept the cost with #[borrowed(clone)]",
+            name,
+            quote!(#ty)
+        ));
+        // the use of the deprecated item needs to point to the field, uses within the derive
+        // expansion itself are not warned about
+        let span = field.ty.span();
+        let warning = format_ident!("field_is_cloned_{}", index, span = span);
+
+        warnings.extend(quote_spanned! {span=>
+            const _: () = {
+                #[deprecated(note = #note)]
+                #[allow(non_camel_case_types)]
+                struct #warning;
+                let _ = #warning;
+            };
+        });
+    }
+
+    Ok(warnings)
+}
@@ -0,0 +1,113 @@
+//! Project wide configuration read from `derive-into-owned.toml`, enabled with the `config`
+//! feature. Without the feature there is never any configuration.
+//!
+//! The file is looked up from the directory of the crate being compiled, or from the path given
+//! in the `DERIVE_INTO_OWNED_CONFIG` environment variable:
+//!
+//! ```toml
+//! [known]
+//! "my_crate::Span" = "into_owned"
+//! "other::Token" = "to_static"
+//! ```
+
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use quote::quote;
+
+use derive_into_owned_core::KnownType;
+
+fn config_path() -> Option<PathBuf> {
+    if !cfg!(feature = "config") {
+        return None;
+    }
+
+    let path = match std::env::var_os("DERIVE_INTO_OWNED_CONFIG") {
+        Some(path) => PathBuf::from(path),
+        None => {
+            PathBuf::from(std::env::var_os("CARGO_MANIFEST_DIR")?).join("derive-into-owned.toml")
+        }
+    };
+
+    if path.is_file() {
+        Some(path)
+    } else {
+        None
+    }
+}
+
+/// The types listed in the `[known]` table of the configuration file, if any.
+pub fn known_types() -> syn::Result<Vec<KnownType>> {
+    match config_path() {
+        Some(path) => parse_known_types(&path),
+        None => Ok(Vec::new()),
+    }
+}
+
+#[cfg(not(feature = "config"))]
+fn parse_known_types(_path: &Path) -> syn::Result<Vec<KnownType>> {
+    Ok(Vec::new())
+}
+
+#[cfg(feature = "config")]
+fn parse_known_types(path: &Path) -> syn::Result<Vec<KnownType>> {
+    let error = |msg: String| {
+        syn::Error::new(
+            proc_macro2::Span::call_site(),
+            derive_into_owned_core::diagnostics::CONFIG.message(format_args!(
+                "{}: {}",
+                path.display(),
+                msg
+            )),
+        )
+    };
+
+    let contents = std::fs::read_to_string(path).map_err(|e| error(e.to_string()))?;
+    let config = contents
+        .parse::<toml::Value>()
+        .map_err(|e| error(e.to_string()))?;
+
+    let known = match config.get("known") {
+        Some(toml::Value::Table(known)) => known,
+        Some(_) => return Err(error(String::from("expected `known` to be a table"))),
+        None => return Ok(Vec::new()),
+    };
+
+    known
+        .iter()
+        .map(|(path, method)| {
+            let method = method
+                .as_str()
+                .ok_or_else(|| error(format!("expected a method name for `{}`", path)))?;
+
+            Ok(KnownType {
+                path: syn::parse_str(path).map_err(|e| error(e.to_string()))?,
+                method: syn::parse_str(method).map_err(|e| error(e.to_string()))?,
+            })
+        })
+        .collect()
+}
+
+/// Whether [`track`] has emitted the tracking items already.
+static TRACKED: AtomicBool = AtomicBool::new(false);
+
+/// Makes cargo rebuild the deriving crate when the configuration file or the
+/// `DERIVE_INTO_OWNED_CONFIG` environment variable changes.
+///
+/// rustc compiles each crate in a process of its own, so only the first expansion of the process
+/// emits the items instead of repeating them for every derived type.
+pub fn track() -> proc_macro2::TokenStream {
+    if !cfg!(feature = "config") || TRACKED.swap(true, Ordering::Relaxed) {
+        return quote! {};
+    }
+
+    let file = match config_path().and_then(|path| path.to_str().map(String::from)) {
+        Some(path) => quote! { const _: &[u8] = include_bytes!(#path); },
+        None => quote! {},
+    };
+
+    quote! {
+        #file
+        const _: ::core::option::Option<&str> = ::core::option_env!("DERIVE_INTO_OWNED_CONFIG");
+    }
+}
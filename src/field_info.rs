@@ -0,0 +1,89 @@
+//! Generation of the `BORROW_FIELDS` table enabled with `#[into_owned(field_info)]`.
+
+use quote::quote;
+use syn::ext::IdentExt;
+
+use derive_into_owned_core::{diagnostics, FieldKind};
+
+use crate::attrs::{ContainerAttrs, FieldAttrs};
+
+/// Generates `const BORROW_FIELDS: &'static [FieldInfo]` describing every field which
+/// `into_owned` converts.
+pub fn generate(
+    ast: &syn::DeriveInput,
+    attrs: &ContainerAttrs,
+) -> syn::Result<proc_macro2::TokenStream> {
+    let mut infos = Vec::new();
+
+    match ast.data {
+        syn::Data::Struct(ref data) => {
+            for (index, field) in data.fields.iter().enumerate() {
+                infos.extend(field_info(None, index, field, attrs)?);
+            }
+        }
+        syn::Data::Enum(ref data) => {
+            for variant in &data.variants {
+                let name = variant.ident.unraw().to_string();
+                for (index, field) in variant.fields.iter().enumerate() {
+                    infos.extend(field_info(Some(&name), index, field, attrs)?);
+                }
+            }
+        }
+        syn::Data::Union(_) => {
+            return Err(diagnostics::UNSUPPORTED_SHAPE.error(
+                &ast.ident,
+                "field info can only be generated for structs and enums",
+            ))
+        }
+    }
+
+    let name = &ast.ident;
+    let (impl_generics, ty_generics, where_clause) = ast.generics.split_for_impl();
+
+    Ok(quote! {
+        impl #impl_generics #name #ty_generics #where_clause {
+            /// The fields which `into_owned` converts, with how they are classified.
+            pub const BORROW_FIELDS: &'static [::derive_into_owned_core::FieldInfo] = &[
+                #(#infos),*
+            ];
+        }
+    })
+}
+
+fn field_info(
+    variant: Option<&str>,
+    index: usize,
+    field: &syn::Field,
+    attrs: &ContainerAttrs,
+) -> syn::Result<Option<proc_macro2::TokenStream>> {
+    let field_attrs = FieldAttrs::parse(&field.attrs)?;
+    let (kind, nesting) = if field_attrs.with.is_some() {
+        (String::from("with"), 0)
+    } else if field_attrs.skip {
+        (String::from("skip"), 0)
+    } else {
+        let kind = FieldKind::resolve(&field.ty, &attrs.classifier);
+        if kind == derive_into_owned_core::FieldKind::JustMoved {
+            return Ok(None);
+        }
+        (kind.to_string(), kind.nesting())
+    };
+
+    let name = match field.ident {
+        Some(ref ident) => ident.unraw().to_string(),
+        None => index.to_string(),
+    };
+    let variant = match variant {
+        Some(variant) => quote! { ::std::option::Option::Some(#variant) },
+        None => quote! { ::std::option::Option::None },
+    };
+
+    Ok(Some(quote! {
+        ::derive_into_owned_core::FieldInfo {
+            variant: #variant,
+            name: #name,
+            kind: #kind,
+            nesting: #nesting,
+        }
+    }))
+}
@@ -0,0 +1,959 @@
+//! The `IntoOwned` derive, generating `into_owned` and the optional extras configured with
+//! `#[into_owned(...)]`.
+
+use quote::{format_ident, quote, quote_spanned};
+use syn::parse_quote;
+use syn::spanned::Spanned;
+
+use derive_into_owned_core::helpers::{
+    fresh_type_param, hygienic, replace_lifetimes, without_lifetimes,
+};
+use derive_into_owned_core::{
+    diagnostics, impl_with_generator, BodyGenerator, FieldKind, Strategy,
+};
+
+use crate::attrs::{ContainerAttrs, FieldAttrs};
+use crate::{
+    all_fields, check_lifetime_bounds, check_mut_references, check_union, combine_errors, config,
+    field_info, mirror, no_std, ownership, without_param_attrs, yoke,
+};
+
+pub fn derive_into_owned(input: &syn::DeriveInput) -> syn::Result<proc_macro2::TokenStream> {
+    let ast = &without_param_attrs(input)?;
+    let attrs = ContainerAttrs::parse(&ast.attrs)?;
+
+    check_union(ast, "IntoOwned")?;
+
+    check_expectations(ast, &attrs)?;
+    check_mut_references(
+        ast,
+        has_conversion,
+        "cannot be cloned or owned; leave it out with #[into_owned(skip)], convert it with \
+         #[into_owned(with = \"...\")] or hold the data by value or in a `Cow` instead",
+    )?;
+
+    check_trait_objects(ast)?;
+    check_fn_pointers(ast)?;
+
+    if attrs.mirror.is_none() {
+        check_no_mirror_fields(ast)?;
+    }
+
+    if attrs.transparent {
+        check_transparent(ast)?;
+    }
+
+    if attrs.try_into_owned && !matches!(ast.data, syn::Data::Enum(_)) {
+        return Err(diagnostics::INAPPLICABLE_OPTION
+            .error(&ast.ident, "`try_into_owned` can only be used for enums"));
+    }
+
+    // only the mirror type is generated for types holding references
+    if attrs.mirror.is_none() || !has_references(ast, &attrs) {
+        check_lifetime_bounds(ast, "IntoOwned", "`'static`")?;
+    }
+
+    // the conversions need the extra bounds but the other generated items do not
+    let bounded = with_bounds(ast, &attrs);
+
+    let mut expanded = if attrs.leak {
+        impl_with_generator(
+            &bounded,
+            IntoOwnedGen {
+                attrs: &attrs,
+                vis: &ast.vis,
+                generics: &ast.generics,
+                strategy: Strategy::Leak,
+                constness: false,
+                fallible: false,
+                unchanged: false,
+            },
+        )
+    } else if attrs.try_into_owned {
+        impl_with_generator(
+            &bounded,
+            IntoOwnedGen {
+                attrs: &attrs,
+                vis: &ast.vis,
+                generics: &ast.generics,
+                strategy: Strategy::IntoOwned,
+                constness: false,
+                fallible: true,
+                unchanged: false,
+            },
+        )
+    } else if attrs.mirror.is_some() && has_references(ast, &attrs) {
+        // `&'a str` and friends cannot be turned into `'static`, such types only get the mirror
+        quote! {}
+    } else {
+        check_no_references(ast, &attrs)?;
+        if attrs.strict {
+            check_fallbacks(ast, &attrs)?;
+        }
+        let mut expanded = impl_with_generator(
+            &bounded,
+            IntoOwnedGen {
+                attrs: &attrs,
+                vis: &ast.vis,
+                generics: &ast.generics,
+                strategy: Strategy::IntoOwned,
+                constness: is_trivial(ast, &attrs)
+                    || (is_unchanged(ast) && attrs.allows_rust(1, 61)),
+                fallible: false,
+                unchanged: is_unchanged(ast),
+            },
+        );
+        if attrs.intern {
+            let interner = hygienic("interner");
+            expanded.extend(impl_with_generator(
+                &bounded,
+                IntoOwnedGen {
+                    attrs: &attrs,
+                    vis: &ast.vis,
+                    generics: &ast.generics,
+                    strategy: Strategy::Intern(quote! { #interner }),
+                    constness: false,
+                    fallible: false,
+                    unchanged: false,
+                },
+            ));
+        }
+        if attrs.shrink {
+            expanded.extend(impl_with_generator(
+                &bounded,
+                IntoOwnedGen {
+                    attrs: &attrs,
+                    vis: &ast.vis,
+                    generics: &ast.generics,
+                    strategy: Strategy::Shrink,
+                    constness: false,
+                    fallible: false,
+                    unchanged: false,
+                },
+            ));
+        }
+        expanded
+    };
+
+    if attrs.map_cows {
+        let f = hygienic("f");
+        expanded.extend(impl_with_generator(
+            &bounded,
+            IntoOwnedGen {
+                attrs: &attrs,
+                vis: &ast.vis,
+                generics: &ast.generics,
+                strategy: Strategy::MapCows(quote! { #f }),
+                constness: false,
+                fallible: false,
+                unchanged: false,
+            },
+        ));
+    }
+
+    if let Some(ref mirror) = attrs.mirror {
+        expanded.extend(mirror::generate(ast, &attrs, mirror)?);
+    }
+
+    if attrs.ownership {
+        expanded.extend(ownership::generate(ast, &attrs)?);
+    }
+
+    if attrs.borrowed_strs {
+        expanded.extend(ownership::generate_borrowed_strs(ast, &attrs)?);
+    }
+
+    if attrs.try_reborrow_original {
+        expanded.extend(ownership::generate_try_reborrow(input, &attrs)?);
+    }
+
+    if attrs.field_info {
+        expanded.extend(field_info::generate(ast, &attrs)?);
+    }
+
+    if attrs.assert_send_sync {
+        expanded.extend(send_sync_assertions(ast));
+    }
+
+    if attrs.yoke {
+        expanded.extend(yoke::generate(ast)?);
+    }
+
+    if attrs.self_owned {
+        expanded.extend(yoke::generate_self_owned(ast)?);
+    }
+
+    expanded.extend(config::track());
+
+    if attrs.no_std {
+        expanded = no_std::rewrite(expanded);
+    }
+
+    Ok(expanded)
+}
+
+/// Asserts that the fields of `Foo<'static>` are `Send + Sync + 'static` for
+/// `#[into_owned(assert_send_sync)]`, given type parameters which are. The assertions are spanned
+/// at the field types so that the errors point at the offending fields instead of at the
+/// `tokio::spawn` far away.
+fn send_sync_assertions(ast: &syn::DeriveInput) -> proc_macro2::TokenStream {
+    let lifetimes = ast
+        .generics
+        .lifetimes()
+        .map(|def| def.lifetime.clone())
+        .collect::<Vec<_>>();
+    let lifetime = syn::Lifetime::new("'static", proc_macro2::Span::call_site());
+
+    let mut generics = without_lifetimes(&ast.generics);
+    for param in generics.type_params_mut() {
+        param.bounds.push(parse_quote!(::std::marker::Send));
+        param.bounds.push(parse_quote!(::std::marker::Sync));
+        param.bounds.push(parse_quote!('static));
+    }
+    let (impl_generics, _, where_clause) = generics.split_for_impl();
+
+    let assertions = all_fields(ast).into_iter().map(|(_, field)| {
+        let ty = replace_lifetimes(&field.ty, &lifetimes, &lifetime);
+        let span = field.ty.span().resolved_at(proc_macro2::Span::call_site());
+        quote_spanned!(span=> assert_send_sync::<#ty>();)
+    });
+
+    quote! {
+        const _: () = {
+            fn assert_send_sync<T: ?::std::marker::Sized + ::std::marker::Send + ::std::marker::Sync + 'static>() {}
+
+            #[allow(dead_code)]
+            fn assert_fields #impl_generics () #where_clause {
+                #(#assertions)*
+            }
+        };
+    }
+}
+
+/// `ast` with the predicates of `#[into_owned(bound = "...")]` added to its where clause.
+fn with_bounds(ast: &syn::DeriveInput, attrs: &ContainerAttrs) -> syn::DeriveInput {
+    let mut ast = ast.clone();
+    if !attrs.bounds.is_empty() {
+        let where_clause = ast.generics.make_where_clause();
+        where_clause.predicates.extend(attrs.bounds.iter().cloned());
+    }
+    ast
+}
+
+/// Whether the field is converted with `#[into_owned(with = "...")]` or replaced with
+/// `#[into_owned(skip)]` instead of converted by its type.
+fn has_conversion(field: &syn::Field) -> bool {
+    FieldAttrs::parse(&field.attrs).map_or(false, |attrs| attrs.with.is_some() || attrs.skip)
+}
+
+fn has_references(ast: &syn::DeriveInput, attrs: &ContainerAttrs) -> bool {
+    all_fields(ast)
+        .into_iter()
+        .any(|(_, field)| field_has_references(field, attrs))
+}
+
+fn field_has_references(field: &syn::Field, attrs: &ContainerAttrs) -> bool {
+    !has_conversion(field) && FieldKind::resolve(&field.ty, &attrs.classifier).has_references()
+}
+
+/// Whether the type has no lifetime parameters and no fields with their own conversions, so that
+/// it is `'static` already and `into_owned` can return it as it is. Generated code often derives
+/// for every type, including the ones which never borrow.
+fn is_unchanged(ast: &syn::DeriveInput) -> bool {
+    ast.generics.lifetimes().next().is_none()
+        && all_fields(ast)
+            .into_iter()
+            .all(|(_, field)| !has_conversion(field))
+}
+
+/// Whether converting the type only moves the fields, which is possible in a `const fn`.
+fn is_trivial(ast: &syn::DeriveInput, attrs: &ContainerAttrs) -> bool {
+    // bounds on the type parameters of a `const fn` need Rust 1.61
+    attrs.allows_rust(1, 61)
+        && all_fields(ast).into_iter().all(|(_, field)| {
+            !has_conversion(field)
+                && FieldKind::resolve(&field.ty, &attrs.classifier) == FieldKind::JustMoved
+        })
+}
+
+/// Errors for all the fields which are not classified as given with
+/// `#[into_owned(expect = "...")]`.
+fn check_expectations(ast: &syn::DeriveInput, attrs: &ContainerAttrs) -> syn::Result<()> {
+    let errors = all_fields(ast)
+        .into_iter()
+        .map(|(name, field)| {
+            let expected = match FieldAttrs::parse(&field.attrs)?.expect {
+                Some(expected) => expected,
+                None => return Ok(()),
+            };
+
+            let actual = FieldKind::resolve(&field.ty, &attrs.classifier).to_string();
+            let normalized = expected
+                .value()
+                .chars()
+                .filter(|c| !c.is_whitespace())
+                .collect::<String>();
+
+            if normalized == actual.replace(' ', "") {
+                Ok(())
+            } else {
+                Err(diagnostics::UNEXPECTED_KIND.error(
+                    &expected,
+                    format!(
+                        "field `{}` was expected to be `{}` but it is `{}`",
+                        name,
+                        expected.value(),
+                        actual
+                    ),
+                ))
+            }
+        })
+        .filter_map(Result::err);
+
+    combine_errors(errors)
+}
+
+/// Errors unless the type is a struct with a single field, for `#[into_owned(transparent)]`.
+fn check_transparent(ast: &syn::DeriveInput) -> syn::Result<()> {
+    match ast.data {
+        syn::Data::Struct(ref data) if data.fields.len() == 1 => Ok(()),
+        _ => Err(diagnostics::INAPPLICABLE_OPTION.error(
+            &ast.ident,
+            "`transparent` can only be used for structs with a single field",
+        )),
+    }
+}
+
+/// Errors for the field options of a mirror type which is not generated.
+fn check_no_mirror_fields(ast: &syn::DeriveInput) -> syn::Result<()> {
+    let errors = all_fields(ast).into_iter().filter_map(|(name, field)| {
+        FieldAttrs::parse(&field.attrs).ok()?.mirror?;
+        Some(diagnostics::MIRROR_FIELD_WITHOUT_MIRROR.error(
+            field,
+            format!(
+                "field `{}` has mirror options but the type has no `#[into_owned(mirror)]`",
+                name
+            ),
+        ))
+    });
+
+    combine_errors(errors)
+}
+
+/// Errors for the fields holding trait objects with lifetimes, like `Arc<dyn Visitor<'a> + 'a>`,
+/// which are not converted with `clone_box` or another conversion. The field would otherwise be
+/// moved as is and fail to compile with an error about lifetimes.
+fn check_trait_objects(ast: &syn::DeriveInput) -> syn::Result<()> {
+    let errors = all_fields(ast).into_iter().filter_map(|(name, field)| {
+        if has_conversion(field) {
+            return None;
+        }
+        let object = derive_into_owned_core::helpers::find_borrowing_trait_object(&field.ty)?;
+        Some(diagnostics::TRAIT_OBJECT_FIELD.error(
+            object,
+            format!(
+                "field `{}` holds the trait object `{}`, which `into_owned` cannot make \
+                 `'static`; name a method cloning it into a `Box<dyn ... + 'static>` with \
+                 #[into_owned(clone_box = \"...\")] or convert it with #[into_owned(with = \"...\")]",
+                name,
+                quote!(#object),
+            ),
+        ))
+    });
+
+    combine_errors(errors)
+}
+
+fn check_fn_pointers(ast: &syn::DeriveInput) -> syn::Result<()> {
+    let errors = all_fields(ast).into_iter().filter_map(|(name, field)| {
+        if has_conversion(field) {
+            return None;
+        }
+        let bare_fn = derive_into_owned_core::helpers::find_borrowing_fn(&field.ty, true)?;
+        Some(diagnostics::FN_POINTER_FIELD.error(
+            bare_fn,
+            format!(
+                "field `{}` holds the function pointer `{}` returning borrowed data, which \
+                 `into_owned` cannot make `'static`; convert it with \
+                 #[into_owned(with = \"...\")] or leave it out with #[into_owned(skip)]",
+                name,
+                quote!(#bare_fn),
+            ),
+        ))
+    });
+
+    combine_errors(errors)
+}
+
+/// Errors for the fields holding plain references, which `into_owned` cannot make `'static`,
+/// pointing at the reference within the field type where there is one.
+fn check_no_references(ast: &syn::DeriveInput, attrs: &ContainerAttrs) -> syn::Result<()> {
+    let errors = all_fields(ast).into_iter().filter_map(|(name, field)| {
+        if !field_has_references(field, attrs) {
+            return None;
+        }
+        let error = match derive_into_owned_core::helpers::find_reference(&field.ty) {
+            Some(reference) => diagnostics::REFERENCE_FIELD.error(
+                reference,
+                format!(
+                    "field `{}` holds the plain reference `{}`, which `into_owned` cannot make \
+                     `'static`; generate an owned mirror type with #[into_owned(mirror)], \
+                     leak it with #[into_owned(leak)] or use a `Cow` instead",
+                    name,
+                    quote!(#reference),
+                ),
+            ),
+            None => diagnostics::ARENA_FIELD.error(
+                &field.ty,
+                format!(
+                    "field `{}` borrows an arena, which `into_owned` cannot make `'static`; \
+                     generate an owned mirror type with #[into_owned(mirror)] instead",
+                    name,
+                ),
+            ),
+        };
+        Some(error)
+    });
+
+    combine_errors(errors)
+}
+
+/// Errors for all the fields which would be moved as is, for `#[into_owned(strict)]`.
+fn check_fallbacks(ast: &syn::DeriveInput, attrs: &ContainerAttrs) -> syn::Result<()> {
+    let errors = all_fields(ast).into_iter().filter_map(|(name, field)| {
+        if has_conversion(field) {
+            return None;
+        }
+        let fallback = FieldKind::find_fallback(&field.ty, &attrs.classifier)?;
+        let msg = match fallback {
+            syn::Type::Path(ref p) => {
+                let path = p.path.segments.iter().map(|segment| &segment.ident);
+                format!(
+                    "field `{}` would be moved as is: `{}` is not known to be convertible, \
+                     list `{}` with #[into_owned(known(...))]",
+                    name,
+                    quote!(#fallback),
+                    quote!(#(#path)::*),
+                )
+            }
+            _ => format!(
+                "field `{}` would be moved as is: `{}` cannot be converted into `'static`",
+                name,
+                quote!(#fallback),
+            ),
+        };
+        Some(diagnostics::MOVED_AS_IS.error(&field.ty, msg))
+    });
+
+    combine_errors(errors)
+}
+
+struct IntoOwnedGen<'a> {
+    attrs: &'a ContainerAttrs,
+    /// The visibility of the type, used for the free function
+    vis: &'a syn::Visibility,
+    /// The generics of the type, which the type parameters of the generated methods avoid
+    generics: &'a syn::Generics,
+    /// `IntoOwned` generates `into_owned`, `Leak` generates `leaked`, `Intern` generates
+    /// `into_owned_interned` and `Shrink` generates `into_owned_and_shrink`
+    strategy: Strategy,
+    /// All fields are moved as is, so `into_owned` can be a `const fn`
+    constness: bool,
+    /// Generates `try_into_owned` instead, which returns the variants holding plain references
+    /// back as errors
+    fallible: bool,
+    /// The type has no lifetime parameters, so `self` is returned as it is
+    unchanged: bool,
+}
+
+impl IntoOwnedGen<'_> {
+    /// `var` is expected to be the field itself.
+    fn convert_field(
+        &self,
+        field: &syn::Field,
+        var: &proc_macro2::TokenStream,
+    ) -> proc_macro2::TokenStream {
+        let field_attrs =
+            FieldAttrs::parse(&field.attrs).expect("field attributes were checked already");
+
+        match (field_attrs.with, &self.strategy) {
+            // the type stays the same
+            (_, Strategy::MapCows(_)) => FieldKind::resolve(&field.ty, &self.attrs.classifier)
+                .walk_by_value(var, &self.strategy),
+            (Some(with), _) => quote! { (#with)(#var) },
+            (None, _) if field_attrs.skip => quote! {
+                {
+                    // enum variants bind the field either way
+                    let _ = #var;
+                    ::std::default::Default::default()
+                }
+            },
+            (None, _) => FieldKind::resolve(&field.ty, &self.attrs.classifier)
+                .walk_by_value(var, &self.strategy),
+        }
+    }
+}
+
+impl BodyGenerator for IntoOwnedGen<'_> {
+    fn visit_struct(&self, data: &syn::DataStruct) -> proc_macro2::TokenStream {
+        // Helper ternary to avoid Option<bool>
+        enum Fields {
+            Named,
+            Tuple,
+            Unit,
+        }
+
+        use Fields::*;
+
+        let fields_kind = data
+            .fields
+            .iter()
+            .next()
+            .map(|field| if field.ident.is_some() { Named } else { Tuple })
+            .unwrap_or(Unit);
+
+        match fields_kind {
+            Named => {
+                let fields = data.fields.iter().map(|field| {
+                    let ident = field.ident.as_ref().expect("unexpected unnamed field");
+                    let field_ref = quote! { self.#ident };
+                    let code = self.convert_field(field, &field_ref);
+                    quote! { #ident: #code }
+                });
+                quote! { { #(#fields),* } }
+            }
+            Tuple => {
+                let fields = data.fields.iter().enumerate().map(|(index, field)| {
+                    let index = syn::Index::from(index);
+                    let index = quote! { self.#index };
+                    self.convert_field(field, &index)
+                });
+                quote! { ( #(#fields),* ) }
+            }
+            Unit => {
+                quote! {}
+            }
+        }
+    }
+
+    fn visit_enum_data(
+        &self,
+        ident: proc_macro2::TokenStream,
+        variant: &syn::Variant,
+    ) -> proc_macro2::TokenStream {
+        let ok = |value: proc_macro2::TokenStream| {
+            if self.fallible {
+                quote! { ::std::result::Result::Ok(#value) }
+            } else {
+                value
+            }
+        };
+
+        if self.fallible
+            && variant
+                .fields
+                .iter()
+                .any(|field| field_has_references(field, self.attrs))
+        {
+            return quote! { value @ #ident { .. } => ::std::result::Result::Err(value) };
+        }
+
+        if variant.fields.is_empty() {
+            let value = ok(quote! { #ident });
+            return quote!(#ident => #value);
+        }
+
+        let fields_are_named = variant.fields.iter().any(|field| field.ident.is_some());
+
+        if fields_are_named {
+            let named_fields = variant
+                .fields
+                .iter()
+                .filter_map(|field| field.ident.as_ref());
+
+            let cloned = variant.fields.iter().map(|field| {
+                let ident = field.ident.as_ref().unwrap();
+                let ident = quote!(#ident);
+                let code = self.convert_field(field, &ident);
+                quote! { #ident: #code }
+            });
+            let value = ok(quote! { #ident { #(#cloned),* } });
+            quote! { #ident { #(#named_fields),* } => #value }
+        } else {
+            let unnamed_fields = &variant
+                .fields
+                .iter()
+                .enumerate()
+                .filter(|(_, field)| field.ident.is_none())
+                .map(|(index, _)| format_ident!("x{}", index))
+                .collect::<Vec<_>>();
+
+            let cloned = unnamed_fields
+                .iter()
+                .zip(variant.fields.iter())
+                .map(|(ident, field)| {
+                    let ident = quote! { #ident };
+                    self.convert_field(field, &ident)
+                })
+                .collect::<Vec<_>>();
+
+            let value = ok(quote! { #ident ( #(#cloned),* ) });
+            quote! { #ident ( #(#unnamed_fields),* ) => #value }
+        }
+    }
+
+    fn combine_impl(
+        &self,
+        borrowed: proc_macro2::TokenStream,
+        name: &syn::Ident,
+        params: proc_macro2::TokenStream,
+        where_clause: proc_macro2::TokenStream,
+        owned: proc_macro2::TokenStream,
+        body: proc_macro2::TokenStream,
+    ) -> proc_macro2::TokenStream {
+        match self.strategy {
+            Strategy::Leak => {
+                return quote! {
+                    impl #borrowed #name #params #where_clause {
+                        /// Returns a `'static` version of `self` by leaking all the borrowed
+                        /// data, meant for values which live until the end of the process anyway.
+                        pub fn leaked(self) -> #name #owned { #body }
+                    }
+                };
+            }
+            Strategy::Intern(ref interner) => {
+                let param = fresh_type_param(self.generics, "I");
+
+                return quote! {
+                    impl #borrowed #name #params #where_clause {
+                        /// Like `into_owned` but `Cow<str>` fields borrow the strings returned by
+                        /// `interner` instead of owning copies of them.
+                        pub fn into_owned_interned<#param>(self, #interner: &mut #param) -> #name #owned
+                        where
+                            #param: ?::std::marker::Sized + ::derive_into_owned_core::Interner,
+                        {
+                            #body
+                        }
+                    }
+                };
+            }
+            Strategy::Shrink => {
+                return quote! {
+                    impl #borrowed #name #params #where_clause {
+                        /// Like `into_owned` but the owned strings and vectors are shrunk to fit,
+                        /// so that long lived values do not keep the spare capacity of the
+                        /// buffers they were parsed into.
+                        pub fn into_owned_and_shrink(self) -> #name #owned { #body }
+                    }
+                };
+            }
+            Strategy::MapCows(ref f) => {
+                let param = fresh_type_param(self.generics, "F");
+
+                return quote! {
+                    impl #borrowed #name #params #where_clause {
+                        /// Returns `self` with every `Cow<str>` replaced by what `f` returns for
+                        /// it, including the ones within options, collections and nested types.
+                        pub fn map_cows<#param>(self, mut #f: #param) -> Self
+                        where
+                            #param: for<'c> ::std::ops::FnMut(
+                                ::std::borrow::Cow<'c, str>,
+                            ) -> ::std::borrow::Cow<'c, str>,
+                        {
+                            let _ = &mut #f;
+                            #body
+                        }
+                    }
+                };
+            }
+            _ => {}
+        }
+
+        let body = if self.unchanged {
+            quote! { self }
+        } else {
+            body
+        };
+
+        if self.fallible {
+            return quote! {
+                impl #borrowed #name #params #where_clause {
+                    /// Returns a version of `self` with all fields converted to owning versions,
+                    /// or `self` as the error for the variants holding plain references which
+                    /// cannot be converted.
+                    pub fn try_into_owned(
+                        self,
+                    ) -> ::std::result::Result<#name #owned, Self> {
+                        #body
+                    }
+                }
+            };
+        }
+
+        let constness = if self.constness {
+            quote! { const }
+        } else {
+            quote! {}
+        };
+
+        let free_fn = self.attrs.free_fn.as_ref().map(|free_fn| {
+            free_fn
+                .clone()
+                .unwrap_or_else(|| format_ident!("{}_into_owned", snake_case(&name.to_string())))
+        });
+
+        let into_owned_trait = if self.attrs.trait_impl {
+            // unlike the return type of a method, the associated type gets no implied bounds, so
+            // the type parameters outliving `'a` need to be `'static` for `Foo<'static, T>`
+            let mut generics: syn::Generics =
+                syn::parse2(borrowed.clone()).expect("impl generics parse as generics");
+            generics.where_clause =
+                syn::parse2(where_clause.clone()).expect("where clause parses as one");
+            let statics = generics
+                .type_params()
+                .map(|param| -> syn::WherePredicate {
+                    let ident = &param.ident;
+                    syn::parse_quote! { #ident: 'static }
+                })
+                .collect::<Vec<_>>();
+            generics.make_where_clause().predicates.extend(statics);
+            let where_clause = &generics.where_clause;
+            let into_owned = match free_fn {
+                Some(ref free_fn) => quote! { #free_fn(self) },
+                None => quote! { #name::into_owned(self) },
+            };
+
+            quote! {
+                impl #borrowed ::derive_into_owned_core::IntoOwned for #name #params #where_clause {
+                    type Owned = #name #owned;
+
+                    fn into_owned(self) -> #name #owned {
+                        #into_owned
+                    }
+                }
+            }
+        } else {
+            quote! {}
+        };
+
+        if let Some(free_fn) = free_fn {
+            let vis = self.vis;
+            let body = replace_self(body, &quote! { value });
+
+            let into_owned_arc = if self.attrs.arc {
+                let arc_fn = format_ident!("{}_arc", free_fn);
+                quote! {
+                    /// Returns a version of `value` with all fields converted to owning versions,
+                    /// ready to be shared between threads or tasks.
+                    #vis fn #arc_fn #borrowed (
+                        value: #name #params,
+                    ) -> ::std::sync::Arc<#name #owned>
+                    #where_clause
+                    {
+                        ::std::sync::Arc::new(#free_fn(value))
+                    }
+                }
+            } else {
+                quote! {}
+            };
+
+            let into_owned_blocking = if self.attrs.blocking {
+                let blocking_fn = format_ident!("{}_blocking", free_fn);
+                let mut where_clause: Option<syn::WhereClause> =
+                    syn::parse2(where_clause.clone()).expect("where clause parses as one");
+                where_clause
+                    .get_or_insert_with(|| syn::parse_quote! { where })
+                    .predicates
+                    .push(syn::parse_quote! { #name #params: ::std::marker::Send + 'static });
+                quote! {
+                    /// Returns a version of `value` with all fields converted to owning versions,
+                    /// copying the data with `tokio::task::spawn_blocking` so that converting
+                    /// large values does not stall the other tasks of the runtime.
+                    #vis async fn #blocking_fn #borrowed (value: #name #params) -> #name #owned
+                    #where_clause
+                    {
+                        // the `'static` bound makes the borrowed type the owned one
+                        let owned = ::tokio::task::spawn_blocking(move || -> #name #params {
+                            #free_fn(value)
+                        });
+                        match owned.await {
+                            ::std::result::Result::Ok(owned) => owned,
+                            ::std::result::Result::Err(error) => {
+                                ::std::panic::resume_unwind(error.into_panic())
+                            }
+                        }
+                    }
+                }
+            } else {
+                quote! {}
+            };
+
+            return quote! {
+                #into_owned_trait
+
+                /// Returns a version of `value` with all fields converted to owning versions.
+                #vis #constness fn #free_fn #borrowed (value: #name #params) -> #name #owned
+                #where_clause
+                {
+                    #body
+                }
+
+                #into_owned_arc
+
+                #into_owned_blocking
+            };
+        }
+
+        if self.attrs.transparent {
+            return quote! {
+                #into_owned_trait
+
+                impl #borrowed #name #params #where_clause {
+                    /// Returns a version of `self` with the wrapped value converted to an owning
+                    /// version.
+                    #[inline]
+                    pub #constness fn into_owned(self) -> #name #owned { #body }
+                }
+            };
+        }
+
+        let into_owned_arc = if self.attrs.arc {
+            quote! {
+                /// Returns a version of `self` with all fields converted to owning versions, ready
+                /// to be shared between threads or tasks.
+                pub fn into_owned_arc(self) -> ::std::sync::Arc<#name #owned> {
+                    ::std::sync::Arc::new(self.into_owned())
+                }
+            }
+        } else {
+            quote! {}
+        };
+
+        let into_owned_boxed = if self.attrs.boxed {
+            quote! {
+                /// Returns a version of `self` with all fields converted to owning versions, on
+                /// the heap so that large values are not moved around on the stack afterwards.
+                pub fn into_owned_boxed(self) -> ::std::boxed::Box<#name #owned> {
+                    ::std::boxed::Box::new(self.into_owned())
+                }
+            }
+        } else {
+            quote! {}
+        };
+
+        let take_owned = if self.attrs.take_owned {
+            quote! {
+                /// Moves the data out of `self` converted to owning versions, leaving
+                /// `Default::default()` in its place, like `std::mem::take`.
+                pub fn take_owned(&mut self) -> #name #owned
+                where
+                    Self: ::std::default::Default,
+                {
+                    ::std::mem::take(self).into_owned()
+                }
+            }
+        } else {
+            quote! {}
+        };
+
+        let into_owned_blocking = if self.attrs.blocking {
+            quote! {
+                /// Returns a version of `self` with all fields converted to owning versions,
+                /// copying the data with `tokio::task::spawn_blocking` so that converting large
+                /// values does not stall the other tasks of the runtime.
+                pub async fn into_owned_blocking(self) -> #name #owned
+                where
+                    Self: ::std::marker::Send + 'static,
+                {
+                    // the `'static` bound makes `Self` the owned type, a second `Send` bound
+                    // on it would be ambiguous
+                    let owned = ::tokio::task::spawn_blocking(move || -> Self { self.into_owned() });
+                    match owned.await {
+                        ::std::result::Result::Ok(owned) => owned,
+                        ::std::result::Result::Err(error) => {
+                            ::std::panic::resume_unwind(error.into_panic())
+                        }
+                    }
+                }
+            }
+        } else {
+            quote! {}
+        };
+
+        quote! {
+            #into_owned_trait
+
+            impl #borrowed #name #params #where_clause {
+                /// Returns a version of `self` with all fields converted to owning versions.
+                pub #constness fn into_owned(self) -> #name #owned { #body }
+
+                #into_owned_arc
+
+                #into_owned_boxed
+
+                #take_owned
+
+                #into_owned_blocking
+            }
+        }
+    }
+}
+
+/// `FooBar` as `foo_bar`, keeping acronyms like `HTTPRequest` together as `http_request`.
+fn snake_case(name: &str) -> String {
+    let chars = name.chars().collect::<Vec<_>>();
+    let mut snake = String::new();
+
+    for (index, &c) in chars.iter().enumerate() {
+        if c.is_uppercase() && index > 0 {
+            let previous = chars[index - 1];
+            let next_is_lower = chars
+                .get(index + 1)
+                .map_or(false, |next| next.is_lowercase());
+            if (!previous.is_uppercase() && previous != '_')
+                || (previous.is_uppercase() && next_is_lower)
+            {
+                snake.push('_');
+            }
+        }
+        snake.extend(c.to_lowercase());
+    }
+
+    snake
+}
+
+/// Replaces the `self` receiver in `tokens` with `value`, leaving paths like `self::foo` alone.
+fn replace_self(
+    tokens: proc_macro2::TokenStream,
+    value: &proc_macro2::TokenStream,
+) -> proc_macro2::TokenStream {
+    use proc_macro2::TokenTree;
+
+    let mut tokens = tokens.into_iter().peekable();
+    let mut replaced = proc_macro2::TokenStream::new();
+
+    while let Some(token) = tokens.next() {
+        match token {
+            TokenTree::Ident(ref ident) if ident == "self" => {
+                let is_path = matches!(
+                    tokens.peek(),
+                    Some(TokenTree::Punct(ref punct)) if punct.as_char() == ':'
+                );
+                if is_path {
+                    replaced.extend(Some(token));
+                } else {
+                    replaced.extend(value.clone());
+                }
+            }
+            TokenTree::Group(ref group) => {
+                let mut new =
+                    proc_macro2::Group::new(group.delimiter(), replace_self(group.stream(), value));
+                new.set_span(group.span());
+                replaced.extend(Some(TokenTree::Group(new)));
+            }
+            other => replaced.extend(Some(other)),
+        }
+    }
+
+    replaced
+}
@@ -27,331 +27,834 @@
 //! `'static` lifetime which means the value owns all of it's data. This is useful if you are
 //! for example, working with [`tokio-rs`] which currently requires types to be `'static`.
 //!
+//! When all the fields are just moved the method is a `const fn` and can be used in const
+//! contexts. Types without any lifetime parameters own their data already, so the derives can be
+//! put on every type of generated code: `into_owned` returns the value as it is and `borrowed`
+//! clones it, which needs the type to be `Clone`.
+//!
+//! Large owned values, like big protobuf messages, can be put on the heap right away with
+//! `#[into_owned(boxed)]` which additionally generates
+//! `fn into_owned_boxed(self) -> Box<Foo<'static>>`.
+//!
+//! Values parsed into over-allocated buffers can drop the spare capacity with
+//! `#[into_owned(shrink)]`, which additionally generates
+//! `fn into_owned_and_shrink(self) -> Foo<'static>` calling `shrink_to_fit` on the owned values of
+//! the [`Cow`]s and on the collected vectors. "Cow-alike" fields need the option as well.
+//!
+//! `#[into_owned(trait_impl)]` additionally implements the `IntoOwned` trait of the
+//! `derive-into-owned-core` crate, which is also implemented for `Vec<T>`, `Option<T>` and
+//! `Box<T>`, so that `foos.into_owned()` converts a `Vec<Foo<'a>>` into a `Vec<Foo<'static>>`.
+//! `From` conversions between these cannot be generated instead, as the coherence rules reject
+//! them. The trait is only implemented for `'static` type parameters, and the generated code
+//! requires `derive-into-owned-core` as a dependency.
+//!
+//! Reusable buffers which are drained after every parse can use `#[into_owned(take_owned)]`,
+//! which additionally generates `fn take_owned(&mut self) -> Foo<'static>`. Like
+//! `std::mem::take` it leaves `Default::default()` behind, so the type needs to implement
+//! `Default`.
+//!
+//! The `std::ops::Bound<T>` keys of range queries are converted like options, keeping
+//! `Bound::Unbounded` as is.
+//!
+//! Lazily computed values in `OnceCell<T>`, of either `std` or `once_cell`, and `OnceLock<T>`
+//! are converted when the cell is initialized and left empty otherwise. The `std` cells need
+//! Rust 1.70. `LazyCell` and `Lazy` cannot be converted as the closure initializing them keeps
+//! the lifetime.
+//!
+//! Newtypes like `struct Name<'a>(Cow<'a, str>)` can be marked with `#[into_owned(transparent)]`,
+//! which checks that there is a single field and generates only an `#[inline]` `into_owned`
+//! delegating to it, without the extras of the Cargo features. Like any type with a lifetime
+//! parameter the newtype is "Cow-alike" for the types containing it, so newtypes can wrap each
+//! other.
+//!
+//! When the type should keep its method namespace, for example because it already has an
+//! `into_owned` of its own, `#[into_owned(free_fn)]` generates a free function in the current
+//! module instead: `fn http_request_into_owned(value: HttpRequest<'_>) -> HttpRequest<'static>`,
+//! with the same visibility as the type. `#[into_owned(free_fn = "detach")]` names the function.
+//! The extras of the Cargo features become free functions as well, like `detach_arc` for
+//! `#[into_owned(arc)]` and `detach_blocking` for `#[into_owned(blocking)]`, while `boxed` and `take_owned` cannot be combined with it.
+//! Types containing such a type still call `.into_owned()` on the field, so they need
+//! `#[into_owned(with = "...")]` pointing at the free function.
+//!
+//! Generated code, like the output of pb-rs, can be left untouched by putting it in a module with
+//! `#[into_owned_module]`, which adds `#[derive(IntoOwned)]` to every struct and enum of the
+//! module and its submodules that does not derive it already. Files brought in with `include!`
+//! are read by the attribute, so their paths need to be absolute, as in the usual
+//! `include!(concat!(env!("OUT_DIR"), "/person.rs"))`.
+//!
+//! ```ignore
+//! use derive_into_owned::into_owned_module;
+//!
+//! #[into_owned_module]
+//! mod person {
+//!     include!(concat!(env!("OUT_DIR"), "/person.rs"));
+//! }
+//! ```
+//!
+//! ## `no_std`
+//!
+//! The generated code refers to `std`, except with `#[into_owned(no_std)]` which makes all three
+//! derives use the same items from `core` and `alloc`, for crates with `extern crate alloc;`.
+//! This composes with deserializers borrowing from the received buffer in `no_std` crates, like
+//! postcard with `#[serde(borrow)]`, as shown by the `no-std-test` crate of the repository. The
+//! types only found in `std`, like `HashMap`, cannot be used, and neither can `arc` and `blocking`.
+//! As `derive-into-owned-core` needs `std` as well, the options referring to its items, `intern`,
+//! `field_info` and `trait_impl`, are rejected.
+//!
+//! ## Minimum supported Rust version
+//!
+//! The generated code compiles with Rust 1.56 and newer, except for the `const fn` above which
+//! needs Rust 1.61. Crates with a strict MSRV policy can declare theirs with
+//! `#[into_owned(msrv = "1.56")]`, which leaves out everything newer than the given version.
+//! Versions older than 1.56 are rejected. The derives themselves build with Rust 1.56 as well,
+//! which the `msrv-test` crate of the repository checks.
+//!
 //! ## `Borrowed`
 //!
-//! `#[derive(Borrowed)]` implements a method `fn borrowed<'b>(&'b self) -> Foo<'b>` for type
+//! `#[derive(Borrowed)]` implements a method `fn borrowed(&self) -> Foo<'_>` for type
 //! `Foo<'a>`. This is useful in case you need to transform the value into another type using
 //! std conversions like [`From`], but you don't want to clone the data in the process. Note that
 //! the all the fields that are not [`Cow`] or "Cow-alike" are just cloned, and new vectors are
 //! collected, so this yields savings only when you manage to save big chunks of memory.
 //!
+//! `fn with_borrowed<'b, R>(&'b self, f: impl FnOnce(Foo<'b>) -> R) -> R` hands the borrowed copy
+//! to a closure and drops it afterwards, so that `foo.with_borrowed(Summary::from)` converts
+//! without a temporary or any lifetime annotations. The lifetime is named `'b1`, `'b2` and so on
+//! for types which already have a `'b`.
+//!
+//! The "Cow-alike" fields, like `Bar<'a>`, need a `fn borrowed<'b>(&'b self) -> Bar<'b>` of
+//! their own. A field type without one is reported at the field, with the `[DIO016]` error.
+//!
+//! As the cloning can be accidentally expensive, `#[borrowed(warn_clones)]` warns about all
+//! fields of heap allocating types like `String`, `Vec<T>` or maps which are deep cloned. The
+//! warning for a single field is silenced by accepting the cost with `#[borrowed(clone)]`:
+//!
+//! ```ignore
+//! #[derive(Borrowed)]
+//! #[borrowed(warn_clones)]
+//! struct Message<'a> {
+//!     topic: Cow<'a, str>,
+//!     #[borrowed(clone)]
+//!     id: String,
+//! }
+//! ```
+//!
+//! Wrapper types which are not "Cow-alike" but implement `AsRef<T>` and `From<&T>` can be
+//! borrowed without the clone with `#[borrowed(as_ref)]`, which generates
+//! `From::from(field.as_ref())`. When the type has several `AsRef` implementations the one to use
+//! is given with `#[borrowed(as_ref = "str")]`.
+//!
+//! Type parameters used in the cloned fields, like `T` in `values: Vec<T>`, get a `T: Clone`
+//! bound on `borrowed` without declaring it on the type. Fields like `PhantomData<T>` are cloned
+//! without it, so the bound can be left out with `#[into_owned(skip_bounds)]` on the type or on
+//! a single parameter, `struct Tagged<'a, #[into_owned(skip_bounds)] T>`.
+//!
+//! ## Borrowed views
+//!
+//! `borrowed` keeps the type and so still clones the fields which never borrow. For reading
+//! without any clones, `#[borrowed(view)]` generates a separate type `FooRef<'b>` and
+//! `fn view(&self) -> FooRef<'_>`. In the view type [`Cow`] and reference fields become plain
+//! references like `&'b str`, "Cow-alike" fields become `Bar<'b>` through their `borrowed`,
+//! owned `String` and `Vec<T>` fields become `&'b str` and `&'b [T]`, and any other field
+//! becomes a reference to the original one. The view type takes the same options as
+//! `mirror(...)`, like `view(name = "FooView", derive(Debug, Clone, Copy))`.
+//!
+//! Views compose across types: marking a field of a type which has a view itself with
+//! `#[borrowed(view)]` uses `BarRef<'b>` for it instead.
+//!
+//! ```ignore
+//! #[derive(Borrowed)]
+//! #[borrowed(view)]
+//! struct Message<'a> {
+//!     topic: Cow<'a, str>,
+//!     #[borrowed(view)]
+//!     header: Header,
+//! }
+//!
+//! let view: MessageRef<'_> = message.view();
+//! let header: HeaderRef<'_> = view.header;
+//! ```
+//!
+//! ## Leaking
+//!
+//! For values which are loaded once and then live until the end of the process, like
+//! configuration, `#[into_owned(leak)]` generates `fn leaked(self) -> Foo<'static>` instead of
+//! `into_owned`. It leaks the owned versions of all the borrowed data, keeping the [`Cow`]s
+//! borrowed, and so also works for plain `&'a str` and `&'a [u8]` fields. "Cow-alike" fields are
+//! expected to have a `leaked` method as well.
+//!
+//! ## Fallible conversion
+//!
+//! Enums where only some variants hold plain references can use `#[into_owned(try_into_owned)]`,
+//! which generates `fn try_into_owned(self) -> Result<Foo<'static>, Self>` instead of
+//! `into_owned`. The variants with plain reference fields are returned unchanged as the error,
+//! while all other variants are converted as usual.
+//!
+//! ## Interning strings
+//!
+//! When many values repeat the same strings, `#[into_owned(intern)]` generates
+//! `fn into_owned_interned(self, interner: &mut impl Interner) -> Foo<'static>` next to
+//! `into_owned`. Every `Cow<'a, str>` is then routed through the interner, which returns a
+//! borrowed `'static` string where it can and a copy otherwise, while the other fields are
+//! converted as with `into_owned`:
+//!
+//! ```ignore
+//! use derive_into_owned_core::Vocabulary;
+//!
+//! let mut interner: Vocabulary = ["fi", "se", "no"].into_iter().collect();
+//! let rows: Vec<Row<'static>> = rows
+//!     .into_iter()
+//!     .map(|row| row.into_owned_interned(&mut interner))
+//!     .collect();
+//! ```
+//!
+//! The `Interner` trait lives in the `derive-into-owned-core` crate, which then needs to be a
+//! dependency as well. Its code generation is not needed for that, so it can be added with
+//! `default-features = false`. `Vocabulary` borrows the strings it was created from and copies
+//! all others, so it never leaks.
+//!
+//! **`Interner` is also implemented for `HashSet<&'static str>`, which leaks every distinct
+//! string it sees and never frees it.** Its memory grows with the number of distinct strings for
+//! as long as the program runs, so it is only suitable for small, bounded sets of strings and
+//! never for strings coming from untrusted or unbounded input.
+//!
+//! "Cow-alike" fields are expected to have an `into_owned_interned` method as well.
+//!
+//! ## Known types
+//!
+//! Instead of relying on the "Cow-alike" guess, the lifetime carrying types of other crates can
+//! be listed explicitly together with the method used to convert them:
+//!
+//! ```ignore
+//! #[derive(IntoOwned, Borrowed)]
+//! #[into_owned(known(my_crate::Span, other::Token = "to_static"))]
+//! struct Statement<'a> {
+//!     span: Span<'a>,
+//!     tokens: Vec<other::Token<'a>>,
+//! }
+//! ```
+//!
+//! Listed types are matched by their path, so `Span` matches `my_crate::Span` above, and are
+//! converted with `into_owned` unless another method is given. `Borrowed` still uses `borrowed`.
+//!
+//! With the `config` feature the known types can also be listed once for the whole crate in a
+//! `derive-into-owned.toml` file next to `Cargo.toml`, which is useful with generated code:
+//!
+//! ```toml
+//! [known]
+//! "my_crate::Span" = "into_owned"
+//! "other::Token" = "to_static"
+//! ```
+//!
+//! The path of the file can be overridden with the `DERIVE_INTO_OWNED_CONFIG` environment
+//! variable. Types listed with the attribute take precedence over the file.
+//!
+//! Generic wrappers like `Spanned<T>`, which keep some metadata next to the value, are moved
+//! as is by default. Listing them with `#[into_owned(wrapper(...))]` converts the value within
+//! through the method mapping it, `map` unless another one is given, so the wrapper is kept:
+//!
+//! ```ignore
+//! #[derive(IntoOwned, Borrowed)]
+//! #[into_owned(wrapper(ast::Spanned, other::Node = "map_value"))]
+//! struct Call<'a> {
+//!     name: Spanned<Cow<'a, str>>,
+//!     args: Vec<Spanned<Arg<'a>>>,
+//! }
+//! ```
+//!
+//! The method is called like `spanned.map(|value| value.into_owned())`, so it needs to take the
+//! wrapper by value and return it with a different type argument like `Option::map` does.
+//! `Borrowed` additionally needs `fn as_ref(&self) -> Spanned<&T>` for
+//! `spanned.as_ref().map(..)`. The payload cannot be reached by reference, so ownership reports
+//! leave the wrapped fields out.
+//!
+//! An `Rc<Payload<'a>>` or `Arc<Payload<'a>>` is moved as is by default, as the payload may be
+//! shared. With `#[into_owned(unwrap_or_clone)]` the payload is taken out of the pointer when the
+//! field holds the only reference and cloned otherwise, then converted and put into a new one, so
+//! the payload needs to implement `Clone`. `borrowed` shares the pointer itself.
+//!
+//! A `Pin<Box<Node<'a>>>` is converted like the box within, which is taken out of the `Pin` with
+//! `Pin::into_inner` and pinned again, so `Node` needs to be `Unpin`. Pins of other pointers are
+//! moved as is.
+//!
+//! Tuples like the pairs of `Vec<(String, Cow<'a, str>)>` are converted element by element,
+//! also within options, vectors and the other containers above.
+//!
+//! ## Custom conversions and storage generic types
+//!
+//! A single field can be converted with a function instead with
+//! `#[into_owned(with = "path::to_owned")]`, which gets the field by value and returns the
+//! `'static` version of it. Fields which are not worth keeping can be replaced with
+//! `Default::default()` with `#[into_owned(skip)]`. Extra where predicates for the generated impls
+//! can be added with `#[into_owned(bound = "...")]`.
+//!
+//! The derives ignore the attributes of other derives, so error enums can derive both
+//! `thiserror::Error` and `IntoOwned` to be made `'static` before crossing task boundaries. Boxed
+//! `dyn Error` sources are `'static` already, while errors which borrow, like
+//! `Box<dyn Error + 'a>`, need `with`, `skip` or `#[into_owned(stringify)]`. `stringify` replaces
+//! the error with one holding only its message, with `From::from(field.to_string())`, which works
+//! for any field type convertible from a `String`. Other wrappers of the error, like
+//! `Option<Box<dyn Error + 'a>>`, need a function doing the same:
+//!
+//! ```ignore
+//! #[derive(Debug, thiserror::Error, IntoOwned)]
+//! enum ParseError<'a> {
+//!     #[error("unexpected `{token}`")]
+//!     Unexpected { token: Cow<'a, str> },
+//!     #[error("invalid value `{0}`")]
+//!     Invalid(Cow<'a, str>, #[source] Box<dyn Error + Send + Sync>),
+//!     #[error("{0}")]
+//!     Custom(#[into_owned(stringify)] Box<dyn Error + Send + Sync + 'a>),
+//!     #[error("{0}")]
+//!     Nested(Cow<'a, str>, #[into_owned(with = "detach")] Option<Box<dyn Error + 'a>>),
+//! }
+//!
+//! fn detach(error: Option<Box<dyn Error + '_>>) -> Option<Box<dyn Error>> {
+//!     error.map(|error| error.to_string().into())
+//! }
+//! ```
+//!
+//! Other trait objects which borrow, like `Arc<dyn Visitor<'a> + 'a>`, are reported with the
+//! `[DIO017]` error, as only the trait can clone them into `'static` ones. A `Box`, `Rc` or `Arc`
+//! of one is converted with `#[into_owned(clone_box = "Visitor::clone_box")]`, naming a function
+//! which takes the trait object by reference and returns a `Box<dyn Visitor<'static>>`.
+//!
+//! Function pointers are moved as they are. The ones taking arguments of any lifetime, like
+//! `fn(&str) -> &str`, work with both derives, and `into_owned` also accepts `fn(&'a str)`. The
+//! ones which return data borrowed for a lifetime parameter, like `fn() -> &'a str`, are
+//! reported with the `[DIO019]` error by `IntoOwned`, and the ones which take such arguments by
+//! `Borrowed`.
+//!
+//! Together they cover types which are generic over how they store their data instead of using
+//! [`Cow`] directly. The storage trait declares the stored types as generic associated types and
+//! a separate trait converts them, which the type only requires for `into_owned`:
+//!
+//! ```ignore
+//! trait Storage {
+//!     type Str<'a>;
+//! }
+//!
+//! trait IntoOwnedStorage: Storage {
+//!     fn owned_str(s: Self::Str<'_>) -> Self::Str<'static>;
+//! }
+//!
+//! #[derive(IntoOwned)]
+//! #[into_owned(bound = "S: IntoOwnedStorage")]
+//! struct Msg<'a, S: Storage> {
+//!     #[into_owned(with = "S::owned_str")]
+//!     body: S::Str<'a>,
+//! }
+//! ```
+//!
+//! Storage traits with a lifetime parameter, like `struct Msg<'a, S: Storage<'a>> { body: S::Str }`,
+//! are not supported and rejected with `[DIO018]`. Converting `S::Str` would need both
+//! `S: Storage<'a>` and `S: Storage<'static>`, and with both bounds the compiler cannot tell which
+//! impl `S::Str` refers to, so such types need to be rewritten as above.
+//!
+//! ## Strict mode
+//!
+//! Fields of types which are not recognized are moved as is, which fails to compile only if the
+//! type happens to borrow something. With `#[into_owned(strict)]` every field which would be
+//! moved as is even though its type has lifetimes, like `Cell<&'a str>`, is a compile error
+//! pointing at the field, so that it can be listed with `known(...)` instead.
+//!
+//! ## Asserting `Send` and `Sync`
+//!
+//! Owned values are often sent to other tasks or threads, where a field like `Rc<T>` or
+//! `Cell<T>` is only noticed at the `tokio::spawn` call. `#[into_owned(assert_send_sync)]` checks
+//! that every field of `Foo<'static>` is `Send + Sync + 'static`, given type parameters which are,
+//! and reports the ones which are not at the fields.
+//!
+//! ## Asserting the classification
+//!
+//! `#[into_owned(expect = "...")]` on a field makes it a compile error if the field is not
+//! handled as expected, for example after a change in the `.proto` file the code is generated
+//! from. The handling is written as `cow`, `cow_alike`, `known`, `zerovec`, `varzerovec`,
+//! `bump_string`, `reference` or `moved`, possibly within `option(..)`, `iterable(..)`, `set(..)`,
+//! `box(..)`, `pin(..)`, `rc(..)`, `arc(..)`, `map(key, value)`, `dashmap(key, value)`,
+//! `tuple(..)` with every element like `tuple(moved, cow)`, `bump_vec(..)` or `wrapper(..)`,
+//! where `set(..)` also stands for a `BinaryHeap`:
+//!
+//! ```ignore
+//! #[derive(IntoOwned)]
+//! struct Message<'a> {
+//!     #[into_owned(expect = "iterable(cow)")]
+//!     tags: Vec<Cow<'a, str>>,
+//! }
+//! ```
+//!
+//! ## Ownership reports
+//!
+//! For finding out what is still borrowed, `#[into_owned(ownership)]` generates a struct
+//! `FooOwnership` with a `bool` for each field which can borrow, and
+//! `fn ownership(&self) -> FooOwnership`. A field is reported as owned when all the [`Cow`]s in
+//! it, including the ones in options, vectors, maps and boxes, are `Cow::Owned`. Fields which
+//! never borrow and fields whose ownership cannot be told, like "Cow-alike" and plain reference
+//! fields, are left out.
+//!
+//! ```ignore
+//! #[derive(IntoOwned)]
+//! #[into_owned(ownership)]
+//! struct Request<'a> {
+//!     path: Cow<'a, str>,
+//!     headers: Vec<Cow<'a, str>>,
+//! }
+//!
+//! let request = Request { path: Cow::Owned(path), headers: vec![Cow::Borrowed("a")] };
+//! assert_eq!(request.ownership(), RequestOwnership { path: true, headers: false });
+//! ```
+//!
+//! For debugging tools, `#[into_owned(borrowed_strs)]` generates
+//! `fn borrowed_strs(&self) -> Vec<&str>` listing every `Cow<str>` which is still
+//! `Cow::Borrowed`, in the order of the fields and recursing into "Cow-alike" fields, which then
+//! need `borrowed_strs` as well. This works for enums too.
+//!
+//! For fanning out freshly parsed values without allocating, `#[into_owned(try_reborrow_original)]`
+//! generates `fn try_reborrow_original(&self) -> Option<Foo<'a>>`, which copies the value keeping
+//! its lifetimes if every [`Cow`] in it is `Cow::Borrowed`, and returns `None` if any of them is
+//! owned. Plain references are copied, while the values whose ownership cannot be told, like
+//! known types and wrappers, and the other fields are cloned, so the type parameters used in the
+//! fields get a `T: Clone` bound. "Cow-alike" fields need `try_reborrow_original` as well.
+//!
+//! For tooling which needs to know what `into_owned` touches without parsing the type itself,
+//! `#[into_owned(field_info)]` generates `const BORROW_FIELDS: &'static [FieldInfo]` with the
+//! variant, name, classification and container nesting of every field which is converted. The
+//! classification is written like for `expect`, and the fields which are just moved are left
+//! out. The `FieldInfo` type lives in the `derive-into-owned-core` crate, which then needs to be
+//! a dependency as well.
+//!
+//! ```ignore
+//! #[derive(IntoOwned)]
+//! #[into_owned(field_info)]
+//! struct Request<'a> {
+//!     path: Cow<'a, str>,
+//!     headers: Vec<Cow<'a, str>>,
+//!     retries: u32,
+//! }
+//!
+//! assert_eq!(Request::BORROW_FIELDS[1].kind, "iterable(cow)");
+//! assert_eq!(Request::BORROW_FIELDS.len(), 2);
+//! ```
+//!
+//! `#[derive(OwnershipDebug)]` generates `fn ownership_debug(&self) -> Box<dyn Debug + '_>`,
+//! which prints the value like `Debug` with every [`Cow`] prefixed by `[borrowed]` or `[owned]`,
+//! and plain references by `[borrowed]`. The value is boxed as `impl Debug + '_` could not
+//! capture the lifetime of types which are invariant in it. "Cow-alike" fields are printed with
+//! their own `ownership_debug`, so they need the derive as well, and the other fields with
+//! `Debug`. The type parameters used in the fields get a `T: Debug` bound, which
+//! `#[into_owned(skip_bounds)]` leaves out.
+//!
+//! ```ignore
+//! #[derive(OwnershipDebug)]
+//! struct Request<'a> {
+//!     path: Cow<'a, str>,
+//!     headers: Vec<Cow<'a, str>>,
+//! }
+//!
+//! // Request { path: [owned] "/", headers: [[borrowed] "a"] }
+//! println!("{:?}", request.ownership_debug());
+//! ```
+//!
+//! ## Mapping strings
+//!
+//! For sanitizing or normalizing passes, `#[into_owned(map_cows)]` generates
+//! `fn map_cows(self, f) -> Self` which replaces every `Cow<str>` with what the closure returns
+//! for it. The strings are found the same way as for `into_owned`, so the ones within options,
+//! collections, boxes and "Cow-alike" fields are included, the latter needing `map_cows` as well.
+//! Other [`Cow`]s and plain references are left as they are.
+//!
+//! ```ignore
+//! let comment = comment.map_cows(|s| match s {
+//!     Cow::Borrowed(s) => Cow::Borrowed(s.trim()),
+//!     Cow::Owned(s) => Cow::Owned(s.trim().to_owned()),
+//! });
+//! ```
+//!
+//! ## Owned mirror types
+//!
+//! With `#[into_owned(mirror)]` the `IntoOwned` derive also generates a separate owned type
+//! `FooOwned` without the lifetime parameters, and `impl<'a> From<Foo<'a>> for FooOwned`. In the
+//! mirror type [`Cow`] fields become the owned type, like `String` for `Cow<'a, str>`, and
+//! "Cow-alike" fields become `Bar<'static>`. For enums, like the oneofs of protobuf messages, the
+//! mirror type is an enum with the same variants holding the owned payloads.
+//!
+//! Plain references like `&'a str` and `&'a [u8]`, as found in many tokenizer types, cannot be
+//! converted into `'static` so for types with such fields only the mirror type is generated. In
+//! the mirror they become `String` and `Vec<u8>`, `CString` for `CStr` as passed to C APIs, or
+//! `<T as ToOwned>::Owned` in general.
+//! Without the mirror type such fields are reported as errors pointing at the reference, like
+//! the key of a `HashMap<&'a str, V>`. `Borrowed` handles them by copying the references.
+//!
+//! The mirror type can be customized with `#[into_owned(mirror(...))]`:
+//!
+//!  * `name = "OwnedFoo"` to use a different name
+//!  * `derive(...)` to add derives, for example `derive(Debug, rkyv::Archive, rkyv::Serialize)`
+//!  * `attr(...)` to add any other attributes, for example `attr(archive(check_bytes))`
+//!  * `arc` to use `Arc<str>` and `Arc<[T]>` instead of `String` and `Vec<T>` for the string and
+//!    slice leaves, so that clones of the mirror type share the buffers
+//!  * `bytes` to use `bytes::Bytes` for the byte slice leaves, with the `bytes` feature
+//!  * `minicbor` to derive `minicbor::Encode` and `minicbor::Decode` for the mirror type, with the
+//!    `minicbor` feature. The `#[n(..)]`, `#[b(..)]` and `#[cbor(..)]` attributes of the type,
+//!    its variants and fields are copied over, with the borrowing `b` indices becoming `n`, so
+//!    that the borrowed type decodes zero-copy and the mirror decodes the same bytes owned
+//!  * `partial_eq` to implement `PartialEq<FooOwned>` for `Foo<'a>` and the other way around,
+//!    comparing the fields by their contents, so that tests can compare parsed values with owned
+//!    expected ones. Maps are compared entry by entry, which is quadratic in their sizes, and the
+//!    skipped fields are left out of the comparison
+//!
+//! The fields of the mirror type can be customized with `#[into_owned(mirror(...))]` on the
+//! fields:
+//!
+//!  * `name = "other"` to rename the field
+//!  * `ty = "Box<str>"` to use a different owned type, which the usual one is converted into with
+//!    [`Into`]
+//!  * `skip` to leave the field out
+//!
+//! Readers generated for other zero-copy formats, like the tables of `flatbuffers`, have no
+//! fields to derive on as they only wrap the buffer and decode the values in accessor methods.
+//! Such readers can be owned through a view struct with plain references filled from the
+//! accessors, for which the mirror type is then generated:
+//!
+//! ```ignore
+//! #[derive(IntoOwned)]
+//! #[into_owned(mirror)]
+//! struct MonsterView<'a> {
+//!     name: &'a str,
+//!     inventory: Option<&'a [u8]>,
+//! }
+//!
+//! let view = MonsterView { name: monster.name(), inventory: monster.inventory().map(|v| v.bytes()) };
+//! let owned = MonsterViewOwned::from(view);
+//! ```
+//!
+//! ## Working with `serde`
+//!
+//! The derives do not look at attributes of other derives, so `#[serde(borrow)]` and friends can
+//! be used as usual. The common workflow is to deserialize without copying and only then take
+//! ownership of the parts which need to outlive the input buffer:
+//!
+//! ```ignore
+//! #[derive(Deserialize, IntoOwned)]
+//! struct Message<'a> {
+//!     #[serde(borrow)]
+//!     topic: Cow<'a, str>,
+//! }
+//!
+//! let message: Message<'_> = serde_json::from_str(&input)?;
+//! let message: Message<'static> = message.into_owned();
+//! ```
+//!
+//! See [`tests/serde.rs`](https://github.com/koivunej/derive-into-owned/blob/master/tests/serde.rs)
+//! for a complete example.
+//!
+//! ## Cargo features
+//!
+//!  * `into_owned` and `borrowed`, enabled by default: the `IntoOwned` and `Borrowed` derives.
+//!    Crates using only one of them can disable the default features to skip compiling the
+//!    other. `arc`, `bytes`, `minicbor`, `futures` and `yoke` enable `into_owned`.
+//!  * `zerovec`: recognize `zerovec::ZeroVec<'a, T>` and `zerovec::VarZeroVec<'a, T>` fields,
+//!    which are owned with their own `into_owned` and borrowed with `as_zerovec` and
+//!    `as_varzerovec` respectively.
+//!  * `dashmap`: recognize `dashmap::DashMap<K, V>` fields. `into_owned` drains the map and
+//!    collects the converted entries into a new one. The entries of a `DashMap` can only be
+//!    reached through lock guards, which `borrowed` cannot return borrows of, so it clones the
+//!    keys and values into a new map instead, keeping the borrowed [`Cow`]s borrowed.
+//!    `borrowed_strs` leaves the maps out for the same reason.
+//!  * `bumpalo`: recognize `bumpalo::collections::Vec<'bump, T>` and
+//!    `bumpalo::collections::String<'bump>` fields, spelled with the full path or as `Vec` and
+//!    `String` with the lifetime argument. Like plain references they borrow the arena and cannot
+//!    be made `'static`, so types with such fields are detached from the arena with the mirror
+//!    type, where they become `Vec` and `String`. `borrowed` clones them within the arena.
+//!  * `arc`: enables `#[into_owned(arc)]` which additionally generates
+//!    `fn into_owned_arc(self) -> Arc<Foo<'static>>` for sharing the owned value between for
+//!    example multiple spawned tasks.
+//!  * `bytes`: enables `#[into_owned(mirror(bytes))]` which uses `bytes::Bytes` instead of
+//!    `Vec<u8>` for the `Cow<'a, [u8]>` and `&'a [u8]` leaves of the mirror type, copying the
+//!    borrowed bytes once. Combined with `arc` the other strings and slices become `Arc`s. The
+//!    generated code requires `bytes` as a dependency.
+//!  * `minicbor`: enables `#[into_owned(mirror(minicbor))]`. The generated code requires
+//!    `minicbor` with its `derive` feature as a dependency.
+//!  * `futures`: enables the `futures` feature of `derive-into-owned-core`, whose `OwnedItems`
+//!    extension trait provides `fn owned_items(self) -> OwnedItemsStream<Self>` for streams of
+//!    borrowing values implementing its `IntoOwned` trait, as the types deriving with
+//!    `#[into_owned(trait_impl)]` do, converting every item before it crosses a spawn boundary.
+//!    The feature does not change the generated code. As the derives are built separately from
+//!    the crate using them, `OwnedItems` is only available there when its own
+//!    `derive-into-owned-core` dependency enables the `futures` feature as well.
+//!  * `tokio`: enables `#[into_owned(blocking)]` which additionally generates
+//!    `async fn into_owned_blocking(self) -> Foo<'static>` doing the copying inside
+//!    `tokio::task::spawn_blocking`, keeping the conversion of multi-megabyte values from stalling
+//!    the other tasks of the runtime. Like any task the value needs to be `Send + 'static`, which
+//!    fits values borrowing from leaked or `static` buffers. The generated code requires `tokio`
+//!    as a dependency.
+//!  * `yoke`: enables `#[into_owned(yoke)]` which generates
+//!    `fn into_yoke(cart: C, f: F) -> yoke::Yoke<Foo<'static>, C>` for types deriving
+//!    `yoke::Yokeable`. It keeps the value parsed by `f` together with the cart it borrows from,
+//!    which avoids converting into an owned value at all. The generated code requires `yoke` and
+//!    `stable_deref_trait` as dependencies.
+//!
+//!    The feature also enables `#[into_owned(self_owned)]` which generates a wrapper type
+//!    `FooSelfOwned<C>` owning both the buffer `C`, like a `String` or `Vec<u8>`, and the `Foo<'_>`
+//!    parsed out of it. It is created with `FooSelfOwned::new(cart, parse)` or `try_new` and the
+//!    borrowed value is accessed with `get()`.
+//!  * `config`: read known types from `derive-into-owned.toml`, see [Known types](#known-types).
+//!
+//! ## Error codes
+//!
+//! The errors and warnings of the derives start with a stable code like `[DIO001]`, which can be
+//! searched for in build logs. `derive_into_owned_core::diagnostics::explain("DIO001")` returns
+//! the long form explanation of a code, and `derive_into_owned_core::diagnostics::ALL` lists
+//! them all.
+//!
 //! ## Limitations
 //!
 //! Currently only the types I needed are supported and this might be a rather limited set of
 //! functionality. If you find that this does not work in your case please file an issue at [project
 //! repository](https://github.com/koivunej/derive-into-owned/issues).
 //!
+//! Mutable reference fields like `&'a mut [u8]` can be neither cloned nor owned, so both derives
+//! report them as errors. `IntoOwned` accepts them with `#[into_owned(skip)]` or
+//! `#[into_owned(with = "...")]`, otherwise the data needs to be held by value or in a [`Cow`].
+//!
+//! The bounds and where clauses of the type are repeated on the generated impls. Trait bounds
+//! naming a lifetime parameter, like `T: Into<Cow<'a, str>>` or `&'a T: IntoIterator`, are
+//! reported with the `[DIO018]` error, as the returned types would need the trait for other
+//! lifetimes. The compiler cannot tell apart two such bounds which differ only in their
+//! lifetimes, so they cannot be required next to each other. Bounds over all lifetimes, like
+//! `T: for<'x> Into<Cow<'x, str>>` or `for<'x> &'x T: IntoIterator`, work instead.
+//!
+//! Unions are only supported without lifetime parameters, as the active field cannot be known.
+//! Their fields are then `Copy` and `'static`, so `into_owned` returns the value as it is and
+//! `borrowed` copies it, which needs the union to be `Copy`.
+//!
 //! [`quick_protobuf`]: https://github.com/tafia/quick-protobuf/
 //! [`tokio-rs`]: https://tokio.rs
 //! [`Cow`]: https://doc.rust-lang.org/std/borrow/enum.Cow.html
 //! [`From`]: https://doc.rust-lang.org/std/convert/trait.From.html
 
-use quote::{format_ident, quote};
+// without either derive there is nothing to export, which is only useful for checking features
+#![cfg_attr(not(any(feature = "into_owned", feature = "borrowed")), allow(unused))]
 
 use proc_macro::TokenStream;
 use syn::{parse_macro_input, DeriveInput};
 
-mod field_kind;
-mod helpers;
-
-use field_kind::FieldKind;
-
-#[proc_macro_derive(IntoOwned)]
+mod attrs;
+#[cfg(feature = "borrowed")]
+mod borrowed;
+#[cfg(feature = "borrowed")]
+mod clones;
+mod config;
+#[cfg(feature = "into_owned")]
+mod field_info;
+#[cfg(feature = "into_owned")]
+mod into_owned;
+#[cfg(feature = "into_owned")]
+mod mirror;
+#[cfg(feature = "into_owned")]
+mod module;
+mod no_std;
+#[cfg(feature = "into_owned")]
+mod ownership;
+#[cfg(test)]
+mod proptests;
+#[cfg(feature = "borrowed")]
+mod view;
+#[cfg(feature = "into_owned")]
+mod yoke;
+
+#[cfg(feature = "into_owned")]
+#[proc_macro_derive(IntoOwned, attributes(into_owned))]
 pub fn into_owned(input: TokenStream) -> TokenStream {
     let ast = parse_macro_input!(input as DeriveInput);
 
-    let expanded = impl_with_generator(&ast, IntoOwnedGen);
+    let expanded = match into_owned::derive_into_owned(&ast) {
+        Ok(expanded) => expanded,
+        Err(e) => e.to_compile_error(),
+    };
 
     TokenStream::from(expanded)
 }
 
-#[proc_macro_derive(Borrowed)]
-pub fn borrowed(input: TokenStream) -> TokenStream {
-    let ast = parse_macro_input!(input as DeriveInput);
+#[cfg(feature = "into_owned")]
+#[proc_macro_attribute]
+pub fn into_owned_module(args: TokenStream, input: TokenStream) -> TokenStream {
+    let module = parse_macro_input!(input as syn::ItemMod);
 
-    let expanded = impl_with_generator(&ast, BorrowedGen);
+    let expanded = match module::into_owned_module(args.into(), module) {
+        Ok(expanded) => expanded,
+        Err(e) => e.to_compile_error(),
+    };
 
     TokenStream::from(expanded)
 }
 
-fn impl_with_generator<G: BodyGenerator>(
-    ast: &syn::DeriveInput,
-    gen: G,
-) -> proc_macro2::TokenStream {
-    // this is based heavily on https://github.com/asajeffrey/deep-clone/blob/master/deep-clone-derive/lib.rs
-    let name = &ast.ident;
-
-    let borrowed_params = gen.quote_borrowed_params(ast);
-    let borrowed = if borrowed_params.is_empty() {
-        quote! {}
-    } else {
-        quote! { < #(#borrowed_params),* > }
-    };
-
-    let params = gen.quote_type_params(ast);
-    let params = if params.is_empty() {
-        quote! {}
-    } else {
-        quote! { < #(#params),* > }
-    };
-
-    let owned_params = gen.quote_rhs_params(ast);
-    let owned = if owned_params.is_empty() {
-        quote! {}
-    } else {
-        quote! { < #(#owned_params),* > }
-    };
-
-    let body = match ast.data {
-        syn::Data::Struct(ref body) => {
-            let inner = gen.visit_struct(body);
-            quote! { #name #inner }
-        }
-        syn::Data::Enum(ref body) => {
-            let cases = body.variants.iter().map(|variant| {
-                let unqualified_ident = &variant.ident;
-                let ident = quote! { #name::#unqualified_ident };
+#[cfg(feature = "into_owned")]
+#[proc_macro_derive(OwnershipDebug, attributes(into_owned))]
+pub fn ownership_debug(input: TokenStream) -> TokenStream {
+    let ast = parse_macro_input!(input as DeriveInput);
 
-                gen.visit_enum_data(ident, variant)
-            });
-            quote! { match self { #(#cases),* } }
-        }
-        syn::Data::Union(_) => todo!("unions are not supported (5)"),
+    let expanded = match ownership::derive_ownership_debug(&ast) {
+        Ok(expanded) => expanded,
+        Err(e) => e.to_compile_error(),
     };
 
-    gen.combine_impl(borrowed, name, params, owned, body)
+    TokenStream::from(expanded)
 }
 
-/// Probably not the best abstraction
-trait BodyGenerator {
-    fn quote_borrowed_params(&self, ast: &syn::DeriveInput) -> Vec<proc_macro2::TokenStream> {
-        let borrowed_lifetime_params = ast.generics.lifetimes().map(|alpha| quote! { #alpha });
-        let borrowed_type_params = ast.generics.type_params().map(|ty| quote! { #ty });
-        borrowed_lifetime_params
-            .chain(borrowed_type_params)
-            .collect::<Vec<_>>()
-    }
-
-    fn quote_type_params(&self, ast: &syn::DeriveInput) -> Vec<proc_macro2::TokenStream> {
-        ast.generics
-            .lifetimes()
-            .map(|alpha| quote! { #alpha })
-            .chain(ast.generics.type_params().map(|ty| {
-                let ident = &ty.ident;
-                quote! { #ident }
-            }))
-            .collect::<Vec<_>>()
-    }
+#[cfg(feature = "borrowed")]
+#[proc_macro_derive(Borrowed, attributes(into_owned, borrowed))]
+pub fn borrowed(input: TokenStream) -> TokenStream {
+    let ast = parse_macro_input!(input as DeriveInput);
 
-    fn quote_rhs_params(&self, ast: &syn::DeriveInput) -> Vec<proc_macro2::TokenStream> {
-        let owned_lifetime_params = ast.generics.lifetimes().map(|_| quote! { 'static });
-        let owned_type_params = ast.generics.type_params().map(|ty| {
-            let ident = &ty.ident;
-            quote! { #ident }
-        });
-        owned_lifetime_params
-            .chain(owned_type_params)
-            .collect::<Vec<_>>()
-    }
+    let expanded = match borrowed::derive_borrowed(&ast) {
+        Ok(expanded) => expanded,
+        Err(e) => e.to_compile_error(),
+    };
 
-    fn visit_struct(&self, data: &syn::DataStruct) -> proc_macro2::TokenStream;
-    fn visit_enum_data(
-        &self,
-        variant: proc_macro2::TokenStream,
-        data: &syn::Variant,
-    ) -> proc_macro2::TokenStream;
-    fn combine_impl(
-        &self,
-        borrows: proc_macro2::TokenStream,
-        name: &syn::Ident,
-        rhs_params: proc_macro2::TokenStream,
-        owned: proc_macro2::TokenStream,
-        body: proc_macro2::TokenStream,
-    ) -> proc_macro2::TokenStream;
+    TokenStream::from(expanded)
 }
 
-struct IntoOwnedGen;
+/// Checks the `#[into_owned(...)]` options of the type parameters and returns `ast` without the
+/// attributes of its generic parameters, which are unknown in the generated items.
+fn without_param_attrs(ast: &DeriveInput) -> syn::Result<DeriveInput> {
+    let mut ast = ast.clone();
 
-impl BodyGenerator for IntoOwnedGen {
-    fn visit_struct(&self, data: &syn::DataStruct) -> proc_macro2::TokenStream {
-        // Helper ternary to avoid Option<bool>
-        enum Fields {
-            Named,
-            Tuple,
-            Unit,
-        }
-
-        use Fields::*;
-
-        let fields_kind = data
-            .fields
-            .iter()
-            .next()
-            .map(|field| if field.ident.is_some() { Named } else { Tuple })
-            .unwrap_or(Unit);
-
-        match fields_kind {
-            Named => {
-                let fields = data.fields.iter().map(|field| {
-                    let ident = field.ident.as_ref().expect("unexpected unnamed field");
-                    let field_ref = quote! { self.#ident };
-                    let code = FieldKind::resolve(&field.ty).move_or_clone_field(&field_ref);
-                    quote! { #ident: #code }
-                });
-                quote! { { #(#fields),* } }
-            }
-            Tuple => {
-                let fields = data.fields.iter().enumerate().map(|(index, field)| {
-                    let index = syn::Index::from(index);
-                    let index = quote! { self.#index };
-                    FieldKind::resolve(&field.ty).move_or_clone_field(&index)
-                });
-                quote! { ( #(#fields),* ) }
-            }
-            Unit => {
-                quote! {}
+    for param in ast.generics.params.iter_mut() {
+        match *param {
+            syn::GenericParam::Type(ref mut param) => {
+                attrs::ParamAttrs::parse(&param.attrs)?;
+                param.attrs.clear();
             }
+            syn::GenericParam::Lifetime(ref mut param) => param.attrs.clear(),
+            syn::GenericParam::Const(ref mut param) => param.attrs.clear(),
         }
     }
 
-    fn visit_enum_data(
-        &self,
-        ident: proc_macro2::TokenStream,
-        variant: &syn::Variant,
-    ) -> proc_macro2::TokenStream {
-        if variant.fields.is_empty() {
-            return quote!(#ident => #ident);
-        }
-
-        let fields_are_named = variant.fields.iter().any(|field| field.ident.is_some());
-
-        if fields_are_named {
-            let named_fields = variant
-                .fields
-                .iter()
-                .filter_map(|field| field.ident.as_ref());
-
-            let cloned = variant.fields.iter().map(|field| {
-                let ident = field.ident.as_ref().unwrap();
-                let ident = quote!(#ident);
-                let code = FieldKind::resolve(&field.ty).move_or_clone_field(&ident);
-                quote! { #ident: #code }
-            });
-            quote! { #ident { #(#named_fields),* } => #ident { #(#cloned),* } }
-        } else {
-            let unnamed_fields = &variant
-                .fields
-                .iter()
-                .enumerate()
-                .filter(|(_, field)| field.ident.is_none())
-                .map(|(index, _)| format_ident!("arg_{}", index))
-                .collect::<Vec<_>>();
-
-            let cloned = unnamed_fields
-                .iter()
-                .zip(variant.fields.iter())
-                .map(|(ident, field)| {
-                    let ident = quote! { #ident };
-                    FieldKind::resolve(&field.ty).move_or_clone_field(&ident)
-                })
-                .collect::<Vec<_>>();
+    Ok(ast)
+}
 
-            quote! { #ident ( #(#unnamed_fields),* ) => #ident ( #(#cloned),* ) }
+/// Combines all `errors` into one so that they are all reported at once.
+fn combine_errors(mut errors: impl Iterator<Item = syn::Error>) -> syn::Result<()> {
+    match errors.next() {
+        Some(mut first) => {
+            errors.for_each(|e| first.combine(e));
+            Err(first)
         }
+        None => Ok(()),
     }
+}
 
-    fn combine_impl(
-        &self,
-        borrowed: proc_macro2::TokenStream,
-        name: &syn::Ident,
-        params: proc_macro2::TokenStream,
-        owned: proc_macro2::TokenStream,
-        body: proc_macro2::TokenStream,
-    ) -> proc_macro2::TokenStream {
-        quote! {
-            impl #borrowed #name #params {
-                /// Returns a version of `self` with all fields converted to owning versions.
-                pub fn into_owned(self) -> #name #owned { #body }
-            }
+/// Unions are only supported with `Copy` and `'static` fields, which need no conversion, so an
+/// error for unions with lifetime parameters. `derive` is the name of the derive for the message.
+fn check_union(ast: &syn::DeriveInput, derive: &str) -> syn::Result<()> {
+    if let syn::Data::Union(_) = ast.data {
+        if let Some(param) = ast.generics.lifetimes().next() {
+            return Err(
+                derive_into_owned_core::diagnostics::UNSUPPORTED_SHAPE.error(
+                    param,
+                    format!(
+                        "{} can only be derived for unions without lifetime parameters, as only \
+                     unions of `Copy` and `'static` fields are supported",
+                        derive
+                    ),
+                ),
+            );
         }
     }
+    Ok(())
 }
 
-struct BorrowedGen;
-
-impl BodyGenerator for BorrowedGen {
-    fn quote_rhs_params(&self, ast: &syn::DeriveInput) -> Vec<proc_macro2::TokenStream> {
-        let owned_lifetime_params = ast.generics.lifetimes().map(|_| quote! { '__borrowedgen });
-        let owned_type_params = ast.generics.type_params().map(|ty| {
-            let ident = &ty.ident;
-            quote! { #ident }
+/// Errors for the trait bounds naming the lifetime parameters and the types bounded by traits
+/// in the where clause naming them, which the derive named `derive` replaces with
+/// `replacement` in the type it returns.
+fn check_lifetime_bounds(
+    ast: &syn::DeriveInput,
+    derive: &str,
+    replacement: &str,
+) -> syn::Result<()> {
+    let errors = derive_into_owned_core::helpers::bounds_naming_lifetimes(&ast.generics)
+        .into_iter()
+        .map(|bound| {
+            derive_into_owned_core::diagnostics::LIFETIME_BOUND.error(
+                bound,
+                format!(
+                    "the bound `{}` names a lifetime parameter, which {} replaces with {}; \
+                     require the trait for all lifetimes with `for<'x>` instead",
+                    quote::quote!(#bound),
+                    derive,
+                    replacement,
+                ),
+            )
         });
-        owned_lifetime_params
-            .chain(owned_type_params)
-            .collect::<Vec<_>>()
-    }
-
-    fn visit_struct(&self, data: &syn::DataStruct) -> proc_macro2::TokenStream {
-        let fields = data.fields.iter().map(|field| {
-            let ident = field.ident.as_ref().expect("this fields has no ident (4)");
-            let field_ref = quote! { self.#ident };
-            let code = FieldKind::resolve(&field.ty).borrow_or_clone(&field_ref);
-            quote! { #ident: #code }
+    let predicates = derive_into_owned_core::helpers::bounded_types_naming_lifetimes(&ast.generics)
+        .into_iter()
+        .map(|predicate| {
+            let ty = &predicate.bounded_ty;
+            derive_into_owned_core::diagnostics::LIFETIME_BOUND.error(
+                predicate,
+                format!(
+                    "the bounded type `{}` names a lifetime parameter, which {} replaces with {}; \
+                     bound it for all lifetimes with `for<'x>` instead",
+                    quote::quote!(#ty),
+                    derive,
+                    replacement,
+                ),
+            )
         });
-        quote! { { #(#fields),* } }
-    }
 
-    fn visit_enum_data(
-        &self,
-        ident: proc_macro2::TokenStream,
-        variant: &syn::Variant,
-    ) -> proc_macro2::TokenStream {
-        if variant.fields.is_empty() {
-            return quote!(#ident => #ident);
-        }
+    combine_errors(errors.chain(predicates))
+}
 
-        let fields_are_named = variant.fields.iter().any(|field| field.ident.is_some());
-        if fields_are_named {
-            let idents = variant
-                .fields
-                .iter()
-                .map(|field| field.ident.as_ref().expect("this fields has no ident (5)"));
-            let cloned = variant.fields.iter().map(|field| {
-                let ident = field.ident.as_ref().expect("this fields has no ident (6)");
-                let ident = quote! { #ident };
-                let code = FieldKind::resolve(&field.ty).borrow_or_clone(&ident);
-                quote! { #ident: #code }
-            });
-            quote! { #ident { #(ref #idents),* } => #ident { #(#cloned),* } }
-        } else {
-            let idents = (0..variant.fields.len())
-                .map(|index| quote::format_ident!("x{}", index))
-                .collect::<Vec<_>>();
-            let cloned = idents
-                .iter()
-                .zip(variant.fields.iter())
-                .map(|(ident, field)| {
-                    let ident = quote! { #ident };
-                    FieldKind::resolve(&field.ty).borrow_or_clone(&ident)
-                })
-                .collect::<Vec<_>>();
-            quote! { #ident ( #(ref #idents),* ) => #ident ( #(#cloned),* ) }
+/// Errors for the fields holding mutable references, which neither derive can clone or own,
+/// leaving out the fields for which `exempt` returns true. `hint` tells what can be done about
+/// the field.
+fn check_mut_references(
+    ast: &syn::DeriveInput,
+    exempt: impl Fn(&syn::Field) -> bool,
+    hint: &str,
+) -> syn::Result<()> {
+    let errors = all_fields(ast).into_iter().filter_map(|(name, field)| {
+        if exempt(field) {
+            return None;
         }
-    }
+        let reference = derive_into_owned_core::helpers::find_mut_reference(&field.ty)?;
+        Some(
+            derive_into_owned_core::diagnostics::MUT_REFERENCE_FIELD.error(
+                reference,
+                format!(
+                    "field `{}` holds the mutable reference `{}`, which {}",
+                    name,
+                    quote::ToTokens::to_token_stream(reference),
+                    hint
+                ),
+            ),
+        )
+    });
+
+    combine_errors(errors)
+}
 
-    fn combine_impl(
-        &self,
-        borrowed: proc_macro2::TokenStream,
-        name: &syn::Ident,
-        params: proc_macro2::TokenStream,
-        owned: proc_macro2::TokenStream,
-        body: proc_macro2::TokenStream,
-    ) -> proc_macro2::TokenStream {
-        quote! {
-            impl #borrowed #name #params {
-                /// Returns a clone of `self` that shares all the "Cow-alike" data with `self`.
-                pub fn borrowed<'__borrowedgen>(&'__borrowedgen self) -> #name #owned { #body }
-            }
-        }
+/// All the fields of a struct or of all enum variants, named like `field` or `Variant::0`.
+fn all_fields(ast: &syn::DeriveInput) -> Vec<(String, &syn::Field)> {
+    match ast.data {
+        syn::Data::Struct(ref data) => named_fields(&data.fields, ""),
+        syn::Data::Enum(ref data) => data
+            .variants
+            .iter()
+            .flat_map(|variant| named_fields(&variant.fields, &format!("{}::", variant.ident)))
+            .collect(),
+        syn::Data::Union(_) => Vec::new(),
     }
 }
+
+/// The fields with their names or indices, prefixed with `prefix`.
+fn named_fields<'a>(fields: &'a syn::Fields, prefix: &str) -> Vec<(String, &'a syn::Field)> {
+    fields
+        .iter()
+        .enumerate()
+        .map(|(index, field)| match field.ident {
+            Some(ref ident) => (format!("{}{}", prefix, ident), field),
+            None => (format!("{}{}", prefix, index), field),
+        })
+        .collect()
+}
@@ -0,0 +1,375 @@
+//! Generation of the owned mirror type enabled with `#[into_owned(mirror)]`.
+
+use quote::{format_ident, quote};
+
+use crate::attrs::{ContainerAttrs, FieldAttrs, MirrorAttrs};
+use derive_into_owned_core::diagnostics;
+use derive_into_owned_core::helpers::{construct, without_lifetimes};
+use derive_into_owned_core::{FieldKind, Strategy};
+
+/// Generates `FooOwned` for `Foo<'a>` along with `impl<'a> From<Foo<'a>> for FooOwned`.
+pub fn generate(
+    ast: &syn::DeriveInput,
+    container: &ContainerAttrs,
+    attrs: &MirrorAttrs,
+) -> syn::Result<proc_macro2::TokenStream> {
+    let name = &ast.ident;
+    let mirror = attrs
+        .name
+        .clone()
+        .unwrap_or_else(|| format_ident!("{}Owned", name));
+    let vis = &ast.vis;
+
+    let lifetimes = ast
+        .generics
+        .lifetimes()
+        .map(|def| def.lifetime.clone())
+        .collect::<Vec<_>>();
+
+    let mirror_generics = without_lifetimes(&ast.generics);
+    let (_, mirror_ty_generics, mirror_where_clause) = mirror_generics.split_for_impl();
+    let (impl_generics, ty_generics, where_clause) = ast.generics.split_for_impl();
+
+    // the body of `PartialEq::eq` for structs, the arms of its match for enums
+    let mut comparisons = Vec::new();
+
+    let (item, body) = match ast.data {
+        syn::Data::Struct(ref data) => {
+            let fields = MirrorFields::new(&data.fields, container, attrs, &lifetimes, false)?;
+            let definitions = &fields.definitions;
+            let definition = match data.fields {
+                syn::Fields::Named(_) => quote! { #mirror_where_clause { #(#definitions),* } },
+                syn::Fields::Unnamed(_) => quote! { ( #(#definitions),* ) #mirror_where_clause; },
+                syn::Fields::Unit => quote! { #mirror_where_clause; },
+            };
+            let body = construct(&data.fields, quote! { #mirror }, &fields.conversions);
+            let (this, other) = fields.patterns_for_eq(quote! { #name }, quote! { #mirror });
+            let comparison = fields.comparison();
+            comparisons.push(quote! {
+                let #this = self;
+                let #other = other;
+                #comparison
+            });
+
+            (quote! { struct #mirror #mirror_generics #definition }, body)
+        }
+        syn::Data::Enum(ref data) => {
+            let mut variants = Vec::new();
+            let mut arms = Vec::new();
+
+            for variant in &data.variants {
+                let ident = &variant.ident;
+                let fields =
+                    MirrorFields::new(&variant.fields, container, attrs, &lifetimes, true)?;
+                let definitions = &fields.definitions;
+                let docs = variant
+                    .attrs
+                    .iter()
+                    .filter(|attr| attr.path.is_ident("doc"));
+                let cbor = minicbor_attrs(&variant.attrs, attrs);
+                variants.push(match variant.fields {
+                    syn::Fields::Named(_) => {
+                        quote! { #(#docs)* #(#cbor)* #ident { #(#definitions),* } }
+                    }
+                    syn::Fields::Unnamed(_) => {
+                        quote! { #(#docs)* #(#cbor)* #ident ( #(#definitions),* ) }
+                    }
+                    syn::Fields::Unit => quote! { #(#docs)* #(#cbor)* #ident },
+                });
+
+                let patterns = &fields.patterns;
+                let body = construct(
+                    &variant.fields,
+                    quote! { #mirror::#ident },
+                    &fields.conversions,
+                );
+                arms.push(quote! { #name::#ident { #(#patterns,)* .. } => #body });
+
+                let (this, other) =
+                    fields.patterns_for_eq(quote! { #name::#ident }, quote! { #mirror::#ident });
+                let comparison = fields.comparison();
+                comparisons.push(quote! { (#this, #other) => #comparison });
+            }
+
+            (
+                quote! { enum #mirror #mirror_generics #mirror_where_clause { #(#variants),* } },
+                quote! { match value { #(#arms,)* } },
+            )
+        }
+        syn::Data::Union(_) => {
+            return Err(diagnostics::UNSUPPORTED_SHAPE.error(
+                name,
+                "mirror types can only be generated for structs and enums",
+            ))
+        }
+    };
+
+    let mut derives = attrs.derives.clone();
+    if attrs.minicbor.is_some() {
+        derives.push(syn::parse_quote!(::minicbor::Encode));
+        derives.push(syn::parse_quote!(::minicbor::Decode));
+    }
+    let derives = if derives.is_empty() {
+        quote! {}
+    } else {
+        quote! { #[derive(#(#derives),*)] }
+    };
+    let forwarded = &attrs.attrs;
+    let cbor = minicbor_attrs(&ast.attrs, attrs);
+
+    let doc = format!("Owned version of [`{}`].", name);
+    let partial_eq = if attrs.partial_eq.is_some() {
+        let body = match ast.data {
+            syn::Data::Enum(ref data) if data.variants.is_empty() => quote! { match *self {} },
+            syn::Data::Enum(_) => quote! {
+                #[allow(unreachable_patterns)]
+                match (self, other) {
+                    #(#comparisons,)*
+                    _ => false,
+                }
+            },
+            _ => quote! { #(#comparisons)* },
+        };
+
+        let mut generics = ast.generics.clone();
+        let bounds = ast
+            .generics
+            .type_params()
+            .map(|param| -> syn::WherePredicate {
+                let ident = &param.ident;
+                syn::parse_quote! { #ident: ::std::cmp::PartialEq }
+            })
+            .collect::<Vec<_>>();
+        generics.make_where_clause().predicates.extend(bounds);
+        let (_, _, where_clause) = generics.split_for_impl();
+
+        quote! {
+            impl #impl_generics ::std::cmp::PartialEq<#mirror #mirror_ty_generics> for #name #ty_generics #where_clause {
+                fn eq(&self, other: &#mirror #mirror_ty_generics) -> bool {
+                    #body
+                }
+            }
+
+            impl #impl_generics ::std::cmp::PartialEq<#name #ty_generics> for #mirror #mirror_ty_generics #where_clause {
+                fn eq(&self, other: &#name #ty_generics) -> bool {
+                    ::std::cmp::PartialEq::eq(other, self)
+                }
+            }
+        }
+    } else {
+        quote! {}
+    };
+
+    Ok(quote! {
+        #[doc = #doc]
+        #derives
+        #(#[#forwarded])*
+        #(#cbor)*
+        #vis #item
+
+        impl #impl_generics ::std::convert::From<#name #ty_generics> for #mirror #mirror_ty_generics #where_clause {
+            fn from(value: #name #ty_generics) -> Self {
+                #body
+            }
+        }
+
+        #partial_eq
+    })
+}
+
+/// The fields of a struct or of an enum variant in the mirror type.
+struct MirrorFields {
+    /// The fields as declared in the mirror type
+    definitions: Vec<proc_macro2::TokenStream>,
+    /// Converting the original fields into the mirrored ones
+    conversions: Vec<proc_macro2::TokenStream>,
+    /// Binding the original fields by value, when they are matched instead of accessed through
+    /// `value`
+    patterns: Vec<proc_macro2::TokenStream>,
+    /// Binding the original fields by reference for `PartialEq`, as `this_field`
+    this_patterns: Vec<proc_macro2::TokenStream>,
+    /// Binding the mirrored fields by reference for `PartialEq`, as `mirror_field`
+    mirror_patterns: Vec<proc_macro2::TokenStream>,
+    /// Comparing the bound original fields with the mirrored ones
+    comparisons: Vec<proc_macro2::TokenStream>,
+}
+
+impl MirrorFields {
+    fn new(
+        fields: &syn::Fields,
+        container: &ContainerAttrs,
+        mirror: &MirrorAttrs,
+        lifetimes: &[syn::Lifetime],
+        matched: bool,
+    ) -> syn::Result<Self> {
+        let mut mirrored = MirrorFields {
+            definitions: Vec::new(),
+            conversions: Vec::new(),
+            patterns: Vec::new(),
+            this_patterns: Vec::new(),
+            mirror_patterns: Vec::new(),
+            comparisons: Vec::new(),
+        };
+        // the index of the next field in the mirror type, where the skipped fields are missing
+        let mut mirror_index = 0;
+
+        for (index, field) in fields.iter().enumerate() {
+            let attrs = FieldAttrs::parse(&field.attrs)?.mirror.unwrap_or_default();
+            if let (None, Some(ref name)) = (&field.ident, &attrs.name) {
+                return Err(
+                    diagnostics::INAPPLICABLE_OPTION.error(name, "tuple fields cannot be renamed")
+                );
+            }
+            if attrs.skip {
+                continue;
+            }
+
+            let kind = FieldKind::resolve(&field.ty, &container.classifier);
+            let (this, other) = match field.ident {
+                Some(ref ident) => (
+                    format_ident!("this_{}", ident),
+                    format_ident!("mirror_{}", ident),
+                ),
+                None => (
+                    format_ident!("this_{}", index),
+                    format_ident!("mirror_{}", index),
+                ),
+            };
+            let mirror_member = match (&field.ident, &attrs.name) {
+                (Some(_), Some(name)) => quote! { #name },
+                (Some(ident), None) => quote! { #ident },
+                (None, _) => {
+                    let index = syn::Index::from(mirror_index);
+                    quote! { #index }
+                }
+            };
+            mirror_index += 1;
+
+            let (member, binding, pattern) = match field.ident {
+                Some(ref ident) => (quote! { #ident }, ident.clone(), quote! { #ident }),
+                None => {
+                    let index = syn::Index::from(index);
+                    let binding = format_ident!("field_{}", index);
+                    let pattern = quote! { #index: #binding };
+                    (quote! { #index }, binding, pattern)
+                }
+            };
+
+            let vis = &field.vis;
+            let ty = match attrs.ty {
+                Some(ref ty) => ty.clone(),
+                None => kind.mirror_type_with(&field.ty, lifetimes, mirror.leaves()),
+            };
+            // the documentation of the fields still applies to the owned versions
+            let docs = field.attrs.iter().filter(|attr| attr.path.is_ident("doc"));
+            let cbor = minicbor_attrs(&field.attrs, mirror);
+
+            let source = if matched {
+                mirrored.patterns.push(pattern);
+                quote! { #binding }
+            } else {
+                quote! { value.#member }
+            };
+            let mut code = if mirror.arc.is_some() || mirror.bytes.is_some() {
+                kind.walk_by_value(&source, &Strategy::MirrorInto)
+            } else {
+                kind.move_into_mirror(&source)
+            };
+            if attrs.ty.is_some() {
+                code = quote! { ::std::convert::Into::into(#code) };
+            }
+
+            mirrored.this_patterns.push(quote! { #member: #this });
+            mirrored
+                .mirror_patterns
+                .push(quote! { #mirror_member: #other });
+            mirrored.comparisons.push(match attrs.ty {
+                Some(_) => quote! { *#this == *#other },
+                None => kind.eq_mirror(&field.ty, lifetimes, &quote! { #this }, &quote! { #other }),
+            });
+
+            match field.ident {
+                Some(ref ident) => {
+                    let ident = attrs.name.as_ref().unwrap_or(ident);
+                    mirrored
+                        .definitions
+                        .push(quote! { #(#docs)* #(#cbor)* #vis #ident: #ty });
+                    mirrored.conversions.push(quote! { #ident: #code });
+                }
+                None => {
+                    mirrored
+                        .definitions
+                        .push(quote! { #(#docs)* #(#cbor)* #vis #ty });
+                    mirrored.conversions.push(code);
+                }
+            }
+        }
+
+        Ok(mirrored)
+    }
+
+    /// The patterns binding the fields of the original and the mirror type for `PartialEq`,
+    /// given the paths to the struct or the variant.
+    fn patterns_for_eq(
+        &self,
+        this: proc_macro2::TokenStream,
+        mirror: proc_macro2::TokenStream,
+    ) -> (proc_macro2::TokenStream, proc_macro2::TokenStream) {
+        let this_patterns = &self.this_patterns;
+        let mirror_patterns = &self.mirror_patterns;
+
+        (
+            quote! { #this { #(#this_patterns,)* .. } },
+            quote! { #mirror { #(#mirror_patterns,)* .. } },
+        )
+    }
+
+    /// Compares the fields bound by [`MirrorFields::patterns_for_eq`].
+    fn comparison(&self) -> proc_macro2::TokenStream {
+        if self.comparisons.is_empty() {
+            return quote! { true };
+        }
+
+        let comparisons = &self.comparisons;
+        quote! { #(#comparisons)&&* }
+    }
+}
+
+/// The `#[n(..)]`, `#[b(..)]` and `#[cbor(..)]` attributes of minicbor among `attrs` for
+/// `mirror(minicbor)`, with the borrowing `b` indices turned into `n` as the mirror type owns its
+/// data.
+fn minicbor_attrs(attrs: &[syn::Attribute], mirror: &MirrorAttrs) -> Vec<syn::Attribute> {
+    if mirror.minicbor.is_none() {
+        return Vec::new();
+    }
+
+    let owned_index = |path: &mut syn::Path| {
+        if path.is_ident("b") {
+            *path = syn::parse_quote!(n);
+        }
+    };
+
+    attrs
+        .iter()
+        .filter(|attr| {
+            ["n", "b", "cbor"]
+                .iter()
+                .any(|name| attr.path.is_ident(name))
+        })
+        .map(|attr| {
+            let mut attr = attr.clone();
+            owned_index(&mut attr.path);
+            if let Ok(syn::Meta::List(mut list)) = attr.parse_meta() {
+                if list.path.is_ident("cbor") {
+                    for nested in list.nested.iter_mut() {
+                        if let syn::NestedMeta::Meta(syn::Meta::List(ref mut index)) = *nested {
+                            owned_index(&mut index.path);
+                        }
+                    }
+                    attr = syn::parse_quote!(#[#list]);
+                }
+            }
+            attr
+        })
+        .collect()
+}
@@ -0,0 +1,159 @@
+//! `#[into_owned_module]` which adds `#[derive(IntoOwned)]` to every struct and enum of a module,
+//! for generated code which cannot be annotated type by type.
+//!
+//! Generated code is often brought in with `include!`, which is expanded only after the
+//! attribute, so the included files are read and spliced into the module here. Their paths can
+//! use `concat!` and `env!`, like `include!(concat!(env!("OUT_DIR"), "/person.rs"))`.
+
+use std::path::{Path, PathBuf};
+
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::punctuated::Punctuated;
+
+use derive_into_owned_core::diagnostics;
+
+pub fn into_owned_module(args: TokenStream, mut module: syn::ItemMod) -> syn::Result<TokenStream> {
+    if !args.is_empty() {
+        return Err(
+            diagnostics::MODULE.error(args, "`into_owned_module` does not take any arguments")
+        );
+    }
+
+    let mut included = Vec::new();
+    derive_in_module(&mut module, &mut included)?;
+
+    // the files are read here, so the compiler needs to be told about them to notice changes
+    let tracked = included.iter().map(|path| {
+        let path = path.display().to_string();
+        quote!(
+            const _: &[u8] = include_bytes!(#path);
+        )
+    });
+
+    if let Some((_, ref mut items)) = module.content {
+        items.extend(tracked.map(syn::Item::Verbatim));
+    }
+
+    Ok(quote!(#module))
+}
+
+fn derive_in_module(module: &mut syn::ItemMod, included: &mut Vec<PathBuf>) -> syn::Result<()> {
+    let items =
+        match module.content {
+            Some((_, ref mut items)) => items,
+            None => return Err(diagnostics::MODULE.error(
+                &module.ident,
+                "`into_owned_module` needs the module contents inline, use `include!` to bring \
+                 in the contents of a file",
+            )),
+        };
+
+    let mut expanded = Vec::with_capacity(items.len());
+
+    for item in items.drain(..) {
+        match item {
+            syn::Item::Macro(ref item) if item.mac.path.is_ident("include") => {
+                let path = include_path(&item.mac)?;
+                let file = read_file(&path, &item.mac)?;
+                // the inner attributes of the file, like `#![allow(non_snake_case)]`, apply to
+                // the whole module
+                module.attrs.extend(file.attrs.into_iter().map(|mut attr| {
+                    attr.style = syn::AttrStyle::Outer;
+                    attr
+                }));
+                expanded.extend(file.items);
+                included.push(path);
+            }
+            item => expanded.push(item),
+        }
+    }
+
+    for item in expanded.iter_mut() {
+        match *item {
+            syn::Item::Struct(ref mut item) => add_derive(&mut item.attrs),
+            syn::Item::Enum(ref mut item) => add_derive(&mut item.attrs),
+            syn::Item::Mod(ref mut item) => derive_in_module(item, included)?,
+            _ => {}
+        }
+    }
+
+    *items = expanded;
+    Ok(())
+}
+
+/// Adds `#[derive(IntoOwned)]` unless the type already derives it.
+fn add_derive(attrs: &mut Vec<syn::Attribute>) {
+    let derived = attrs.iter().any(|attr| {
+        attr.path.is_ident("derive")
+            && attr
+                .parse_args_with(Punctuated::<syn::Path, syn::Token![,]>::parse_terminated)
+                .map(|paths| {
+                    paths.iter().any(|path| {
+                        path.segments
+                            .last()
+                            .map_or(false, |segment| segment.ident == "IntoOwned")
+                    })
+                })
+                .unwrap_or(false)
+    });
+
+    if !derived {
+        attrs.push(syn::parse_quote!(#[derive(::derive_into_owned::IntoOwned)]));
+    }
+}
+
+fn include_path(mac: &syn::Macro) -> syn::Result<PathBuf> {
+    let path = PathBuf::from(eval_str(&mac.parse_body()?)?);
+
+    if path.is_relative() {
+        return Err(diagnostics::MODULE.error(
+            mac,
+            format!(
+                "`into_owned_module` cannot resolve the relative path `{}`, start it with \
+                 `concat!(env!(\"CARGO_MANIFEST_DIR\"), ...)` or `concat!(env!(\"OUT_DIR\"), ...)`",
+                path.display()
+            ),
+        ));
+    }
+
+    Ok(path)
+}
+
+/// Evaluates the string literals, `concat!` and `env!` of an `include!` path.
+fn eval_str(expr: &syn::Expr) -> syn::Result<String> {
+    match *expr {
+        syn::Expr::Lit(syn::ExprLit {
+            lit: syn::Lit::Str(ref lit),
+            ..
+        }) => Ok(lit.value()),
+        syn::Expr::Macro(ref expr) if expr.mac.path.is_ident("concat") => expr
+            .mac
+            .parse_body_with(Punctuated::<syn::Expr, syn::Token![,]>::parse_terminated)?
+            .iter()
+            .map(eval_str)
+            .collect(),
+        syn::Expr::Macro(ref expr) if expr.mac.path.is_ident("env") => {
+            let name = expr.mac.parse_body::<syn::LitStr>()?;
+            std::env::var(name.value()).map_err(|_| {
+                diagnostics::MODULE.error(
+                    &name,
+                    format!("environment variable `{}` not defined", name.value()),
+                )
+            })
+        }
+        _ => Err(diagnostics::MODULE.error(
+            expr,
+            "`into_owned_module` only understands string literals, `concat!` and `env!` in \
+             `include!` paths",
+        )),
+    }
+}
+
+fn read_file(path: &Path, mac: &syn::Macro) -> syn::Result<syn::File> {
+    let error =
+        |msg: String| diagnostics::MODULE.error(mac, format!("{}: {}", path.display(), msg));
+
+    let contents = std::fs::read_to_string(path).map_err(|e| error(e.to_string()))?;
+    syn::parse_str(&contents).map_err(|e| error(e.to_string()))
+}
@@ -0,0 +1,76 @@
+//! `#[into_owned(no_std)]`, for crates using `alloc` without `std`.
+//!
+//! The generated code spells out `::std` paths everywhere, which are rewritten afterwards to the
+//! same items in `::core` or `::alloc`. Items only found in `std`, like `HashMap`, are left as
+//! they are and fail to compile.
+
+use proc_macro2::{Group, Ident, TokenStream, TokenTree};
+
+/// The modules of `std` which are re-exports of `core` or `alloc`.
+const CORE_MODULES: &[&str] = &[
+    "cell", "clone", "cmp", "convert", "default", "fmt", "hash", "iter", "marker", "mem", "ops",
+    "option", "result",
+];
+const ALLOC_MODULES: &[&str] = &[
+    "borrow",
+    "boxed",
+    "collections",
+    "ffi",
+    "rc",
+    "string",
+    "sync",
+    "vec",
+];
+
+/// Replaces every `::std::module` in `tokens` with `::core::module` or `::alloc::module`.
+pub fn rewrite(tokens: TokenStream) -> TokenStream {
+    let tokens = tokens.into_iter().collect::<Vec<_>>();
+    let mut rewritten = Vec::with_capacity(tokens.len());
+
+    for (index, token) in tokens.iter().enumerate() {
+        let token = match *token {
+            TokenTree::Ident(ref ident) if ident == "std" => {
+                let is_absolute =
+                    index >= 2 && is_path_separator(&tokens[index - 2], &tokens[index - 1]);
+                let module = match tokens.get(index + 1..index + 4) {
+                    Some([first, second, TokenTree::Ident(module)])
+                        if is_path_separator(first, second) =>
+                    {
+                        Some(module.to_string())
+                    }
+                    _ => None,
+                };
+
+                match module {
+                    Some(ref module) if is_absolute && CORE_MODULES.contains(&&**module) => {
+                        TokenTree::Ident(Ident::new("core", ident.span()))
+                    }
+                    Some(ref module) if is_absolute && ALLOC_MODULES.contains(&&**module) => {
+                        TokenTree::Ident(Ident::new("alloc", ident.span()))
+                    }
+                    _ => token.clone(),
+                }
+            }
+            TokenTree::Group(ref group) => {
+                let mut new = Group::new(group.delimiter(), rewrite(group.stream()));
+                new.set_span(group.span());
+                TokenTree::Group(new)
+            }
+            ref other => other.clone(),
+        };
+        rewritten.push(token);
+    }
+
+    rewritten.into_iter().collect()
+}
+
+fn is_path_separator(first: &TokenTree, second: &TokenTree) -> bool {
+    match (first, second) {
+        (TokenTree::Punct(first), TokenTree::Punct(second)) => {
+            first.as_char() == ':'
+                && second.as_char() == ':'
+                && first.spacing() == proc_macro2::Spacing::Joint
+        }
+        _ => false,
+    }
+}
@@ -0,0 +1,363 @@
+//! Generation of the ownership report enabled with `#[into_owned(ownership)]`, of
+//! `borrowed_strs` enabled with `#[into_owned(borrowed_strs)]`, of `try_reborrow_original`
+//! enabled with `#[into_owned(try_reborrow_original)]` and the `OwnershipDebug` derive.
+
+use quote::{format_ident, quote};
+use syn::ext::IdentExt;
+use syn::parse_quote;
+
+use derive_into_owned_core::diagnostics;
+use derive_into_owned_core::helpers::{hygienic, uses_type_param};
+use derive_into_owned_core::FieldKind;
+
+use crate::attrs::{ContainerAttrs, FieldAttrs, ParamAttrs};
+use crate::{all_fields, without_param_attrs};
+
+/// Generates `FooOwnership` with a `bool` for each field which can borrow, along with
+/// `fn ownership(&self) -> FooOwnership`.
+pub fn generate(
+    ast: &syn::DeriveInput,
+    attrs: &ContainerAttrs,
+) -> syn::Result<proc_macro2::TokenStream> {
+    let data = match ast.data {
+        syn::Data::Struct(ref data) => data,
+        _ => {
+            return Err(diagnostics::UNSUPPORTED_SHAPE.error(
+                &ast.ident,
+                "ownership reports can only be generated for structs",
+            ))
+        }
+    };
+
+    let name = &ast.ident;
+    let report = format_ident!("{}Ownership", name);
+    let vis = &ast.vis;
+    let (impl_generics, ty_generics, where_clause) = ast.generics.split_for_impl();
+
+    let (fields, checks): (Vec<_>, Vec<_>) = data
+        .fields
+        .iter()
+        .enumerate()
+        .filter_map(|(index, field)| {
+            let kind = FieldKind::resolve(&field.ty, &attrs.classifier);
+            if kind == derive_into_owned_core::FieldKind::JustMoved {
+                return None;
+            }
+
+            let (ident, member) = match field.ident {
+                Some(ref ident) => (ident.clone(), quote! { #ident }),
+                None => {
+                    let index = syn::Index::from(index);
+                    (format_ident!("field_{}", index), quote! { #index })
+                }
+            };
+            let check = kind.is_owned(&quote! { (&self.#member) })?;
+
+            let doc = format!("`true` when `{}` owns all of its data.", member);
+            let field = quote! {
+                #[doc = #doc]
+                pub #ident: bool
+            };
+            Some((field, quote! { #ident: #check }))
+        })
+        .unzip();
+
+    let doc = format!(
+        "Which fields of [`{}`] own their data, returned by [`{}::ownership`].",
+        name, name
+    );
+
+    Ok(quote! {
+        #[doc = #doc]
+        #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+        #vis struct #report {
+            #(#fields),*
+        }
+
+        impl #impl_generics #name #ty_generics #where_clause {
+            /// Reports which of the fields borrowing data currently own it.
+            pub fn ownership(&self) -> #report {
+                #report {
+                    #(#checks),*
+                }
+            }
+        }
+    })
+}
+
+/// Generates `fn borrowed_strs(&self) -> Vec<&str>` listing the strings still borrowed by the
+/// value, including the ones of nested "Cow-alike" values.
+pub fn generate_borrowed_strs(
+    ast: &syn::DeriveInput,
+    attrs: &ContainerAttrs,
+) -> syn::Result<proc_macro2::TokenStream> {
+    let name = &ast.ident;
+    let (impl_generics, ty_generics, where_clause) = ast.generics.split_for_impl();
+    let out = quote! { strs };
+
+    let arm = |path: proc_macro2::TokenStream, fields: &syn::Fields| {
+        let (bindings, pushes): (Vec<_>, Vec<_>) = fields
+            .iter()
+            .enumerate()
+            .filter_map(|(index, field)| {
+                let (ident, member) = match field.ident {
+                    Some(ref ident) => (ident.clone(), quote! { #ident }),
+                    None => {
+                        let index = syn::Index::from(index);
+                        (format_ident!("field_{}", index), quote! { #index })
+                    }
+                };
+                let push = FieldKind::resolve(&field.ty, &attrs.classifier)
+                    .push_borrowed_strs(&quote! { #ident }, &out)?;
+
+                Some((quote! { #member: ref #ident }, push))
+            })
+            .unzip();
+
+        quote! { #path { #(#bindings,)* .. } => { #(#pushes)* } }
+    };
+
+    let arms = match ast.data {
+        syn::Data::Struct(ref data) => vec![arm(quote! { #name }, &data.fields)],
+        syn::Data::Enum(ref data) => data
+            .variants
+            .iter()
+            .map(|variant| {
+                let ident = &variant.ident;
+                arm(quote! { #name::#ident }, &variant.fields)
+            })
+            .collect(),
+        syn::Data::Union(_) => {
+            return Err(diagnostics::UNSUPPORTED_SHAPE.error(
+                name,
+                "borrowed strings can only be listed for structs and enums",
+            ))
+        }
+    };
+
+    Ok(quote! {
+        impl #impl_generics #name #ty_generics #where_clause {
+            /// Lists the strings which are still borrowed, for finding out what keeps the value
+            /// from being `'static`.
+            pub fn borrowed_strs(&self) -> ::std::vec::Vec<&str> {
+                #[allow(unused_mut)]
+                let mut #out = ::std::vec::Vec::new();
+                match *self {
+                    #(#arms)*
+                }
+                #out
+            }
+        }
+    })
+}
+
+/// Generates `fn try_reborrow_original(&self) -> Option<Self>` which copies the value keeping its
+/// lifetimes when all of its `Cow`s are borrowed. `input` is expected to still have the
+/// attributes of the type parameters.
+pub fn generate_try_reborrow(
+    input: &syn::DeriveInput,
+    attrs: &ContainerAttrs,
+) -> syn::Result<proc_macro2::TokenStream> {
+    let bounded = without_param_attrs(&with_bounds(
+        input,
+        attrs,
+        parse_quote!(::std::clone::Clone),
+    )?)?;
+
+    let name = &bounded.ident;
+    let (impl_generics, ty_generics, where_clause) = bounded.generics.split_for_impl();
+
+    let arm = |path: proc_macro2::TokenStream, fields: &syn::Fields| {
+        let mut bindings = Vec::new();
+        let mut values = Vec::new();
+
+        for (index, field) in fields.iter().enumerate() {
+            let (ident, member) = match field.ident {
+                Some(ref ident) => (ident.clone(), quote! { #ident }),
+                None => {
+                    let index = syn::Index::from(index);
+                    (format_ident!("field_{}", index), quote! { #index })
+                }
+            };
+            bindings.push(quote! { #member: ref #ident });
+
+            let field_attrs = FieldAttrs::parse(&field.attrs)?;
+            let kind = if field_attrs.with.is_some() || field_attrs.skip {
+                FieldKind::JustMoved
+            } else {
+                FieldKind::resolve(&field.ty, &attrs.classifier)
+            };
+            let value = kind.try_reborrow(&quote! { #ident });
+            values.push(quote! { #member: (#value)? });
+        }
+
+        Ok(quote! {
+            #path { #(#bindings,)* } => ::std::option::Option::Some(#path { #(#values,)* }),
+        })
+    };
+
+    let arms = match bounded.data {
+        syn::Data::Struct(ref data) => vec![arm(quote! { #name }, &data.fields)?],
+        syn::Data::Enum(ref data) => data
+            .variants
+            .iter()
+            .map(|variant| {
+                let ident = &variant.ident;
+                arm(quote! { #name::#ident }, &variant.fields)
+            })
+            .collect::<syn::Result<_>>()?,
+        syn::Data::Union(_) => {
+            return Err(diagnostics::UNSUPPORTED_SHAPE.error(
+                name,
+                "`try_reborrow_original` can only be generated for structs and enums",
+            ))
+        }
+    };
+
+    Ok(quote! {
+        impl #impl_generics #name #ty_generics #where_clause {
+            /// Copies the value keeping the original lifetimes if all of its `Cow`s are borrowed,
+            /// which only copies the references, or returns `None` if any of them is owned.
+            pub fn try_reborrow_original(&self) -> ::std::option::Option<Self> {
+                match *self {
+                    #(#arms)*
+                }
+            }
+        }
+    })
+}
+
+/// The `OwnershipDebug` derive, generating `fn ownership_debug(&self) -> Box<dyn Debug>` which
+/// prints the value like `Debug` with the `Cow`s marked as borrowed or owned.
+pub fn derive_ownership_debug(ast: &syn::DeriveInput) -> syn::Result<proc_macro2::TokenStream> {
+    let attrs = ContainerAttrs::parse(&ast.attrs)?;
+    let bounded = without_param_attrs(&with_bounds(ast, &attrs, parse_quote!(::std::fmt::Debug))?)?;
+
+    let name = &ast.ident;
+    let (impl_generics, ty_generics, where_clause) = bounded.generics.split_for_impl();
+    let wrapper = quote! { OwnershipDebug };
+    // the fields are bound by their names within the closure taking the formatter
+    let f = hygienic("f");
+
+    let arm = |path: proc_macro2::TokenStream, label: &syn::Ident, fields: &syn::Fields| {
+        let label = label.unraw().to_string();
+        let mut bindings = Vec::new();
+        let mut entries = Vec::new();
+
+        for (index, field) in fields.iter().enumerate() {
+            let (ident, member) = match field.ident {
+                Some(ref ident) => (ident.clone(), quote! { #ident }),
+                None => {
+                    let index = syn::Index::from(index);
+                    (format_ident!("field_{}", index), quote! { #index })
+                }
+            };
+            bindings.push(quote! { #member: ref #ident });
+
+            let field_attrs = FieldAttrs::parse(&field.attrs)?;
+            let var = quote! { #ident };
+            let value = if field_attrs.with.is_some() || field_attrs.skip {
+                var
+            } else {
+                FieldKind::resolve(&field.ty, &attrs.classifier).ownership_debug(&var, &wrapper)
+            };
+
+            entries.push(match field.ident {
+                Some(ref ident) => {
+                    let ident = ident.unraw().to_string();
+                    quote! { .field(#ident, &#value) }
+                }
+                None => quote! { .field(&#value) },
+            });
+        }
+
+        let debug = match *fields {
+            syn::Fields::Named(_) => quote! { #f.debug_struct(#label) #(#entries)* .finish() },
+            syn::Fields::Unnamed(_) => quote! { #f.debug_tuple(#label) #(#entries)* .finish() },
+            syn::Fields::Unit => quote! { #f.write_str(#label) },
+        };
+
+        Ok(quote! { #path { #(#bindings,)* } => #debug, })
+    };
+
+    let arms = match ast.data {
+        syn::Data::Struct(ref data) => vec![arm(quote! { #name }, name, &data.fields)?],
+        syn::Data::Enum(ref data) => data
+            .variants
+            .iter()
+            .map(|variant| {
+                let ident = &variant.ident;
+                arm(quote! { #name::#ident }, ident, &variant.fields)
+            })
+            .collect::<syn::Result<_>>()?,
+        syn::Data::Union(_) => {
+            return Err(diagnostics::UNSUPPORTED_SHAPE.error(
+                name,
+                "OwnershipDebug can only be derived for structs and enums",
+            ))
+        }
+    };
+
+    let expanded = quote! {
+        impl #impl_generics #name #ty_generics #where_clause {
+            /// Returns a value printing `self` like `Debug` does, except that every `Cow` is
+            /// prefixed with `[borrowed]` or `[owned]`.
+            pub fn ownership_debug(&self) -> ::std::boxed::Box<dyn ::std::fmt::Debug + '_> {
+                struct #wrapper<F>(F);
+
+                impl<F> ::std::fmt::Debug for #wrapper<F>
+                where
+                    F: Fn(&mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result,
+                {
+                    fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
+                        (self.0)(f)
+                    }
+                }
+
+                ::std::boxed::Box::new(#wrapper(move |#f: &mut ::std::fmt::Formatter<'_>| {
+                    match *self {
+                        #(#arms)*
+                    }
+                }))
+            }
+        }
+    };
+
+    if attrs.no_std {
+        Ok(crate::no_std::rewrite(expanded))
+    } else {
+        Ok(expanded)
+    }
+}
+
+/// `ast` with `T: bound` added to its where clause for the type parameters used in the fields,
+/// unless marked with `#[into_owned(skip_bounds)]` on the type or the parameter.
+fn with_bounds(
+    ast: &syn::DeriveInput,
+    attrs: &ContainerAttrs,
+    bound: syn::Path,
+) -> syn::Result<syn::DeriveInput> {
+    let fields = all_fields(ast);
+
+    let mut bounds: Vec<syn::WherePredicate> = Vec::new();
+    for param in ast.generics.type_params() {
+        if attrs.skip_bounds || ParamAttrs::parse(&param.attrs)?.skip_bounds {
+            continue;
+        }
+
+        let ident = &param.ident;
+        if fields
+            .iter()
+            .any(|(_, field)| uses_type_param(&field.ty, ident))
+        {
+            bounds.push(parse_quote! { #ident: #bound });
+        }
+    }
+
+    let mut ast = ast.clone();
+    if !bounds.is_empty() {
+        let where_clause = ast.generics.make_where_clause();
+        where_clause.predicates.extend(bounds);
+    }
+    Ok(ast)
+}
@@ -0,0 +1,215 @@
+//! Feeds generated type definitions through the derives, which must return either code or
+//! diagnostics but never panic.
+
+use proptest::prelude::*;
+
+/// The field types, built from these leaves wrapped in containers.
+fn field_type() -> impl Strategy<Value = String> {
+    let leaf = prop_oneof![
+        Just("Cow<'a, str>"),
+        Just("Cow<'a, [u8]>"),
+        Just("std::borrow::Cow<'a, [T]>"),
+        Just("&'a str"),
+        Just("&'a [u8]"),
+        Just("&'a mut [u8]"),
+        Just("&'static str"),
+        Just("String"),
+        Just("u32"),
+        Just("T"),
+        Just("Foo<'a>"),
+        Just("Foo<'a, 'b, T>"),
+        Just("Bar"),
+        Just("[u8; N]"),
+        Just("(u8, Cow<'a, str>)"),
+        Just("()"),
+        Just("fn(&str) -> u8"),
+        Just("Box<dyn Fn(&'a str)>"),
+        Just("ZeroVec<'a, u16>"),
+        Just("bumpalo::collections::Vec<'a, u8>"),
+        Just("<T as Trait>::Assoc"),
+        Just("impl_trait::Bar<'_>"),
+    ]
+    .prop_map(String::from);
+
+    leaf.prop_recursive(3, 16, 2, |inner| {
+        prop_oneof![
+            inner.clone().prop_map(|ty| format!("Option<{}>", ty)),
+            inner.clone().prop_map(|ty| format!("Vec<{}>", ty)),
+            inner.clone().prop_map(|ty| format!("HashSet<{}>", ty)),
+            inner.clone().prop_map(|ty| format!("Box<{}>", ty)),
+            inner.clone().prop_map(|ty| format!("Pin<Box<{}>>", ty)),
+            inner
+                .clone()
+                .prop_map(|ty| format!("std::ops::Bound<{}>", ty)),
+            inner.clone().prop_map(|ty| format!("Arc<{}>", ty)),
+            inner.clone().prop_map(|ty| format!("Rc<{}>", ty)),
+            inner.clone().prop_map(|ty| format!("OnceCell<{}>", ty)),
+            inner.clone().prop_map(|ty| format!("&'a {}", ty)),
+            inner.clone().prop_map(|ty| format!("[{}]", ty)),
+            inner.clone().prop_map(|ty| format!("({},)", ty)),
+            (inner.clone(), inner.clone()).prop_map(|(k, v)| format!("HashMap<{}, {}>", k, v)),
+            (inner.clone(), inner).prop_map(|(k, v)| format!("DashMap<{}, {}>", k, v)),
+        ]
+    })
+}
+
+/// At most one option per field, most fields have none.
+fn field_attrs() -> impl Strategy<Value = String> {
+    let attr = prop::sample::select(vec![
+        "#[into_owned(skip)]",
+        "#[into_owned(with = \"convert\")]",
+        "#[into_owned(clone_box = \"Trait::clone_box\")]",
+        "#[into_owned(expect = \"cow\")]",
+        "#[into_owned(mirror(name = \"other\"))]",
+        "#[into_owned(mirror(ty = \"Box<str>\"))]",
+        "#[into_owned(unknown)]",
+        "#[borrowed(clone)]",
+        "#[borrowed(as_ref)]",
+        "#[borrowed(view)]",
+        "#[n(0)]",
+    ]);
+
+    prop::option::weighted(0.2, attr).prop_map(|attr| attr.unwrap_or_default().to_string())
+}
+
+fn fields(named: bool) -> impl Strategy<Value = String> {
+    prop::collection::vec((field_attrs(), field_type()), 0..4).prop_map(move |fields| {
+        let fields = fields
+            .into_iter()
+            .enumerate()
+            .map(|(index, (attrs, ty))| match named {
+                true => format!("{} f{}: {}", attrs, index, ty),
+                false => format!("{} {}", attrs, ty),
+            })
+            .collect::<Vec<_>>()
+            .join(", ");
+
+        match named {
+            true => format!("{{ {} }}", fields),
+            false => format!("({})", fields),
+        }
+    })
+}
+
+/// A type named `Foo` with the `generics` and the `where_clause`.
+fn body(generics: String, where_clause: String) -> impl Strategy<Value = String> {
+    let named = format!("Foo<{}> {}", generics, where_clause);
+    let variant = prop_oneof![
+        fields(true),
+        fields(false),
+        Just(String::new()),
+        Just(" = 1".to_string()),
+    ];
+
+    prop_oneof![
+        fields(true).prop_map({
+            let named = named.clone();
+            move |fields| format!("struct {} {}", named, fields)
+        }),
+        fields(false).prop_map({
+            let where_clause = where_clause.clone();
+            move |fields| format!("struct Foo<{}> {} {};", generics, fields, where_clause)
+        }),
+        Just(format!("struct {};", named)),
+        fields(true).prop_map({
+            let named = named.clone();
+            move |fields| format!("union {} {}", named, fields)
+        }),
+        prop::collection::vec(variant, 0..4).prop_map(move |variants| {
+            let variants = variants
+                .into_iter()
+                .enumerate()
+                .map(|(index, fields)| format!("V{}{}", index, fields))
+                .collect::<Vec<_>>()
+                .join(", ");
+            format!("enum {} {{ {} }}", named, variants)
+        }),
+    ]
+}
+
+fn generics() -> impl Strategy<Value = (String, String)> {
+    let params = prop::sample::subsequence(
+        vec![
+            "'a",
+            "'b: 'a",
+            "T",
+            "U: Clone + 'a",
+            "#[into_owned(skip_bounds)] V",
+            "const N: usize",
+        ],
+        0..4,
+    );
+    let where_clause = prop_oneof![
+        Just(""),
+        Just("where T: Clone"),
+        Just("where for<'x> &'x T: IntoIterator"),
+        Just("where 'a: 'static"),
+    ];
+
+    (params, where_clause)
+        .prop_map(|(params, where_clause)| (params.join(", "), where_clause.to_string()))
+}
+
+fn container_attrs() -> impl Strategy<Value = String> {
+    prop::sample::subsequence(
+        vec![
+            "#[into_owned(mirror)]",
+            "#[into_owned(mirror(arc, bytes, derive(Debug)))]",
+            "#[into_owned(mirror(partial_eq))]",
+            "#[into_owned(leak)]",
+            "#[into_owned(intern)]",
+            "#[into_owned(shrink)]",
+            "#[into_owned(strict)]",
+            "#[into_owned(unwrap_or_clone)]",
+            "#[into_owned(transparent)]",
+            "#[into_owned(try_into_owned)]",
+            "#[into_owned(take_owned)]",
+            "#[into_owned(map_cows)]",
+            "#[into_owned(borrowed_strs)]",
+            "#[into_owned(field_info)]",
+            "#[into_owned(ownership)]",
+            "#[into_owned(no_std)]",
+            "#[into_owned(assert_send_sync)]",
+            "#[into_owned(trait_impl)]",
+            "#[into_owned(msrv = \"1.56\")]",
+            "#[into_owned(known(Bar = \"clone\"))]",
+            "#[into_owned(wrapper(Arc = \"map\"))]",
+            "#[into_owned(free_fn)]",
+            "#[into_owned(boxed)]",
+            "#[into_owned(arc)]",
+            "#[into_owned(blocking)]",
+            "#[into_owned(yoke)]",
+            "#[into_owned = \"value\"]",
+            "#[borrowed(warn_clones)]",
+            "#[borrowed(view)]",
+            "#[borrowed(view(name = \"FooRef\"))]",
+        ],
+        0..4,
+    )
+    .prop_map(|attrs| attrs.concat())
+}
+
+fn derive_input() -> impl Strategy<Value = syn::DeriveInput> {
+    let body = generics().prop_flat_map(|(generics, where_clause)| body(generics, where_clause));
+
+    (container_attrs(), body).prop_filter_map("does not parse", |(attrs, body)| {
+        syn::parse_str(&format!("{} {}", attrs, body)).ok()
+    })
+}
+
+proptest! {
+    #![proptest_config(ProptestConfig::with_cases(512))]
+
+    #[cfg(feature = "into_owned")]
+    #[test]
+    fn into_owned_never_panics(ast in derive_input()) {
+        let _ = crate::into_owned::derive_into_owned(&ast);
+        let _ = crate::ownership::derive_ownership_debug(&ast);
+    }
+
+    #[cfg(feature = "borrowed")]
+    #[test]
+    fn borrowed_never_panics(ast in derive_input()) {
+        let _ = crate::borrowed::derive_borrowed(&ast);
+    }
+}
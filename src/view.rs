@@ -0,0 +1,284 @@
+//! Generation of the borrowed view type enabled with `#[borrowed(view)]`.
+
+use quote::{format_ident, quote};
+
+use derive_into_owned_core::diagnostics;
+use derive_into_owned_core::helpers::{
+    collect_segments, construct, is_string, is_vec, last_type_argument, replace_lifetimes, ungroup,
+};
+use derive_into_owned_core::FieldKind;
+
+use crate::attrs::{BorrowedFieldAttrs, ContainerAttrs, MirrorAttrs};
+
+/// Generates `FooRef<'b>` for `Foo<'a>` along with `fn view(&self) -> FooRef<'_>`.
+pub fn generate(
+    ast: &syn::DeriveInput,
+    container: &ContainerAttrs,
+    attrs: &MirrorAttrs,
+) -> syn::Result<proc_macro2::TokenStream> {
+    let name = &ast.ident;
+    let view = attrs
+        .name
+        .clone()
+        .unwrap_or_else(|| format_ident!("{}Ref", name));
+    let vis = &ast.vis;
+
+    let lifetime = syn::Lifetime::new("'view", proc_macro2::Span::call_site());
+    let lifetimes = ast
+        .generics
+        .lifetimes()
+        .map(|def| def.lifetime.clone())
+        .collect::<Vec<_>>();
+    let context = Context {
+        container,
+        lifetimes: &lifetimes,
+        lifetime: &lifetime,
+    };
+
+    let mut view_generics =
+        derive_into_owned_core::helpers::with_lifetimes_replaced(&ast.generics, &lifetime);
+    view_generics.params.insert(
+        0,
+        syn::GenericParam::Lifetime(syn::LifetimeDef::new(lifetime.clone())),
+    );
+    let (_, _, view_where_clause) = view_generics.split_for_impl();
+    let view_ty_arguments = ast.generics.params.iter().filter_map(|param| match param {
+        syn::GenericParam::Type(ty) => Some(&ty.ident),
+        syn::GenericParam::Const(constant) => Some(&constant.ident),
+        syn::GenericParam::Lifetime(_) => None,
+    });
+    let (impl_generics, ty_generics, where_clause) = ast.generics.split_for_impl();
+
+    let (item, body) = match ast.data {
+        syn::Data::Struct(ref data) => {
+            let fields = ViewFields::new(&data.fields, &context, false)?;
+            let definitions = &fields.definitions;
+            let definition = match data.fields {
+                syn::Fields::Named(_) => quote! { #view_where_clause { #(#definitions),* } },
+                syn::Fields::Unnamed(_) => quote! { ( #(#definitions),* ) #view_where_clause; },
+                syn::Fields::Unit => quote! { #view_where_clause; },
+            };
+            let body = construct(&data.fields, quote! { #view }, &fields.views);
+
+            (quote! { struct #view #view_generics #definition }, body)
+        }
+        syn::Data::Enum(ref data) => {
+            let mut variants = Vec::new();
+            let mut arms = Vec::new();
+
+            for variant in &data.variants {
+                let ident = &variant.ident;
+                let fields = ViewFields::new(&variant.fields, &context, true)?;
+                let definitions = &fields.definitions;
+                let docs = variant
+                    .attrs
+                    .iter()
+                    .filter(|attr| attr.path.is_ident("doc"));
+                variants.push(match variant.fields {
+                    syn::Fields::Named(_) => quote! { #(#docs)* #ident { #(#definitions),* } },
+                    syn::Fields::Unnamed(_) => quote! { #(#docs)* #ident ( #(#definitions),* ) },
+                    syn::Fields::Unit => quote! { #(#docs)* #ident },
+                });
+
+                let patterns = &fields.patterns;
+                let body = construct(&variant.fields, quote! { #view::#ident }, &fields.views);
+                arms.push(quote! { #name::#ident { #(#patterns),* } => #body });
+            }
+
+            (
+                quote! { enum #view #view_generics #view_where_clause { #(#variants),* } },
+                quote! { match *self { #(#arms,)* } },
+            )
+        }
+        syn::Data::Union(_) => {
+            return Err(diagnostics::UNSUPPORTED_SHAPE.error(
+                name,
+                "view types can only be generated for structs and enums",
+            ))
+        }
+    };
+
+    let derives = if attrs.derives.is_empty() {
+        quote! {}
+    } else {
+        let derives = &attrs.derives;
+        quote! { #[derive(#(#derives),*)] }
+    };
+    let forwarded = &attrs.attrs;
+
+    let doc = format!("Borrowed view of [`{}`].", name);
+
+    Ok(quote! {
+        #[doc = #doc]
+        #derives
+        #(#[#forwarded])*
+        #vis #item
+
+        impl #impl_generics #name #ty_generics #where_clause {
+            /// Returns a view of `self` borrowing all of its data.
+            pub fn view(&self) -> #view<'_, #(#view_ty_arguments),*> {
+                #body
+            }
+        }
+    })
+}
+
+struct Context<'a> {
+    container: &'a ContainerAttrs,
+    /// The lifetime parameters of the original type
+    lifetimes: &'a [syn::Lifetime],
+    /// The lifetime parameter of the view type
+    lifetime: &'a syn::Lifetime,
+}
+
+impl Context<'_> {
+    /// The type of the field in the view type along with the code converting `var`, a reference
+    /// to the original field, into it.
+    fn view_field(
+        &self,
+        field: &syn::Field,
+        var: &proc_macro2::TokenStream,
+    ) -> syn::Result<(syn::Type, proc_macro2::TokenStream)> {
+        let lifetime = self.lifetime;
+        let ty = ungroup(&field.ty);
+        let retimed = |ty: &syn::Type| replace_lifetimes(ty, self.lifetimes, lifetime);
+
+        if BorrowedFieldAttrs::parse(&field.attrs)?.view {
+            return Ok((self.nested_view_type(ty)?, quote! { #var.view() }));
+        }
+
+        let kind = FieldKind::resolve(ty, &self.container.classifier);
+        Ok(match (kind, ty) {
+            (FieldKind::PlainCow | FieldKind::StrCow, _) if last_type_argument(ty).is_some() => {
+                let borrowed = retimed(&last_type_argument(ty).unwrap());
+                (
+                    syn::parse_quote! { &#lifetime #borrowed },
+                    quote! { &**#var },
+                )
+            }
+            (FieldKind::Reference, syn::Type::Reference(ref reference)) => {
+                let borrowed = retimed(&reference.elem);
+                (
+                    syn::parse_quote! { &#lifetime #borrowed },
+                    quote! { &**#var },
+                )
+            }
+            (FieldKind::AssumedCow | FieldKind::Known(_), _) => {
+                (retimed(ty), quote! { #var.borrowed() })
+            }
+            // owned strings and vectors are viewed as slices, like the `Cow`s
+            (FieldKind::JustMoved, syn::Type::Path(ref p))
+                if p.qself.is_none() && is_string(&collect_segments(&p.path)) =>
+            {
+                (
+                    syn::parse_quote! { &#lifetime str },
+                    quote! { #var.as_str() },
+                )
+            }
+            (FieldKind::JustMoved, syn::Type::Path(ref p))
+                if p.qself.is_none() && is_vec(&collect_segments(&p.path)).is_some() =>
+            {
+                let elem = retimed(&is_vec(&collect_segments(&p.path)).unwrap());
+                (
+                    syn::parse_quote! { &#lifetime [#elem] },
+                    quote! { #var.as_slice() },
+                )
+            }
+            _ => {
+                let ty = retimed(ty);
+                (syn::parse_quote! { &#lifetime #ty }, quote! { #var })
+            }
+        })
+    }
+
+    /// `BarRef<'view, T>` for `Bar<'a, T>`.
+    fn nested_view_type(&self, ty: &syn::Type) -> syn::Result<syn::Type> {
+        let mut path = match *ty {
+            syn::Type::Path(ref p) if p.qself.is_none() => p.path.clone(),
+            _ => {
+                return Err(diagnostics::INAPPLICABLE_OPTION
+                    .error(ty, "only fields of named types can use their view type"))
+            }
+        };
+
+        let segment = path.segments.last_mut().expect("paths have segments");
+        segment.ident = format_ident!("{}Ref", segment.ident);
+
+        let mut arguments = vec![syn::GenericArgument::Lifetime(self.lifetime.clone())];
+        if let syn::PathArguments::AngleBracketed(ref generics) = segment.arguments {
+            arguments.extend(
+                generics
+                    .args
+                    .iter()
+                    .filter(|arg| !matches!(arg, syn::GenericArgument::Lifetime(_)))
+                    .cloned(),
+            );
+        }
+        segment.arguments = syn::PathArguments::AngleBracketed(syn::parse_quote! {
+            <#(#arguments),*>
+        });
+
+        Ok(replace_lifetimes(
+            &syn::Type::Path(syn::TypePath { qself: None, path }),
+            self.lifetimes,
+            self.lifetime,
+        ))
+    }
+}
+
+/// The fields of a struct or of an enum variant in the view type.
+struct ViewFields {
+    /// The fields as declared in the view type
+    definitions: Vec<proc_macro2::TokenStream>,
+    /// Borrowing the original fields as the viewed ones
+    views: Vec<proc_macro2::TokenStream>,
+    /// Binding the original fields by reference, when they are matched instead of accessed
+    /// through `self`
+    patterns: Vec<proc_macro2::TokenStream>,
+}
+
+impl ViewFields {
+    fn new(fields: &syn::Fields, context: &Context<'_>, matched: bool) -> syn::Result<Self> {
+        let mut viewed = ViewFields {
+            definitions: Vec::new(),
+            views: Vec::new(),
+            patterns: Vec::new(),
+        };
+
+        for (index, field) in fields.iter().enumerate() {
+            let (member, binding) = match field.ident {
+                Some(ref ident) => (quote! { #ident }, ident.clone()),
+                None => {
+                    let index = syn::Index::from(index);
+                    (quote! { #index }, format_ident!("field_{}", index))
+                }
+            };
+
+            let source = if matched {
+                viewed.patterns.push(quote! { #member: ref #binding });
+                quote! { #binding }
+            } else {
+                quote! { (&self.#member) }
+            };
+            let (ty, code) = context.view_field(field, &source)?;
+
+            let vis = &field.vis;
+            // the documentation of the fields still applies to the views
+            let docs = field.attrs.iter().filter(|attr| attr.path.is_ident("doc"));
+            match field.ident {
+                Some(ref ident) => {
+                    viewed
+                        .definitions
+                        .push(quote! { #(#docs)* #vis #ident: #ty });
+                    viewed.views.push(quote! { #ident: #code });
+                }
+                None => {
+                    viewed.definitions.push(quote! { #(#docs)* #vis #ty });
+                    viewed.views.push(code);
+                }
+            }
+        }
+
+        Ok(viewed)
+    }
+}
@@ -0,0 +1,153 @@
+//! Generation of the `yoke::Yoke` based helpers enabled with `#[into_owned(yoke)]` and
+//! `#[into_owned(self_owned)]`.
+//!
+//! The generated code refers to `yoke` and `stable_deref_trait`, both of which need to be
+//! dependencies of the crate using the derive.
+
+use quote::{format_ident, quote};
+
+use derive_into_owned_core::diagnostics;
+use derive_into_owned_core::helpers::without_lifetimes;
+
+/// Generates `fn into_yoke(cart, f) -> Yoke<Foo<'static>, C>`.
+pub fn generate(ast: &syn::DeriveInput) -> syn::Result<proc_macro2::TokenStream> {
+    require_single_lifetime(ast)?;
+
+    let name = &ast.ident;
+
+    let generics = without_lifetimes(&ast.generics);
+    let (impl_generics, _, where_clause) = generics.split_for_impl();
+    let predicates = where_clause.map(|where_clause| &where_clause.predicates);
+
+    let owned = owned_type_arguments(ast);
+
+    Ok(quote! {
+        impl #impl_generics #name #owned #where_clause {
+            /// Attaches the value `f` parses out of `cart` to the `cart`, so that the borrowed
+            /// value can be kept around without converting it into an owned one.
+            pub fn into_yoke<C, F>(cart: C, f: F) -> ::yoke::Yoke<#name #owned, C>
+            where
+                C: ::stable_deref_trait::StableDeref,
+                <C as ::std::ops::Deref>::Target: 'static,
+                F: for<'de> ::std::ops::FnOnce(&'de <C as ::std::ops::Deref>::Target) -> <#name #owned as ::yoke::Yokeable<'de>>::Output,
+                #predicates
+            {
+                ::yoke::Yoke::<#name #owned, C>::attach_to_cart(cart, f)
+            }
+        }
+    })
+}
+
+/// Generates `FooSelfOwned<C>` wrapping a `Yoke<Foo<'static>, C>` with constructors and
+/// accessors.
+pub fn generate_self_owned(ast: &syn::DeriveInput) -> syn::Result<proc_macro2::TokenStream> {
+    require_single_lifetime(ast)?;
+
+    let name = &ast.ident;
+    let vis = &ast.vis;
+    let wrapper = format_ident!("{}SelfOwned", name);
+
+    let owned = owned_type_arguments(ast);
+
+    let mut generics = without_lifetimes(&ast.generics);
+    generics.params.push(syn::parse_quote! { C });
+    generics
+        .make_where_clause()
+        .predicates
+        .push(syn::parse_quote! { #name #owned: for<'y> ::yoke::Yokeable<'y> });
+
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+    let predicates = where_clause.map(|where_clause| &where_clause.predicates);
+
+    let doc = format!(
+        "[`{}`] kept together with the buffer `C` it borrows from.",
+        name
+    );
+
+    Ok(quote! {
+        #[doc = #doc]
+        #vis struct #wrapper #generics #where_clause {
+            yoke: ::yoke::Yoke<#name #owned, C>,
+        }
+
+        impl #impl_generics #wrapper #ty_generics
+        where
+            C: ::stable_deref_trait::StableDeref,
+            <C as ::std::ops::Deref>::Target: 'static,
+            #predicates
+        {
+            /// Parses the value with `f` out of `cart` and keeps the two together.
+            pub fn new<F>(cart: C, f: F) -> Self
+            where
+                F: for<'de> ::std::ops::FnOnce(&'de <C as ::std::ops::Deref>::Target) -> <#name #owned as ::yoke::Yokeable<'de>>::Output,
+            {
+                #wrapper {
+                    yoke: ::yoke::Yoke::<#name #owned, C>::attach_to_cart(cart, f),
+                }
+            }
+
+            /// Like `new` but for parsers which can fail.
+            pub fn try_new<E, F>(cart: C, f: F) -> ::std::result::Result<Self, E>
+            where
+                F: for<'de> ::std::ops::FnOnce(&'de <C as ::std::ops::Deref>::Target) -> ::std::result::Result<<#name #owned as ::yoke::Yokeable<'de>>::Output, E>,
+            {
+                ::yoke::Yoke::<#name #owned, C>::try_attach_to_cart(cart, f)
+                    .map(|yoke| #wrapper { yoke })
+            }
+        }
+
+        impl #impl_generics #wrapper #ty_generics #where_clause {
+            /// Returns the value borrowing from the buffer, the return type is `&'a Foo<'a>`.
+            pub fn get<'a>(&'a self) -> &'a <#name #owned as ::yoke::Yokeable<'a>>::Output {
+                self.yoke.get()
+            }
+
+            /// Returns the buffer the value borrows from.
+            pub fn backing_cart(&self) -> &C {
+                self.yoke.backing_cart()
+            }
+
+            /// Drops the value and returns the buffer it borrowed from.
+            pub fn into_backing_cart(self) -> C {
+                self.yoke.into_backing_cart()
+            }
+
+            /// Returns the underlying `yoke::Yoke`.
+            pub fn into_yoke(self) -> ::yoke::Yoke<#name #owned, C> {
+                self.yoke
+            }
+        }
+
+        impl #impl_generics ::std::convert::From<::yoke::Yoke<#name #owned, C>> for #wrapper #ty_generics #where_clause {
+            fn from(yoke: ::yoke::Yoke<#name #owned, C>) -> Self {
+                #wrapper { yoke }
+            }
+        }
+    })
+}
+
+fn require_single_lifetime(ast: &syn::DeriveInput) -> syn::Result<()> {
+    if ast.generics.lifetimes().count() != 1 {
+        return Err(diagnostics::INAPPLICABLE_OPTION.error(
+            &ast.ident,
+            "yoke support requires exactly one lifetime parameter",
+        ));
+    }
+    Ok(())
+}
+
+/// `<'static, T>` for `Foo<'a, T>`.
+fn owned_type_arguments(ast: &syn::DeriveInput) -> proc_macro2::TokenStream {
+    let params = ast.generics.params.iter().map(|param| match param {
+        syn::GenericParam::Lifetime(_) => quote! { 'static },
+        syn::GenericParam::Type(ty) => {
+            let ident = &ty.ident;
+            quote! { #ident }
+        }
+        syn::GenericParam::Const(c) => {
+            let ident = &c.ident;
+            quote! { #ident }
+        }
+    });
+    quote! { < #(#params),* > }
+}
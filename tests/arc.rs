@@ -0,0 +1,55 @@
+#![cfg(feature = "arc")]
+
+#[macro_use]
+extern crate derive_into_owned;
+
+use std::borrow::Cow;
+use std::sync::Arc;
+
+#[derive(IntoOwned)]
+#[into_owned(arc)]
+struct Message<'a> {
+    topic: Cow<'a, str>,
+    retries: u32,
+}
+
+#[derive(IntoOwned)]
+#[into_owned(free_fn = "detach", arc)]
+struct Event<'a> {
+    name: Cow<'a, str>,
+}
+
+#[test]
+fn into_owned_arc() {
+    let topic = "news".to_string();
+
+    let shared: Arc<Message<'static>> = Message {
+        topic: Cow::Borrowed(&topic),
+        retries: 3,
+    }
+    .into_owned_arc();
+    drop(topic);
+
+    let handles = (0..4)
+        .map(|_| {
+            let shared = Arc::clone(&shared);
+            std::thread::spawn(move || (shared.topic.len(), shared.retries))
+        })
+        .collect::<Vec<_>>();
+
+    for handle in handles {
+        assert_eq!(handle.join().unwrap(), (4, 3));
+    }
+}
+
+#[test]
+fn free_fn_arc() {
+    let name = "started".to_string();
+
+    let shared: Arc<Event<'static>> = detach_arc(Event {
+        name: Cow::Borrowed(&name),
+    });
+    drop(name);
+
+    assert_eq!(shared.name, "started");
+}
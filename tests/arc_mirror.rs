@@ -0,0 +1,72 @@
+#![allow(dead_code)]
+
+#[macro_use]
+extern crate derive_into_owned;
+
+use std::borrow::Cow;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+#[derive(IntoOwned)]
+#[into_owned(mirror(arc, derive(Debug, Clone, PartialEq)))]
+struct Message<'a> {
+    topic: Cow<'a, str>,
+    payload: Cow<'a, [u8]>,
+    source: &'a str,
+    path: Option<Cow<'a, Path>>,
+    tags: Vec<Cow<'a, str>>,
+    headers: HashMap<Cow<'a, str>, Option<&'a [u8]>>,
+    retries: u32,
+}
+
+#[derive(IntoOwned)]
+#[into_owned(mirror(arc, derive(Debug, PartialEq)))]
+enum Event<'a> {
+    Text(Cow<'a, str>),
+    Empty,
+}
+
+#[test]
+fn arc_leaves() {
+    let data = String::from("data");
+
+    let message = Message {
+        topic: Cow::Borrowed(&data),
+        payload: Cow::Owned(data.as_bytes().to_vec()),
+        source: &data[1..],
+        path: Some(Cow::Borrowed(Path::new(&data))),
+        tags: vec![Cow::Borrowed(&data[..1])],
+        headers: vec![(Cow::Borrowed(&data[..2]), Some(data.as_bytes()))]
+            .into_iter()
+            .collect(),
+        retries: 1,
+    };
+
+    let owned: MessageOwned = message.into();
+    drop(data);
+
+    let expected = MessageOwned {
+        topic: Arc::from("data"),
+        payload: Arc::from(&b"data"[..]),
+        source: Arc::from("ata"),
+        path: Some(PathBuf::from("data")),
+        tags: vec![Arc::from("d")],
+        headers: vec![(Arc::from("da"), Some(Arc::from(&b"data"[..])))]
+            .into_iter()
+            .collect(),
+        retries: 1,
+    };
+    assert_eq!(owned, expected);
+
+    // clones share the buffers instead of copying them
+    let cloned = owned.clone();
+    assert!(Arc::ptr_eq(&owned.topic, &cloned.topic));
+}
+
+#[test]
+fn arc_enum() {
+    let event: EventOwned = Event::Text(Cow::Borrowed("text")).into();
+
+    assert_eq!(event, EventOwned::Text(Arc::from("text")));
+}
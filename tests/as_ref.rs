@@ -0,0 +1,85 @@
+#[macro_use]
+extern crate derive_into_owned;
+
+use std::borrow::Cow;
+
+/// A string wrapper without the `borrowed` method expected of "Cow-alike" types.
+#[derive(Debug, Clone, PartialEq)]
+struct Name<'a>(Cow<'a, str>);
+
+impl AsRef<str> for Name<'_> {
+    fn as_ref(&self) -> &str {
+        &self.0
+    }
+}
+
+impl AsRef<[u8]> for Name<'_> {
+    fn as_ref(&self) -> &[u8] {
+        self.0.as_bytes()
+    }
+}
+
+impl<'a> From<&'a str> for Name<'a> {
+    fn from(s: &'a str) -> Self {
+        Name(Cow::Borrowed(s))
+    }
+}
+
+/// Only has a single `AsRef`.
+#[derive(Debug, Clone, PartialEq)]
+struct Tag<'a>(&'a str);
+
+impl AsRef<str> for Tag<'_> {
+    fn as_ref(&self) -> &str {
+        self.0
+    }
+}
+
+impl<'a> From<&'a str> for Tag<'a> {
+    fn from(s: &'a str) -> Self {
+        Tag(s)
+    }
+}
+
+#[derive(Borrowed, Debug)]
+#[borrowed(warn_clones)]
+struct Person<'a> {
+    #[borrowed(as_ref = "str")]
+    name: Name<'a>,
+    #[borrowed(as_ref)]
+    tag: Tag<'a>,
+}
+
+#[derive(Borrowed, Debug)]
+enum Entry<'a> {
+    Named {
+        #[borrowed(as_ref = "str")]
+        name: Name<'a>,
+    },
+    Tagged(#[borrowed(as_ref)] Tag<'a>),
+}
+
+#[test]
+fn rebuilt_from_as_ref() {
+    let person = Person {
+        name: Name(Cow::Owned(String::from("name"))),
+        tag: Tag("tag"),
+    };
+
+    let borrowed = person.borrowed();
+    assert!(matches!(borrowed.name.0, Cow::Borrowed("name")));
+    assert_eq!(borrowed.tag, Tag("tag"));
+
+    let entry = Entry::Named {
+        name: Name(Cow::Owned(String::from("entry"))),
+    };
+    assert!(matches!(
+        entry.borrowed(),
+        Entry::Named {
+            name: Name(Cow::Borrowed("entry"))
+        }
+    ));
+
+    let entry = Entry::Tagged(Tag("tagged"));
+    assert!(matches!(entry.borrowed(), Entry::Tagged(Tag("tagged"))));
+}
@@ -2,6 +2,7 @@
 extern crate derive_into_owned;
 
 use std::borrow::Cow;
+use std::collections::HashMap;
 
 #[derive(IntoOwned, Borrowed)]
 struct Foo<'a> {
@@ -37,6 +38,95 @@ fn borrowed() {
 
 fn test<'b, 'a: 'b>(lives_longer: &Foo<'a>, lives_less: Foo<'b>) {
     drop(lives_less);
-    #[allow(clippy::drop_ref)]
+    #[allow(dropping_references)]
     drop(lives_longer);
 }
+
+#[derive(Borrowed, Debug, PartialEq)]
+struct Index<'a> {
+    words: HashMap<&'a str, Vec<u32>>,
+    name: Cow<'a, str>,
+}
+
+#[test]
+fn map_keyed_by_references() {
+    let text = String::from("a b");
+    let index = Index {
+        words: text.split(' ').map(|word| (word, vec![1])).collect(),
+        name: Cow::Borrowed(&text),
+    };
+
+    let borrowed: Index<'_> = index.borrowed();
+
+    assert_eq!(borrowed, index);
+    assert!(std::ptr::eq(
+        borrowed.words.keys().find(|k| **k == "a").unwrap().as_ptr(),
+        text.as_ptr()
+    ));
+}
+
+struct Summary {
+    name: String,
+    present: bool,
+}
+
+impl<'a> From<Foo<'a>> for Summary {
+    fn from(value: Foo<'a>) -> Self {
+        Summary {
+            name: value.a.into_owned(),
+            present: value.b.is_some(),
+        }
+    }
+}
+
+#[test]
+fn with_borrowed() {
+    let value = Foo {
+        a: Cow::Owned(String::from("owned")),
+        b: None,
+    };
+
+    let summary = value.with_borrowed(Summary::from);
+    assert_eq!(summary.name, "owned");
+    assert!(!summary.present);
+
+    let borrowed = value.with_borrowed(|value| matches!(value.a, Cow::Borrowed("owned")));
+    assert!(borrowed);
+}
+
+#[derive(IntoOwned, Borrowed, Debug, PartialEq)]
+struct Name<'a>(Cow<'a, str>);
+
+#[derive(IntoOwned, Borrowed, Debug, PartialEq)]
+struct Entry<'a>(Name<'a>, Option<Cow<'a, [u8]>>, u32);
+
+#[test]
+fn tuple_structs() {
+    let entry = Entry(Name(Cow::Borrowed("key")), Some(Cow::Borrowed(b"value")), 7).into_owned();
+
+    let borrowed = entry.borrowed();
+    assert!(matches!((borrowed.0).0, Cow::Borrowed("key")));
+    assert!(matches!(borrowed.1, Some(Cow::Borrowed(b"value"))));
+    assert_eq!(borrowed, entry);
+}
+
+#[derive(IntoOwned, Borrowed, Debug, PartialEq)]
+struct Span<'b, 'b1: 'b> {
+    text: Cow<'b, str>,
+    source: Cow<'b1, str>,
+}
+
+#[test]
+fn lifetimes_named_like_the_borrow() {
+    let source = String::from("let x = 1;");
+    let span = Span {
+        text: Cow::Borrowed(&source[4..5]),
+        source: Cow::Borrowed(&source),
+    };
+
+    let borrowed: Span<'_, '_> = span.borrowed();
+    assert_eq!(borrowed, span);
+
+    let text = span.with_borrowed(|span: Span<'_, '_>| span.text.len());
+    assert_eq!(text, 1);
+}
@@ -0,0 +1,49 @@
+#[macro_use]
+extern crate derive_into_owned;
+
+use std::borrow::Cow;
+use std::collections::HashMap;
+
+#[derive(IntoOwned, Debug)]
+#[into_owned(borrowed_strs)]
+struct Message<'a> {
+    topic: Cow<'a, str>,
+    headers: HashMap<Cow<'a, str>, Option<Cow<'a, str>>>,
+    payload: Cow<'a, [u8]>,
+    sender: Option<Box<Sender<'a>>>,
+    id: u64,
+}
+
+#[derive(IntoOwned, Debug)]
+#[into_owned(borrowed_strs)]
+enum Sender<'a> {
+    Anonymous,
+    Named(u32, Cow<'a, str>),
+    Host { name: Cow<'a, str> },
+}
+
+#[test]
+fn lists_borrowed_strings() {
+    let mut headers = HashMap::new();
+    headers.insert(Cow::Borrowed("a"), Some(Cow::Borrowed("1")));
+
+    let message = Message {
+        topic: Cow::Borrowed("news"),
+        headers,
+        payload: Cow::Borrowed(b"..."),
+        sender: Some(Box::new(Sender::Named(1, Cow::Borrowed("alice")))),
+        id: 7,
+    };
+
+    assert_eq!(message.borrowed_strs(), ["news", "a", "1", "alice"]);
+    assert!(message.into_owned().borrowed_strs().is_empty());
+}
+
+#[test]
+fn lists_enum_variants() {
+    assert!(Sender::Anonymous.borrowed_strs().is_empty());
+    let host = Sender::Host {
+        name: Cow::Borrowed("localhost"),
+    };
+    assert_eq!(host.borrowed_strs(), ["localhost"]);
+}
@@ -0,0 +1,85 @@
+#[macro_use]
+extern crate derive_into_owned;
+
+use std::borrow::Cow;
+use std::ops::Bound;
+
+#[derive(IntoOwned, Borrowed, OwnershipDebug, Debug, PartialEq)]
+#[into_owned(
+    ownership,
+    borrowed_strs,
+    try_reborrow_original,
+    mirror(derive(Debug, PartialEq))
+)]
+struct KeyRange<'a> {
+    start: Bound<Cow<'a, str>>,
+    end: std::ops::Bound<Cow<'a, str>>,
+    limit: Bound<u64>,
+}
+
+fn key_range(start: &str) -> KeyRange<'_> {
+    KeyRange {
+        start: Bound::Included(Cow::Borrowed(start)),
+        end: Bound::Unbounded,
+        limit: Bound::Excluded(100),
+    }
+}
+
+#[test]
+fn into_owned_maps_the_bounds() {
+    let start = String::from("user/");
+
+    let owned: KeyRange<'static> = key_range(&start).into_owned();
+    drop(start);
+
+    assert!(matches!(owned.start, Bound::Included(Cow::Owned(ref s)) if s == "user/"));
+    assert_eq!(owned.end, Bound::Unbounded);
+    assert_eq!(owned.limit, Bound::Excluded(100));
+    assert!(owned.ownership().start);
+    assert!(!key_range("user/").ownership().start);
+}
+
+#[test]
+fn borrowed_borrows_the_bounds() {
+    let owned = KeyRange {
+        start: Bound::Excluded(Cow::Owned(String::from("a"))),
+        ..key_range("")
+    };
+
+    let borrowed = owned.borrowed();
+
+    assert!(matches!(
+        borrowed.start,
+        Bound::Excluded(Cow::Borrowed("a"))
+    ));
+    assert_eq!(borrowed, owned);
+}
+
+#[test]
+fn mirror_holds_owned_bounds() {
+    let mirror = KeyRangeOwned::from(key_range("user/"));
+
+    assert_eq!(mirror.start, Bound::Included(String::from("user/")));
+    assert_eq!(mirror.end, Bound::Unbounded);
+}
+
+#[test]
+fn ownership_debug_looks_into_the_bounds() {
+    let debug = format!("{:?}", key_range("user/").ownership_debug());
+
+    assert!(
+        debug.contains("Included([borrowed] \"user/\")"),
+        "{}",
+        debug
+    );
+    assert!(debug.contains("end: Unbounded"), "{}", debug);
+}
+
+#[test]
+fn borrowed_strs_and_reborrow_look_into_the_bounds() {
+    let range = key_range("user/");
+
+    assert_eq!(range.borrowed_strs(), ["user/"]);
+    assert_eq!(range.try_reborrow_original(), Some(key_range("user/")));
+    assert_eq!(range.into_owned().try_reborrow_original(), None);
+}
@@ -0,0 +1,105 @@
+// generated code boxes large repeated fields
+#![allow(clippy::box_collection)]
+
+#[macro_use]
+extern crate derive_into_owned;
+
+use std::borrow::Cow;
+use std::collections::HashMap;
+
+#[derive(IntoOwned, Borrowed)]
+struct Repeated<'a> {
+    names: Box<Vec<Cow<'a, str>>>,
+    attributes: Box<HashMap<u32, Cow<'a, str>>>,
+    maybe: Option<Box<Vec<Cow<'a, [u8]>>>>,
+    plain: Box<Vec<u32>>,
+}
+
+#[test]
+fn boxed_collections() {
+    let local = String::from("name");
+
+    let repeated = Repeated {
+        names: Box::new(vec![Cow::Borrowed(&local)]),
+        attributes: Box::new(vec![(1, Cow::Borrowed(&local[..2]))].into_iter().collect()),
+        maybe: Some(Box::new(vec![Cow::Borrowed(local.as_bytes())])),
+        plain: Box::new(vec![1, 2]),
+    };
+
+    let borrowed = repeated.borrowed();
+    assert!(matches!(borrowed.names[0], Cow::Borrowed("name")));
+    assert!(matches!(borrowed.attributes[&1], Cow::Borrowed("na")));
+
+    let owned: Repeated<'static> = repeated.into_owned();
+    drop(local);
+
+    assert!(matches!(owned.names[0], Cow::Owned(ref s) if s == "name"));
+    assert!(matches!(owned.attributes[&1], Cow::Owned(ref s) if s == "na"));
+    assert!(
+        matches!(owned.maybe.as_deref().map(|v| &v[0]), Some(Cow::Owned(ref s)) if s == b"name")
+    );
+    assert_eq!(*owned.plain, [1, 2]);
+}
+
+#[derive(IntoOwned, Borrowed, Debug, PartialEq)]
+struct Note<'a> {
+    title: Box<Cow<'a, str>>,
+    body: std::boxed::Box<Cow<'a, [u8]>>,
+    footer: Option<Box<Cow<'a, str>>>,
+}
+
+#[test]
+fn boxed_cows() {
+    let local = String::from("title");
+
+    let note = Note {
+        title: Box::new(Cow::Borrowed(&local)),
+        body: Box::new(Cow::Owned(b"body".to_vec())),
+        footer: Some(Box::new(Cow::Borrowed(&local[1..]))),
+    };
+
+    let borrowed = note.borrowed();
+    assert!(matches!(*borrowed.title, Cow::Borrowed("title")));
+    assert!(matches!(*borrowed.body, Cow::Borrowed(b"body")));
+    assert_eq!(borrowed, note);
+
+    let owned: Note<'static> = note.into_owned();
+    drop(local);
+
+    assert!(matches!(*owned.title, Cow::Owned(ref s) if s == "title"));
+    assert!(matches!(owned.footer.as_deref(), Some(Cow::Owned(ref s)) if s == "itle"));
+}
+
+#[derive(IntoOwned, Borrowed, Debug, PartialEq)]
+struct Header<'a> {
+    name: Cow<'a, str>,
+}
+
+#[derive(IntoOwned, Borrowed, Debug, PartialEq)]
+struct Message<'a> {
+    #[into_owned(expect = "box(cow_alike)")]
+    header: Box<Header<'a>>,
+    body: Cow<'a, str>,
+}
+
+#[test]
+fn boxed_cow_alikes() {
+    let local = String::from("header");
+
+    let message = Message {
+        header: Box::new(Header {
+            name: Cow::Owned(local.clone()),
+        }),
+        body: Cow::Borrowed(&local),
+    };
+
+    let borrowed = message.borrowed();
+    assert!(matches!(borrowed.header.name, Cow::Borrowed("header")));
+    assert_eq!(borrowed, message);
+
+    let owned: Message<'static> = message.into_owned();
+    drop(local);
+
+    assert!(matches!(owned.header.name, Cow::Owned(ref s) if s == "header"));
+    assert!(matches!(owned.body, Cow::Owned(ref s) if s == "header"));
+}
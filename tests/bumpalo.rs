@@ -0,0 +1,59 @@
+#![cfg(feature = "bumpalo")]
+
+#[macro_use]
+extern crate derive_into_owned;
+
+use std::borrow::Cow;
+
+use bumpalo::collections::{String as BumpString, Vec as BumpVec};
+use bumpalo::Bump;
+
+// the types are recognized by their paths, so the aliases cannot be used in the fields
+
+#[derive(IntoOwned, Borrowed, OwnershipDebug)]
+#[into_owned(mirror(derive(Debug, PartialEq)), borrowed_strs)]
+struct Document<'bump> {
+    title: bumpalo::collections::String<'bump>,
+    #[into_owned(expect = "bump_vec(cow)")]
+    words: bumpalo::collections::Vec<'bump, Cow<'bump, str>>,
+    offsets: bumpalo::collections::Vec<'bump, u32>,
+    nested: Vec<bumpalo::collections::Vec<'bump, u8>>,
+}
+
+#[test]
+fn detaches_from_the_arena() {
+    let bump = Bump::new();
+
+    let mut words = BumpVec::new_in(&bump);
+    words.push(Cow::Borrowed(&*bump.alloc_str("hello")));
+    words.push(Cow::Owned(String::from("world")));
+
+    let document = Document {
+        title: BumpString::from_str_in("title", &bump),
+        words,
+        offsets: BumpVec::from_iter_in([0, 6], &bump),
+        nested: vec![BumpVec::from_iter_in([1u8], &bump)],
+    };
+
+    assert_eq!(document.borrowed_strs(), ["hello"]);
+    assert_eq!(
+        format!("{:?}", document.ownership_debug()),
+        "Document { title: [borrowed] \"title\", \
+         words: [borrowed] [[borrowed] \"hello\", [owned] \"world\"], \
+         offsets: [borrowed] [0, 6], nested: [[borrowed] [1]] }"
+    );
+    assert_eq!(document.borrowed().words.len(), 2);
+
+    let owned = DocumentOwned::from(document);
+    drop(bump);
+
+    assert_eq!(
+        owned,
+        DocumentOwned {
+            title: String::from("title"),
+            words: vec![String::from("hello"), String::from("world")],
+            offsets: vec![0, 6],
+            nested: vec![vec![1]],
+        }
+    );
+}
@@ -0,0 +1,68 @@
+#![cfg(feature = "bytes")]
+#![allow(dead_code)]
+
+#[macro_use]
+extern crate derive_into_owned;
+
+use std::borrow::Cow;
+use std::sync::Arc;
+
+use bytes::Bytes;
+
+#[derive(IntoOwned)]
+#[into_owned(mirror(bytes, derive(Debug, Clone, PartialEq)))]
+struct Frame<'a> {
+    stream: Cow<'a, str>,
+    payload: Cow<'a, [u8]>,
+    trailer: Option<&'a [u8]>,
+    chunks: Vec<Cow<'a, [u8]>>,
+}
+
+#[derive(IntoOwned)]
+#[into_owned(mirror(arc, bytes, derive(Debug, PartialEq)))]
+struct Shared<'a> {
+    name: Cow<'a, str>,
+    payload: Cow<'a, [u8]>,
+}
+
+#[test]
+fn bytes_leaves() {
+    let data = b"payload".to_vec();
+
+    let frame = Frame {
+        stream: Cow::Borrowed("stream"),
+        payload: Cow::Borrowed(&data),
+        trailer: Some(&data[..3]),
+        chunks: vec![Cow::Owned(b"chunk".to_vec())],
+    };
+
+    let owned: FrameOwned = frame.into();
+    drop(data);
+
+    assert_eq!(
+        owned,
+        FrameOwned {
+            stream: String::from("stream"),
+            payload: Bytes::from_static(b"payload"),
+            trailer: Some(Bytes::from_static(b"pay")),
+            chunks: vec![Bytes::from_static(b"chunk")],
+        }
+    );
+}
+
+#[test]
+fn combined_with_arc() {
+    let shared: SharedOwned = Shared {
+        name: Cow::Borrowed("name"),
+        payload: Cow::Borrowed(b"payload"),
+    }
+    .into();
+
+    assert_eq!(
+        shared,
+        SharedOwned {
+            name: Arc::from("name"),
+            payload: Bytes::from_static(b"payload"),
+        }
+    );
+}
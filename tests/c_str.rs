@@ -0,0 +1,67 @@
+#![allow(dead_code)]
+
+#[macro_use]
+extern crate derive_into_owned;
+
+use std::borrow::Cow;
+use std::ffi::{CStr, CString};
+
+#[derive(IntoOwned, Debug, PartialEq)]
+#[into_owned(mirror(derive(Debug, PartialEq)))]
+struct Request<'a> {
+    path: Cow<'a, CStr>,
+    args: Vec<Cow<'a, CStr>>,
+    mode: Option<Cow<'a, CStr>>,
+}
+
+#[derive(IntoOwned)]
+#[into_owned(mirror(derive(Debug, PartialEq)))]
+struct Call<'a> {
+    symbol: &'a CStr,
+    request: Request<'a>,
+}
+
+fn c_string(s: &str) -> CString {
+    CString::new(s).unwrap()
+}
+
+#[test]
+fn mirror_holds_c_strings() {
+    let path = c_string("/tmp");
+    let arg = c_string("-v");
+    let open = c_string("open");
+
+    let call = Call {
+        symbol: &open,
+        request: Request {
+            path: Cow::Borrowed(&path),
+            args: vec![Cow::Borrowed(&arg)],
+            mode: None,
+        },
+    };
+
+    let owned = CallOwned::from(call);
+    drop((path, arg, open));
+
+    let symbol: CString = owned.symbol;
+    assert_eq!(symbol, c_string("open"));
+    assert_eq!(owned.request.path, c_string("/tmp"));
+    assert_eq!(owned.request.args, [c_string("-v")]);
+}
+
+#[test]
+fn into_owned_keeps_cows() {
+    let path = c_string("/tmp");
+
+    let request = Request {
+        path: Cow::Borrowed(&path),
+        args: Vec::new(),
+        mode: Some(Cow::Borrowed(&path)),
+    };
+
+    let owned: Request<'static> = request.into_owned();
+    drop(path);
+
+    assert_eq!(owned.path.as_ref(), c_string("/tmp").as_c_str());
+    assert!(matches!(owned.mode, Some(Cow::Owned(_))));
+}
@@ -0,0 +1,65 @@
+#[macro_use]
+extern crate derive_into_owned;
+
+use std::borrow::Cow;
+use std::rc::Rc;
+use std::sync::Arc;
+
+trait Visitor<'a> {
+    fn name(&self) -> &str;
+
+    fn clone_box(&self) -> Box<dyn Visitor<'static>>;
+}
+
+#[derive(IntoOwned)]
+struct Printer<'a> {
+    prefix: Cow<'a, str>,
+}
+
+impl<'a> Visitor<'a> for Printer<'a> {
+    fn name(&self) -> &str {
+        &self.prefix
+    }
+
+    fn clone_box(&self) -> Box<dyn Visitor<'static>> {
+        Box::new(
+            Printer {
+                prefix: self.prefix.clone(),
+            }
+            .into_owned(),
+        )
+    }
+}
+
+fn clone_visitor<'a>(visitor: &(dyn Visitor<'a> + 'a)) -> Box<dyn Visitor<'static>> {
+    visitor.clone_box()
+}
+
+#[derive(IntoOwned)]
+struct Walker<'a> {
+    #[into_owned(clone_box = "Visitor::clone_box")]
+    shared: Arc<dyn Visitor<'a> + 'a>,
+    #[into_owned(clone_box = "clone_visitor")]
+    local: Rc<dyn Visitor<'a> + 'a>,
+    #[into_owned(clone_box = "Visitor::clone_box")]
+    boxed: Box<dyn Visitor<'a> + 'a>,
+}
+
+#[test]
+fn clone_box() {
+    let prefix = "> ".to_string();
+    let printer = || Printer {
+        prefix: Cow::Borrowed(&prefix),
+    };
+
+    let walker = Walker {
+        shared: Arc::new(printer()),
+        local: Rc::new(printer()),
+        boxed: Box::new(printer()),
+    };
+
+    let owned: Walker<'static> = walker.into_owned();
+    assert_eq!(owned.shared.name(), "> ");
+    assert_eq!(owned.local.name(), "> ");
+    assert_eq!(owned.boxed.name(), "> ");
+}
@@ -0,0 +1,52 @@
+#![cfg(feature = "config")]
+
+#[macro_use]
+extern crate derive_into_owned;
+
+use std::borrow::Cow;
+
+mod fragments {
+    use std::borrow::Cow;
+
+    /// Listed in `derive-into-owned.toml` at the root of the crate.
+    #[derive(Debug, PartialEq)]
+    pub struct Fragment<'a>(pub Cow<'a, str>);
+
+    impl<'a> Fragment<'a> {
+        pub fn into_static(self) -> Fragment<'static> {
+            Fragment(Cow::Owned(self.0.into_owned()))
+        }
+
+        pub fn borrowed(&self) -> Fragment<'_> {
+            Fragment(Cow::Borrowed(self.0.as_ref()))
+        }
+    }
+}
+
+use fragments::Fragment;
+
+#[derive(IntoOwned, Borrowed, Debug, PartialEq)]
+struct Document<'a> {
+    title: Cow<'a, str>,
+    fragments: Vec<Fragment<'a>>,
+}
+
+#[test]
+fn known_types_from_config() {
+    let source = String::from("title body");
+
+    let document = Document {
+        title: Cow::Borrowed(&source[..5]),
+        fragments: vec![Fragment(Cow::Borrowed(&source[6..]))],
+    };
+
+    assert!(matches!(
+        document.borrowed().fragments[0],
+        Fragment(Cow::Borrowed("body"))
+    ));
+
+    let owned: Document<'static> = document.into_owned();
+    drop(source);
+
+    assert!(matches!(owned.fragments[0], Fragment(Cow::Owned(ref s)) if s == "body"));
+}
@@ -0,0 +1,31 @@
+#[macro_use]
+extern crate derive_into_owned;
+
+#[derive(IntoOwned, Debug, PartialEq)]
+struct Point {
+    x: i32,
+    y: i32,
+}
+
+#[derive(IntoOwned, Debug, PartialEq)]
+struct Line(Point, Point);
+
+#[derive(IntoOwned, Debug, PartialEq)]
+enum Shape {
+    Line { line: Line },
+    Dot(Point),
+    Empty,
+}
+
+const ORIGIN: Point = Point { x: 0, y: 0 }.into_owned();
+const DOT: Shape = Shape::Dot(Point { x: 1, y: 2 }).into_owned();
+const LINE: Line = Line(Point { x: 0, y: 0 }, Point { x: 1, y: 1 }).into_owned();
+const SHAPE: Shape = Shape::Line { line: LINE }.into_owned();
+
+#[test]
+fn into_owned_in_const_context() {
+    assert_eq!(ORIGIN, Point { x: 0, y: 0 });
+    assert_eq!(DOT, Shape::Dot(Point { x: 1, y: 2 }));
+    assert!(matches!(SHAPE, Shape::Line { .. }));
+    assert_eq!(Shape::Empty.into_owned(), Shape::Empty);
+}
@@ -0,0 +1,86 @@
+#![cfg(feature = "dashmap")]
+
+#[macro_use]
+extern crate derive_into_owned;
+
+use std::borrow::Cow;
+
+use dashmap::DashMap;
+
+#[derive(IntoOwned, Borrowed, OwnershipDebug)]
+#[into_owned(ownership, mirror)]
+struct Cache<'a> {
+    name: Cow<'a, str>,
+    #[into_owned(expect = "dashmap(moved, cow)")]
+    entries: DashMap<String, Cow<'a, str>>,
+    plain: DashMap<String, u32>,
+    aliases: DashMap<Cow<'a, str>, u32>,
+}
+
+#[test]
+fn rebuilds_the_map() {
+    let value = String::from("value");
+
+    let cache = Cache {
+        name: Cow::Borrowed("cache"),
+        entries: DashMap::new(),
+        plain: DashMap::new(),
+        aliases: DashMap::new(),
+    };
+    cache
+        .entries
+        .insert(String::from("key"), Cow::Borrowed(&value));
+    cache.plain.insert(String::from("one"), 1);
+    cache.aliases.insert(Cow::Borrowed("first"), 1);
+
+    assert!(!cache.ownership().entries);
+    assert!(!cache.ownership().aliases);
+    assert_eq!(
+        format!("{:?}", cache.ownership_debug()),
+        "Cache { name: [borrowed] \"cache\", entries: {\"key\": [borrowed] \"value\"}, \
+         plain: {\"one\": 1}, aliases: {[borrowed] \"first\": 1} }"
+    );
+
+    {
+        let borrowed = cache.borrowed();
+        let entry = borrowed.entries.get("key").unwrap();
+        assert!(matches!(*entry, Cow::Borrowed("value")));
+    }
+
+    let owned: Cache<'static> = cache.into_owned();
+    drop(value);
+
+    assert!(owned.ownership().entries);
+    assert!(owned.ownership().aliases);
+    assert_eq!(*owned.entries.get("key").unwrap(), "value");
+    assert_eq!(*owned.plain.get("one").unwrap(), 1);
+
+    let mirror = CacheOwned::from(owned);
+    assert_eq!(*mirror.entries.get("key").unwrap(), "value");
+}
+
+type FastHasher = std::hash::BuildHasherDefault<std::collections::hash_map::DefaultHasher>;
+
+#[derive(IntoOwned, Borrowed)]
+struct HashedCache<'a> {
+    #[into_owned(expect = "dashmap(moved, cow)")]
+    entries: DashMap<u32, Cow<'a, str>, FastHasher>,
+}
+
+#[test]
+fn custom_hasher() {
+    let value = String::from("value");
+
+    let cache = HashedCache {
+        entries: DashMap::default(),
+    };
+    cache.entries.insert(1, Cow::Borrowed(&value));
+    assert!(matches!(
+        *cache.borrowed().entries.get(&1).unwrap(),
+        Cow::Borrowed("value")
+    ));
+
+    let owned: HashedCache<'static> = cache.into_owned();
+    drop(value);
+    assert!(matches!(*owned.entries.get(&1).unwrap(), Cow::Owned(ref s) if s == "value"));
+}
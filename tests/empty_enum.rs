@@ -0,0 +1,36 @@
+#[macro_use]
+extern crate derive_into_owned;
+
+use std::borrow::Cow;
+
+/// Placeholder which cannot be constructed, like generated code uses for messages without any
+/// alternatives.
+#[derive(IntoOwned, Borrowed, Debug, Clone)]
+enum Never {}
+
+#[derive(IntoOwned, Borrowed, Debug)]
+struct Message<'a> {
+    text: Cow<'a, str>,
+    never: Option<Never>,
+}
+
+#[test]
+fn zero_variant_enum() {
+    let text = String::from("text");
+
+    let message = Message {
+        text: Cow::Borrowed(&text),
+        never: None,
+    };
+    assert!(message.borrowed().never.is_none());
+
+    let message: Message<'static> = message.into_owned();
+    drop(text);
+    assert_eq!(message.text, "text");
+    assert!(message.never.is_none());
+}
+
+#[allow(dead_code)]
+fn never_converts(never: &Never) -> Never {
+    never.borrowed().into_owned()
+}
@@ -0,0 +1,49 @@
+#![allow(dead_code)]
+
+#[macro_use]
+extern crate derive_into_owned;
+
+use std::borrow::Cow;
+use std::collections::HashMap;
+
+#[derive(IntoOwned)]
+struct Inner<'a> {
+    #[into_owned(expect = "cow")]
+    name: Cow<'a, str>,
+}
+
+#[derive(IntoOwned)]
+struct Message<'a> {
+    #[into_owned(expect = "iterable(cow)")]
+    tags: Vec<Cow<'a, str>>,
+    #[into_owned(expect = "option(option(cow_alike))")]
+    inner: Option<Option<Inner<'a>>>,
+    #[into_owned(expect = "map(cow, box(cow_alike))")]
+    children: HashMap<Cow<'a, str>, Box<Inner<'a>>>,
+    #[into_owned(expect = "moved")]
+    count: u32,
+}
+
+#[derive(IntoOwned)]
+enum Either<'a> {
+    Left(#[into_owned(expect = "cow")] Cow<'a, [u8]>),
+    Right {
+        #[into_owned(expect = "iterable(iterable(cow))")]
+        values: Vec<Vec<Cow<'a, str>>>,
+    },
+}
+
+#[test]
+fn expectations_hold() {
+    let inner = Inner {
+        name: Cow::Borrowed("a"),
+    };
+    let message = Message {
+        tags: vec![],
+        inner: Some(Some(inner)),
+        children: HashMap::new(),
+        count: 1,
+    };
+
+    let _: Message<'static> = message.into_owned();
+}
@@ -0,0 +1,94 @@
+#![allow(dead_code)]
+
+#[macro_use]
+extern crate derive_into_owned;
+
+use std::borrow::Cow;
+use std::collections::HashMap;
+
+use derive_into_owned_core::FieldInfo;
+
+#[derive(IntoOwned)]
+#[into_owned(field_info)]
+struct Request<'a> {
+    path: Cow<'a, str>,
+    headers: HashMap<Cow<'a, str>, Vec<Cow<'a, [u8]>>>,
+    body: Option<Box<Body<'a>>>,
+    #[into_owned(with = "String::from")]
+    r#type: String,
+    retries: u32,
+}
+
+#[derive(IntoOwned)]
+#[into_owned(field_info)]
+struct Body<'a>(Cow<'a, [u8]>);
+
+#[derive(IntoOwned)]
+#[into_owned(field_info)]
+enum Event<'a> {
+    Start,
+    Message { text: Cow<'a, str>, id: u32 },
+    Tags(Vec<Cow<'a, str>>),
+}
+
+#[test]
+fn struct_fields() {
+    assert_eq!(
+        Request::BORROW_FIELDS,
+        [
+            FieldInfo {
+                variant: None,
+                name: "path",
+                kind: "cow",
+                nesting: 0,
+            },
+            FieldInfo {
+                variant: None,
+                name: "headers",
+                kind: "map(cow, iterable(cow))",
+                nesting: 2,
+            },
+            FieldInfo {
+                variant: None,
+                name: "body",
+                kind: "option(box(cow_alike))",
+                nesting: 2,
+            },
+            FieldInfo {
+                variant: None,
+                name: "type",
+                kind: "with",
+                nesting: 0,
+            },
+        ]
+    );
+    assert_eq!(Body::BORROW_FIELDS[0].name, "0");
+}
+
+#[test]
+fn enum_fields() {
+    assert_eq!(
+        Event::BORROW_FIELDS,
+        [
+            FieldInfo {
+                variant: Some("Message"),
+                name: "text",
+                kind: "cow",
+                nesting: 0,
+            },
+            FieldInfo {
+                variant: Some("Tags"),
+                name: "0",
+                kind: "iterable(cow)",
+                nesting: 1,
+            },
+        ]
+    );
+}
+
+const BORROWING: usize = Request::BORROW_FIELDS.len();
+
+#[test]
+fn usable_in_const_context() {
+    assert_eq!(BORROWING, 4);
+}
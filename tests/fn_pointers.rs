@@ -0,0 +1,62 @@
+#[macro_use]
+extern crate derive_into_owned;
+
+use std::borrow::Cow;
+
+fn count(s: &str) -> usize {
+    s.len()
+}
+
+fn first_word(s: &str) -> &str {
+    s.split(' ').next().unwrap_or_default()
+}
+
+fn ignore(_: &str) {}
+
+#[derive(IntoOwned, Borrowed, Clone)]
+#[into_owned(strict)]
+struct Callbacks<'a> {
+    name: Cow<'a, str>,
+    count: for<'x> fn(&'x str) -> usize,
+    first_word: fn(&str) -> &str,
+    ignore: Option<fn(&str)>,
+    all: Vec<for<'x> fn(&'x str) -> usize>,
+}
+
+#[test]
+fn higher_ranked() {
+    let text = String::from("hello world");
+    let callbacks = Callbacks {
+        name: Cow::Borrowed(&text),
+        count,
+        first_word,
+        ignore: Some(ignore),
+        all: vec![count],
+    };
+
+    let borrowed = callbacks.borrowed();
+    assert_eq!((borrowed.count)(&borrowed.name), 11);
+
+    let owned: Callbacks<'static> = callbacks.into_owned();
+    assert_eq!((owned.first_word)(&owned.name), "hello");
+    assert_eq!((owned.all[0])("abc"), 3);
+    assert!(owned.ignore.is_some());
+}
+
+#[derive(IntoOwned)]
+struct Handler<'a> {
+    name: Cow<'a, str>,
+    // takes `&'static str` after `into_owned`, which every `fn(&'a str)` also does
+    handle: fn(&'a str) -> usize,
+}
+
+#[test]
+fn borrowed_arguments() {
+    let handler = Handler {
+        name: Cow::Borrowed("name"),
+        handle: count,
+    };
+
+    let owned: Handler<'static> = handler.into_owned();
+    assert_eq!((owned.handle)("static"), 6);
+}
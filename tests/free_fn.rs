@@ -0,0 +1,101 @@
+#[macro_use]
+extern crate derive_into_owned;
+
+use std::borrow::Cow;
+
+use derive_into_owned_core::IntoOwned;
+
+#[derive(IntoOwned, Debug, PartialEq)]
+#[into_owned(free_fn)]
+pub struct Message<'a> {
+    topic: Cow<'a, str>,
+    parts: Vec<Part<'a>>,
+}
+
+impl Message<'_> {
+    /// A method of the same name, which the derive must not collide with.
+    pub fn into_owned(self) -> String {
+        self.topic.into_owned()
+    }
+}
+
+#[derive(IntoOwned, Debug, PartialEq)]
+pub enum Part<'a> {
+    Text(Cow<'a, str>),
+    Empty,
+}
+
+#[derive(IntoOwned, Debug, PartialEq)]
+#[into_owned(free_fn)]
+struct HTTPRequest<'a, T> {
+    path: Cow<'a, str>,
+    extra: T,
+}
+
+#[derive(IntoOwned, Debug, PartialEq)]
+#[into_owned(free_fn = "detach")]
+enum Event<'a> {
+    Started(Cow<'a, str>),
+    Stopped { reason: Option<Cow<'a, str>> },
+}
+
+#[derive(IntoOwned, Debug, PartialEq)]
+#[into_owned(free_fn, trait_impl)]
+struct Line<'a> {
+    text: Cow<'a, str>,
+}
+
+impl Line<'_> {
+    pub fn into_owned(self) -> usize {
+        self.text.len()
+    }
+}
+
+#[test]
+fn generates_free_functions() {
+    let data = String::from("data");
+
+    let message = message_into_owned(Message {
+        topic: Cow::Borrowed(&data),
+        parts: vec![Part::Text(Cow::Borrowed(&data)), Part::Empty],
+    });
+    let request = http_request_into_owned(HTTPRequest {
+        path: Cow::Borrowed(&data),
+        extra: 1u8,
+    });
+    let event = detach(Event::Stopped {
+        reason: Some(Cow::Borrowed(&data)),
+    });
+    drop(data);
+
+    assert_eq!(
+        message.parts,
+        vec![Part::Text(Cow::Borrowed("data")), Part::Empty]
+    );
+    assert_eq!(message.into_owned(), "data");
+    assert_eq!(request.path, "data");
+    assert_eq!(request.extra, 1);
+    assert_eq!(
+        event,
+        Event::Stopped {
+            reason: Some(Cow::Borrowed("data"))
+        }
+    );
+    assert_eq!(
+        detach(Event::Started(Cow::Borrowed("x"))),
+        Event::Started(Cow::Borrowed("x"))
+    );
+}
+
+#[test]
+fn trait_impl_calls_the_free_function() {
+    let text = String::from("text");
+
+    let lines: Vec<Line<'static>> = IntoOwned::into_owned(vec![Line {
+        text: Cow::Borrowed(&text),
+    }]);
+    drop(text);
+
+    assert!(matches!(lines[0].text, Cow::Owned(ref s) if s == "text"));
+    assert_eq!(line_into_owned(Line { text: "x".into() }).into_owned(), 1);
+}
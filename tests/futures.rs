@@ -0,0 +1,81 @@
+#![cfg(feature = "futures")]
+
+#[macro_use]
+extern crate derive_into_owned;
+
+use std::borrow::Cow;
+
+use derive_into_owned_core::OwnedItems;
+use futures_util::stream::{self, StreamExt};
+
+#[derive(IntoOwned, Debug, PartialEq)]
+#[into_owned(trait_impl)]
+struct Line<'a> {
+    text: Cow<'a, str>,
+    number: usize,
+}
+
+#[tokio::test]
+async fn owned_items() {
+    let input = String::from("first\nsecond");
+
+    let lines = stream::iter(input.lines().enumerate().map(|(number, text)| Line {
+        text: Cow::Borrowed(text),
+        number,
+    }));
+
+    let owned: Vec<Line<'static>> = lines.owned_items().collect().await;
+    drop(input);
+
+    assert_eq!(
+        owned,
+        [
+            Line {
+                text: Cow::Owned(String::from("first")),
+                number: 0,
+            },
+            Line {
+                text: Cow::Owned(String::from("second")),
+                number: 1,
+            },
+        ]
+    );
+}
+
+#[tokio::test]
+async fn crosses_spawn_boundary() {
+    let input = String::from("text");
+    let lines = stream::iter(vec![Line {
+        text: Cow::Borrowed(&input),
+        number: 0,
+    }]);
+    let mut owned = lines.owned_items();
+
+    let line = owned.next().await.unwrap();
+    let handle = tokio::spawn(async move { line.text.len() });
+
+    assert_eq!(handle.await.unwrap(), 4);
+}
+
+#[test]
+fn collections() {
+    use derive_into_owned_core::IntoOwned;
+
+    let input = String::from("text");
+    let line = || Line {
+        text: Cow::Borrowed(&input),
+        number: 0,
+    };
+
+    let lines: Vec<Line<'static>> = vec![line(), line()].into_owned();
+    let maybe: Option<Line<'static>> = Some(line()).into_owned();
+    let boxed: Box<Line<'static>> = Box::new(line()).into_owned();
+    let nested: Option<Vec<Line<'static>>> = Some(vec![line()]).into_owned();
+    drop(input);
+
+    assert_eq!(lines.len(), 2);
+    assert!(matches!(lines[0].text, Cow::Owned(_)));
+    assert_eq!(maybe.unwrap().text, "text");
+    assert_eq!(boxed.text, "text");
+    assert_eq!(nested.unwrap()[0].text, "text");
+}
@@ -0,0 +1,58 @@
+#[macro_use]
+extern crate derive_into_owned;
+
+use std::borrow::Cow;
+use std::fmt::Debug;
+
+#[derive(IntoOwned, Borrowed, Debug, PartialEq)]
+struct Job<'a, T: Clone + Send> {
+    name: Cow<'a, str>,
+    payload: T,
+}
+
+#[derive(IntoOwned, Borrowed, Debug, PartialEq)]
+struct Window<'a, T: Clone + Debug, const N: usize> {
+    label: Cow<'a, str>,
+    samples: [T; N],
+}
+
+#[derive(IntoOwned, Borrowed, Debug, PartialEq)]
+enum Frame<'a, const N: usize> {
+    Text(Cow<'a, str>),
+    Bytes([u8; N]),
+}
+
+fn spawn<T: Send + 'static>(value: T) -> T {
+    std::thread::spawn(move || value).join().unwrap()
+}
+
+#[test]
+fn bounds_are_kept() {
+    let name = "job".to_string();
+    let job = Job {
+        name: Cow::Borrowed(&name),
+        payload: vec![1u8],
+    };
+
+    let owned = spawn(job.borrowed().into_owned());
+    assert_eq!(owned, job);
+}
+
+#[test]
+fn const_generics() {
+    let label = "window".to_string();
+    let window = Window {
+        label: Cow::Borrowed(&label),
+        samples: [1.5f32, 2.5],
+    };
+
+    let owned: Window<'static, f32, 2> = window.borrowed().into_owned();
+    assert_eq!(owned, window);
+
+    let frame: Frame<'static, 4> = Frame::Bytes([1, 2, 3, 4]).into_owned();
+    assert_eq!(frame.borrowed(), Frame::Bytes([1, 2, 3, 4]));
+    assert_eq!(
+        Frame::<4>::Text(Cow::Borrowed(&label)).into_owned(),
+        Frame::Text(Cow::Borrowed("window"))
+    );
+}
@@ -0,0 +1,55 @@
+#[macro_use]
+extern crate derive_into_owned;
+
+use std::borrow::Cow;
+
+#[derive(IntoOwned, Borrowed, Debug, PartialEq)]
+enum Message<'a, T> {
+    Text(Cow<'a, str>),
+    Other(T),
+    Tagged { tag: Cow<'a, str>, value: Option<T> },
+}
+
+#[derive(IntoOwned, Borrowed)]
+enum Bounded<'a, T: std::fmt::Debug, U = u32>
+where
+    U: Copy,
+{
+    Value(T, U),
+    Text(Cow<'a, str>),
+}
+
+#[test]
+fn generic_enum() {
+    let text = "hello".to_string();
+
+    let messages = vec![
+        Message::Text(Cow::Borrowed(&text)),
+        Message::Other(vec![1u8, 2, 3]),
+        Message::Tagged {
+            tag: Cow::Borrowed(&text),
+            value: Some(vec![4]),
+        },
+    ];
+
+    for message in messages {
+        let owned: Message<'static, Vec<u8>> = message.borrowed().into_owned();
+        assert_eq!(owned, message);
+        assert_eq!(owned.borrowed(), message);
+    }
+}
+
+#[test]
+fn bounded_generic_enum() {
+    let text = "hello".to_string();
+
+    match Bounded::<&str>::Text(Cow::Borrowed(&text)).into_owned() {
+        Bounded::Text(text) => assert_eq!(text, "hello"),
+        Bounded::Value(..) => unreachable!(),
+    }
+
+    match Bounded::<&str>::Value("value", 7).borrowed() {
+        Bounded::Value(value, number) => assert_eq!((value, number), ("value", 7)),
+        Bounded::Text(_) => unreachable!(),
+    }
+}
@@ -0,0 +1,68 @@
+#[macro_use]
+extern crate derive_into_owned;
+
+use std::borrow::Cow;
+
+#[derive(IntoOwned, Borrowed, Clone)]
+struct Normalizer<'a, F>
+where
+    for<'x> F: Fn(&'x str) -> Cow<'x, str> + Clone,
+{
+    name: Cow<'a, str>,
+    normalize: F,
+}
+
+#[derive(IntoOwned, Borrowed, Clone)]
+struct Inline<'a, F: for<'x> Fn(&'x str) -> Cow<'x, str> + Clone> {
+    name: Cow<'a, str>,
+    normalize: F,
+}
+
+#[derive(IntoOwned, Borrowed, Clone)]
+#[into_owned(mirror)]
+struct Mirrored<'a, F>
+where
+    F: for<'x> Fn(&'x str) -> Cow<'x, str> + Clone,
+{
+    name: Cow<'a, str>,
+    normalize: F,
+}
+
+fn trim(s: &str) -> Cow<'_, str> {
+    Cow::Borrowed(s.trim())
+}
+
+#[test]
+fn higher_ranked_where_clause() {
+    let name = String::from(" name ");
+
+    let normalizer = Normalizer {
+        name: Cow::Borrowed(&name),
+        normalize: trim,
+    };
+    assert!(matches!(normalizer.borrowed().name, Cow::Borrowed(_)));
+
+    let normalizer: Normalizer<'static, _> = normalizer.into_owned();
+    drop(name);
+    assert_eq!((normalizer.normalize)(&normalizer.name), "name");
+}
+
+#[test]
+fn higher_ranked_inline_bound() {
+    let name = String::from(" name ");
+
+    let inline = Inline {
+        name: Cow::Borrowed(&name),
+        normalize: trim,
+    };
+    let inline: Inline<'static, _> = inline.borrowed().into_owned();
+    drop(name);
+    assert_eq!((inline.normalize)(&inline.name), "name");
+
+    let mirrored = Mirrored {
+        name: Cow::Borrowed(" mirrored "),
+        normalize: trim,
+    };
+    let mirrored = MirroredOwned::from(mirrored);
+    assert_eq!((mirrored.normalize)(&mirrored.name), "mirrored");
+}
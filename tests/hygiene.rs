@@ -0,0 +1,126 @@
+//! Fields and type parameters named like the variables and parameters of the generated code.
+
+#[macro_use]
+extern crate derive_into_owned;
+
+use std::borrow::Cow;
+use std::collections::HashSet;
+
+#[derive(IntoOwned, Borrowed, OwnershipDebug, Debug, PartialEq)]
+#[into_owned(intern, map_cows)]
+enum Token<'a> {
+    Call {
+        f: Vec<Cow<'a, str>>,
+        interner: Option<Cow<'a, str>>,
+        map: Vec<Option<Cow<'a, str>>>,
+        val: Option<Cow<'a, str>>,
+        x: Vec<Cow<'a, str>>,
+    },
+    Empty,
+}
+
+#[derive(IntoOwned, Borrowed, OwnershipDebug, Debug, PartialEq)]
+#[into_owned(intern, map_cows)]
+struct Generic<'a, I: Clone, F: Clone> {
+    f: Cow<'a, str>,
+    i: I,
+    j: F,
+}
+
+#[derive(IntoOwned, Debug, PartialEq)]
+#[into_owned(intern, map_cows)]
+struct Prefixed<'a, __I: Clone, __F: Clone, I1: Clone> {
+    f: Cow<'a, str>,
+    i: __I,
+    j: __F,
+    k: I1,
+}
+
+#[derive(IntoOwned, Borrowed, Debug, PartialEq)]
+struct Returning<'a, R: Clone, __R: Clone> {
+    f: Cow<'a, str>,
+    r: R,
+    s: __R,
+}
+
+fn call(name: &str) -> Token<'_> {
+    Token::Call {
+        f: vec![Cow::Borrowed(name)],
+        interner: Some(Cow::Borrowed(" interner ")),
+        map: vec![None],
+        val: None,
+        x: vec![],
+    }
+}
+
+#[test]
+fn fields_named_like_generated_variables() {
+    let name = String::from("main");
+
+    assert_eq!(call(&name).borrowed(), call(&name));
+    assert_eq!(
+        format!("{:?}", call(&name).ownership_debug()),
+        "Call { f: [[borrowed] \"main\"], interner: Some([borrowed] \" interner \"), \
+         map: [None], val: None, x: [] }"
+    );
+
+    let trimmed = call(&name).map_cows(|s| match s {
+        Cow::Borrowed(s) => Cow::Borrowed(s.trim()),
+        Cow::Owned(s) => Cow::Owned(s.trim().to_string()),
+    });
+    assert!(matches!(
+        trimmed,
+        Token::Call {
+            interner: Some(Cow::Borrowed("interner")),
+            ..
+        }
+    ));
+
+    let mut interner = HashSet::new();
+    let interned: Token<'static> = call(&name).into_owned_interned(&mut interner);
+    drop(name);
+    assert_eq!(interned, call("main"));
+    assert_eq!(Token::Empty.into_owned(), Token::Empty);
+}
+
+#[test]
+fn type_parameters_named_like_generated_ones() {
+    let generic = Generic {
+        f: Cow::Borrowed("f"),
+        i: 1u8,
+        j: 'j',
+    };
+
+    assert_eq!(generic.borrowed(), generic);
+    assert_eq!(
+        format!("{:?}", generic.ownership_debug()),
+        "Generic { f: [borrowed] \"f\", i: 1, j: 'j' }"
+    );
+
+    let mut interner = HashSet::new();
+    let owned: Generic<'static, u8, char> =
+        generic.map_cows(|s| s).into_owned_interned(&mut interner);
+    assert_eq!(owned.i, 1);
+
+    let prefixed = Prefixed {
+        f: Cow::Borrowed("f"),
+        i: 1u8,
+        j: 'j',
+        k: "k",
+    };
+    let owned: Prefixed<'static, u8, char, &str> =
+        prefixed.map_cows(|s| s).into_owned_interned(&mut interner);
+    assert_eq!(owned.k, "k");
+}
+
+#[test]
+fn with_borrowed_type_parameters() {
+    let returning = Returning {
+        f: Cow::Borrowed("f"),
+        r: 1u8,
+        s: 's',
+    };
+
+    let (f, r, s) = returning.with_borrowed(|value| (value.f.len(), value.r, value.s));
+    assert_eq!((f, r, s), (1, 1, 's'));
+}
@@ -0,0 +1,92 @@
+#[macro_use]
+extern crate derive_into_owned;
+
+use derive_into_owned_core::Vocabulary;
+use std::borrow::Cow;
+use std::collections::HashSet;
+
+#[derive(IntoOwned, Debug)]
+#[into_owned(intern)]
+struct Row<'a> {
+    country: Cow<'a, str>,
+    tags: Vec<Cow<'a, str>>,
+    note: Option<Note<'a>>,
+    bytes: Cow<'a, [u8]>,
+}
+
+#[derive(IntoOwned, Debug)]
+#[into_owned(intern)]
+enum Note<'a> {
+    Text(Cow<'a, str>),
+    Empty,
+}
+
+#[test]
+fn interned_rows_share_strings() {
+    let source = String::from("fi fi");
+    let mut interner = HashSet::new();
+
+    let rows = vec![
+        Row {
+            country: Cow::Borrowed(&source[..2]),
+            tags: vec![Cow::Owned(String::from("tag"))],
+            note: Some(Note::Text(Cow::Borrowed("tag"))),
+            bytes: Cow::Borrowed(source.as_bytes()),
+        },
+        Row {
+            country: Cow::Borrowed(&source[3..]),
+            tags: vec![],
+            note: Some(Note::Empty),
+            bytes: Cow::Borrowed(&[]),
+        },
+    ];
+
+    let rows: Vec<Row<'static>> = rows
+        .into_iter()
+        .map(|row| row.into_owned_interned(&mut interner))
+        .collect();
+    drop(source);
+
+    assert_eq!(interner.len(), 2);
+    assert_eq!(rows[0].country.as_ptr(), rows[1].country.as_ptr());
+    assert!(matches!(rows[0].country, Cow::Borrowed("fi")));
+    assert!(matches!(rows[0].tags[0], Cow::Borrowed("tag")));
+    match rows[0].note {
+        Some(Note::Text(ref text)) => assert_eq!(text.as_ptr(), rows[0].tags[0].as_ptr()),
+        ref other => panic!("unexpected {:?}", other),
+    }
+    assert!(matches!(rows[0].bytes, Cow::Owned(_)));
+}
+
+#[test]
+fn vocabulary_copies_unknown_strings() {
+    let source = String::from("fi xx");
+    let mut interner: Vocabulary = ["fi", "tag"].into_iter().collect();
+
+    let row = Row {
+        country: Cow::Borrowed(&source[..2]),
+        tags: vec![Cow::Borrowed(&source[3..]), Cow::Owned(String::from("tag"))],
+        note: None,
+        bytes: Cow::Borrowed(&[]),
+    }
+    .into_owned_interned(&mut interner);
+    drop(source);
+
+    assert!(matches!(row.country, Cow::Borrowed("fi")));
+    assert!(matches!(row.tags[0], Cow::Owned(ref tag) if tag == "xx"));
+    assert!(matches!(row.tags[1], Cow::Borrowed("tag")));
+    assert!(!interner.contains("xx"));
+}
+
+#[test]
+fn into_owned_is_still_generated() {
+    let row = Row {
+        country: Cow::Borrowed("fi"),
+        tags: vec![],
+        note: None,
+        bytes: Cow::Borrowed(&[]),
+    }
+    .into_owned();
+
+    assert!(matches!(row.country, Cow::Owned(_)));
+}
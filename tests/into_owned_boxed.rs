@@ -0,0 +1,27 @@
+#[macro_use]
+extern crate derive_into_owned;
+
+use std::borrow::Cow;
+
+#[derive(IntoOwned, Debug)]
+#[into_owned(boxed)]
+struct Large<'a> {
+    name: Cow<'a, str>,
+    payload: [u64; 512],
+}
+
+#[test]
+fn into_owned_boxed() {
+    let name = String::from("large");
+
+    let large = Large {
+        name: Cow::Borrowed(&name),
+        payload: [7; 512],
+    };
+
+    let large: Box<Large<'static>> = large.into_owned_boxed();
+    drop(name);
+
+    assert_eq!(large.name, "large");
+    assert_eq!(large.payload[511], 7);
+}
@@ -0,0 +1,109 @@
+#[macro_use]
+extern crate derive_into_owned;
+
+use std::borrow::Cow;
+
+mod lexer {
+    use std::borrow::Cow;
+
+    /// Converted with the default `into_owned`.
+    #[derive(Debug, Clone, PartialEq)]
+    pub struct Span<'a>(pub Cow<'a, str>);
+
+    impl<'a> Span<'a> {
+        pub fn into_owned(self) -> Span<'static> {
+            Span(Cow::Owned(self.0.into_owned()))
+        }
+
+        pub fn borrowed(&self) -> Span<'_> {
+            Span(Cow::Borrowed(self.0.as_ref()))
+        }
+    }
+}
+
+mod parser {
+    use std::borrow::Cow;
+
+    /// Converted with a differently named method.
+    #[derive(Debug, Clone, PartialEq)]
+    pub struct Token<'a> {
+        pub text: Cow<'a, str>,
+    }
+
+    impl<'a> Token<'a> {
+        pub fn into_static(self) -> Token<'static> {
+            Token {
+                text: Cow::Owned(self.text.into_owned()),
+            }
+        }
+
+        /// Differs from `into_static` for telling which one was used.
+        pub fn into_owned(self) -> Token<'static> {
+            Token {
+                text: Cow::Owned(self.text.to_uppercase()),
+            }
+        }
+
+        pub fn borrowed(&self) -> Token<'_> {
+            Token {
+                text: Cow::Borrowed(self.text.as_ref()),
+            }
+        }
+    }
+}
+
+use lexer::Span;
+
+#[derive(IntoOwned, Borrowed, Debug, PartialEq)]
+#[into_owned(known(lexer::Span, parser::Token = "into_static"))]
+struct Statement<'a> {
+    span: Span<'a>,
+    tokens: Vec<parser::Token<'a>>,
+    comment: Option<Cow<'a, str>>,
+}
+
+#[test]
+fn known_types() {
+    let source = String::from("let x");
+
+    let statement = Statement {
+        span: Span(Cow::Borrowed(&source)),
+        tokens: source
+            .split(' ')
+            .map(|text| parser::Token {
+                text: Cow::Borrowed(text),
+            })
+            .collect(),
+        comment: None,
+    };
+
+    let borrowed = statement.borrowed();
+    assert!(matches!(borrowed.tokens[0].text, Cow::Borrowed("let")));
+
+    let owned: Statement<'static> = statement.into_owned();
+    drop(source);
+
+    assert_eq!(owned.span, Span(Cow::Borrowed("let x")));
+    assert!(matches!(owned.tokens[1].text, Cow::Owned(ref s) if s == "x"));
+}
+
+/// The same field type as in `Statement` but with another method, which must not be mixed up
+/// with the classification done for `Statement`.
+#[derive(IntoOwned, Borrowed, Debug, PartialEq)]
+#[into_owned(known(parser::Token))]
+struct Expression<'a> {
+    tokens: Vec<parser::Token<'a>>,
+}
+
+#[test]
+fn same_type_with_other_method() {
+    let expression = Expression {
+        tokens: vec![parser::Token {
+            text: Cow::Borrowed("x"),
+        }],
+    };
+
+    let owned: Expression<'static> = expression.into_owned();
+
+    assert!(matches!(owned.tokens[0].text, Cow::Owned(ref s) if s == "X"));
+}
@@ -0,0 +1,45 @@
+#[macro_use]
+extern crate derive_into_owned;
+
+use std::borrow::Cow;
+
+#[derive(IntoOwned, Debug)]
+#[into_owned(leak)]
+struct Config<'a> {
+    name: Cow<'a, str>,
+    tags: Vec<Cow<'a, str>>,
+    listen: Option<Listen<'a>>,
+    retries: u32,
+}
+
+#[derive(IntoOwned, Debug)]
+#[into_owned(leak)]
+struct Listen<'a> {
+    host: &'a str,
+    certificate: Option<&'a [u8]>,
+}
+
+#[test]
+fn leaked_config() {
+    let source = String::from("service localhost");
+
+    let config = Config {
+        name: Cow::Borrowed(&source[..7]),
+        tags: vec![Cow::Owned(String::from("tag"))],
+        listen: Some(Listen {
+            host: &source[8..],
+            certificate: Some(source.as_bytes()),
+        }),
+        retries: 3,
+    };
+
+    let config: Config<'static> = config.leaked();
+    drop(source);
+
+    assert!(matches!(config.name, Cow::Borrowed("service")));
+    assert!(matches!(config.tags[0], Cow::Borrowed("tag")));
+    let listen = config.listen.as_ref().unwrap();
+    assert_eq!(listen.host, "localhost");
+    assert_eq!(listen.certificate, Some(&b"service localhost"[..]));
+    assert_eq!(config.retries, 3);
+}
@@ -0,0 +1,144 @@
+//! Derives every supported shape under strict lint levels, which the expansions must not trip.
+
+#![deny(
+    warnings,
+    unused_qualifications,
+    elided_lifetimes_in_paths,
+    missing_docs
+)]
+#![forbid(unsafe_code)]
+
+#[macro_use]
+extern crate derive_into_owned;
+
+use std::borrow::Cow;
+use std::collections::HashMap;
+
+/// Named fields of all the recognized containers.
+#[derive(IntoOwned, Borrowed, Debug, Clone, PartialEq)]
+#[into_owned(mirror(derive(Debug)), try_reborrow_original)]
+pub struct Named<'a> {
+    /// plain cow
+    pub text: Cow<'a, str>,
+    /// bytes
+    pub bytes: Cow<'a, [u8]>,
+    /// nested options
+    pub optional: Option<Option<Cow<'a, str>>>,
+    /// vector
+    pub list: Vec<Cow<'a, str>>,
+    /// box
+    pub boxed: Box<Cow<'a, str>>,
+    /// map
+    pub map: HashMap<Cow<'a, str>, Cow<'a, [u8]>>,
+    /// just moved
+    pub count: u32,
+}
+
+/// Tuple struct with a "Cow-alike" field.
+#[derive(IntoOwned, Debug, Clone, PartialEq)]
+#[into_owned(try_reborrow_original)]
+pub struct Tuple<'a>(pub Cow<'a, str>, pub Named<'a>);
+
+/// Unit struct.
+#[derive(IntoOwned, Borrowed, Debug, Clone, PartialEq)]
+pub struct Unit;
+
+/// Enum of all variant shapes.
+#[derive(IntoOwned, Borrowed, Debug, Clone, PartialEq)]
+#[into_owned(intern, try_reborrow_original)]
+pub enum Shapes<'a, T: Clone> {
+    /// named
+    Named {
+        /// text
+        text: Cow<'a, str>,
+        /// generic
+        value: T,
+    },
+    /// unnamed
+    Unnamed(Cow<'a, str>, Vec<Option<Cow<'a, str>>>),
+    /// unit
+    Unit,
+}
+
+/// Enum without variants.
+#[derive(IntoOwned, Borrowed, Debug, Clone, PartialEq)]
+#[into_owned(try_reborrow_original)]
+pub enum Never {}
+
+/// Leaked configuration.
+#[derive(IntoOwned, Debug, PartialEq)]
+#[into_owned(leak)]
+pub struct Leaked<'a> {
+    /// reference
+    pub host: &'a str,
+    /// cow
+    pub name: Cow<'a, str>,
+}
+
+/// Only references, which get just the mirror type.
+#[derive(IntoOwned, Debug, PartialEq)]
+#[into_owned(mirror)]
+pub struct References<'a> {
+    /// reference
+    pub host: &'a str,
+}
+
+#[test]
+fn strict_lints() {
+    let named = Named {
+        text: Cow::Borrowed("text"),
+        bytes: Cow::Borrowed(b"bytes"),
+        optional: Some(None),
+        list: vec![Cow::Borrowed("a")],
+        boxed: Box::new(Cow::Borrowed("boxed")),
+        map: HashMap::new(),
+        count: 1,
+    };
+
+    let tuple = Tuple(Cow::Borrowed("tuple"), named.borrowed());
+    assert_eq!(tuple.clone().into_owned(), tuple);
+    assert_eq!(NamedOwned::from(named.clone()).text, "text");
+
+    let shapes: Shapes<'_, u8> = Shapes::Unnamed(Cow::Borrowed("a"), vec![None]);
+    assert_eq!(shapes.borrowed().into_owned(), shapes);
+    let mut interner = std::collections::HashSet::new();
+    let _ = Shapes::Named {
+        text: Cow::Borrowed("a"),
+        value: 0u8,
+    }
+    .into_owned_interned(&mut interner);
+    assert_eq!(Shapes::<'_, u8>::Unit.into_owned(), Shapes::Unit);
+
+    assert_eq!(Unit.borrowed().into_owned(), Unit);
+
+    let leaked = Leaked {
+        host: "localhost",
+        name: Cow::Borrowed("name"),
+    }
+    .leaked();
+    assert_eq!(leaked.host, "localhost");
+
+    assert_eq!(ReferencesOwned::from(References { host: "a" }).host, "a");
+}
+
+/// The `yoke` helpers under the same lints.
+#[cfg(feature = "yoke")]
+pub mod yoked {
+    use std::borrow::Cow;
+
+    /// Line borrowing from its buffer.
+    #[derive(IntoOwned, yoke::Yokeable)]
+    #[into_owned(yoke, self_owned)]
+    pub struct Line<'a> {
+        /// key
+        pub key: Cow<'a, str>,
+    }
+
+    #[test]
+    fn strict_lints_with_yoke() {
+        let line = LineSelfOwned::new(String::from("key"), |s: &str| Line {
+            key: Cow::Borrowed(s),
+        });
+        assert_eq!(line.get().key, "key");
+    }
+}
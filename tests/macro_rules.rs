@@ -0,0 +1,72 @@
+#[macro_use]
+extern crate derive_into_owned;
+
+use std::borrow::Cow;
+
+/// Defines a message the way generated code often does, with every type passed as a fragment.
+macro_rules! message {
+    ($name:ident<$lt:lifetime> { $($field:ident: $ty:ty),* $(,)? }) => {
+        #[derive(IntoOwned, Borrowed, Debug, PartialEq)]
+        pub struct $name<$lt> {
+            $(pub $field: $ty),*
+        }
+    };
+}
+
+macro_rules! wrapper {
+    ($name:ident, $inner:ident) => {
+        #[derive(IntoOwned, Borrowed, Debug, PartialEq)]
+        pub enum $name<'a> {
+            Single($crate::$inner<'a>),
+            Many { items: Vec<$crate::$inner<'a>> },
+        }
+    };
+}
+
+message!(Header<'a> {
+    name: Cow<'a, str>,
+    values: Vec<Cow<'a, [u8]>>,
+    parent: Option<Box<Header<'a>>>,
+    count: u32,
+});
+
+wrapper!(Headers, Header);
+
+#[test]
+fn fragments_are_looked_into() {
+    let name = String::from("host");
+    let header = Header {
+        name: Cow::Borrowed(&name),
+        values: vec![Cow::Borrowed(b"localhost")],
+        parent: None,
+        count: 1,
+    };
+
+    let borrowed = header.borrowed();
+    assert!(matches!(borrowed.name, Cow::Borrowed("host")));
+
+    let owned: Header<'static> = header.into_owned();
+    drop(name);
+    assert!(matches!(owned.name, Cow::Owned(_)));
+    assert!(matches!(owned.values[0], Cow::Owned(_)));
+}
+
+#[test]
+fn crate_paths() {
+    let name = String::from("host");
+    let headers = Headers::Many {
+        items: vec![Header {
+            name: Cow::Borrowed(&name),
+            values: vec![],
+            parent: None,
+            count: 0,
+        }],
+    };
+
+    let owned: Headers<'static> = headers.into_owned();
+    drop(name);
+    match owned {
+        Headers::Many { ref items } => assert!(matches!(items[0].name, Cow::Owned(_))),
+        ref other => panic!("unexpected {:?}", other),
+    }
+}
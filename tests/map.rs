@@ -0,0 +1,184 @@
+#[macro_use]
+extern crate derive_into_owned;
+
+use std::borrow::Cow;
+use std::collections::{BTreeMap, HashMap};
+
+#[derive(IntoOwned, Borrowed, Debug, PartialEq)]
+struct Message<'a> {
+    text: Cow<'a, str>,
+}
+
+#[derive(IntoOwned, Borrowed, Debug, PartialEq)]
+#[into_owned(mirror(derive(Debug)))]
+struct Registry<'a> {
+    #[into_owned(expect = "map(moved, cow)")]
+    labels: HashMap<String, Cow<'a, str>>,
+    #[into_owned(expect = "map(moved, cow_alike)")]
+    messages: HashMap<u32, Message<'a>>,
+    counts: HashMap<String, u32>,
+}
+
+#[test]
+fn map_values() {
+    let text = String::from("text");
+
+    let mut labels = HashMap::new();
+    labels.insert(String::from("label"), Cow::Borrowed(&text[..2]));
+    let mut messages = HashMap::new();
+    messages.insert(
+        1,
+        Message {
+            text: Cow::Borrowed(&text),
+        },
+    );
+
+    let registry = Registry {
+        labels,
+        messages,
+        counts: HashMap::new(),
+    };
+
+    let borrowed = registry.borrowed();
+    assert!(matches!(borrowed.labels["label"], Cow::Borrowed("te")));
+    assert!(matches!(borrowed.messages[&1].text, Cow::Borrowed("text")));
+    assert_eq!(borrowed, registry);
+
+    let owned: Registry<'static> = registry.into_owned();
+    drop(text);
+
+    assert!(matches!(owned.labels["label"], Cow::Owned(ref s) if s == "te"));
+    assert!(matches!(owned.messages[&1].text, Cow::Owned(ref s) if s == "text"));
+
+    let mirror = RegistryOwned::from(owned);
+    assert_eq!(mirror.labels["label"], "te");
+    assert_eq!(mirror.messages[&1].text, "text");
+    assert!(mirror.counts.is_empty());
+}
+
+#[derive(IntoOwned, Borrowed, Debug, PartialEq)]
+#[into_owned(mirror(derive(Debug)))]
+struct Index<'a> {
+    #[into_owned(expect = "map(cow, moved)")]
+    positions: HashMap<Cow<'a, str>, usize>,
+    #[into_owned(expect = "map(cow, cow_alike)")]
+    entries: HashMap<Cow<'a, [u8]>, Message<'a>>,
+}
+
+#[test]
+fn map_keys() {
+    let text = String::from("key value");
+
+    let mut positions = HashMap::new();
+    positions.insert(Cow::Borrowed(&text[..3]), 0);
+    let mut entries = HashMap::new();
+    entries.insert(
+        Cow::Borrowed(&text.as_bytes()[4..]),
+        Message {
+            text: Cow::Borrowed(&text[4..]),
+        },
+    );
+
+    let index = Index { positions, entries };
+
+    let borrowed = index.borrowed();
+    assert!(borrowed
+        .positions
+        .keys()
+        .all(|key| matches!(key, Cow::Borrowed("key"))));
+    assert_eq!(borrowed, index);
+
+    let owned: Index<'static> = index.into_owned();
+    drop(text);
+
+    assert!(owned
+        .positions
+        .keys()
+        .all(|key| matches!(key, Cow::Owned(ref s) if s == "key")));
+    assert!(matches!(owned.entries[&b"value"[..]].text, Cow::Owned(ref s) if s == "value"));
+
+    let mirror = IndexOwned::from(owned);
+    assert_eq!(mirror.positions["key"], 0);
+    assert_eq!(mirror.entries[&b"value"[..]].text, "value");
+}
+
+#[derive(IntoOwned, Borrowed, Debug, PartialEq)]
+#[into_owned(mirror(derive(Debug)))]
+struct Sections<'a> {
+    #[into_owned(expect = "map(cow, iterable(cow))")]
+    chunks: BTreeMap<Cow<'a, str>, Vec<Cow<'a, [u8]>>>,
+    messages: std::collections::BTreeMap<u32, Message<'a>>,
+}
+
+#[test]
+fn btree_maps() {
+    let text = String::from("header body");
+
+    let mut chunks = BTreeMap::new();
+    chunks.insert(
+        Cow::Borrowed(&text[..6]),
+        vec![Cow::Borrowed(&text.as_bytes()[7..])],
+    );
+    let mut messages = BTreeMap::new();
+    messages.insert(
+        2,
+        Message {
+            text: Cow::Borrowed(&text[7..]),
+        },
+    );
+
+    let sections = Sections { chunks, messages };
+
+    let borrowed = sections.borrowed();
+    assert!(matches!(
+        borrowed.chunks["header"][0],
+        Cow::Borrowed(b"body")
+    ));
+    assert_eq!(borrowed, sections);
+
+    let owned: Sections<'static> = sections.into_owned();
+    drop(text);
+
+    let (key, chunks) = owned.chunks.iter().next().unwrap();
+    assert!(matches!(key, Cow::Owned(ref s) if s == "header"));
+    assert!(matches!(chunks[0], Cow::Owned(ref s) if s == b"body"));
+    assert!(matches!(owned.messages[&2].text, Cow::Owned(ref s) if s == "body"));
+
+    let mirror = SectionsOwned::from(owned);
+    assert_eq!(mirror.chunks["header"], [b"body".to_vec()]);
+    assert_eq!(mirror.messages[&2].text, "body");
+}
+
+type FastHasher = std::hash::BuildHasherDefault<std::collections::hash_map::DefaultHasher>;
+
+#[derive(IntoOwned, Borrowed, Debug, PartialEq)]
+#[into_owned(mirror(derive(Debug)))]
+struct Hashed<'a> {
+    #[into_owned(expect = "map(cow, cow_alike)")]
+    messages: HashMap<Cow<'a, str>, Message<'a>, FastHasher>,
+}
+
+#[test]
+fn custom_hashers() {
+    let text = String::from("key");
+
+    let mut messages = HashMap::default();
+    messages.insert(
+        Cow::Borrowed(&text[..]),
+        Message {
+            text: Cow::Borrowed(&text[1..]),
+        },
+    );
+    let hashed = Hashed { messages };
+
+    let borrowed = hashed.borrowed();
+    assert!(matches!(borrowed.messages["key"].text, Cow::Borrowed("ey")));
+
+    let owned: Hashed<'static> = hashed.into_owned();
+    drop(text);
+    assert!(matches!(owned.messages["key"].text, Cow::Owned(ref s) if s == "ey"));
+
+    let mirror: HashedOwned = HashedOwned::from(owned);
+    let messages: &HashMap<String, Message<'static>, FastHasher> = &mirror.messages;
+    assert_eq!(messages["key"].text, "ey");
+}
@@ -0,0 +1,94 @@
+#[macro_use]
+extern crate derive_into_owned;
+
+use std::borrow::Cow;
+
+#[derive(IntoOwned, Debug, PartialEq)]
+#[into_owned(map_cows)]
+struct Comment<'a> {
+    author: Cow<'a, str>,
+    tags: Vec<Cow<'a, str>>,
+    reply_to: Option<Box<Comment<'a>>>,
+    attachment: Option<Cow<'a, [u8]>>,
+    likes: u32,
+}
+
+#[derive(IntoOwned, Debug, PartialEq)]
+#[into_owned(map_cows)]
+enum Event<'a> {
+    Posted(Comment<'a>),
+    Renamed {
+        from: Cow<'a, str>,
+        to: Cow<'a, str>,
+    },
+    Closed,
+}
+
+fn trimmed(s: Cow<'_, str>) -> Cow<'_, str> {
+    match s {
+        Cow::Borrowed(s) => Cow::Borrowed(s.trim()),
+        Cow::Owned(s) => Cow::Owned(s.trim().to_owned()),
+    }
+}
+
+#[test]
+fn maps_every_str() {
+    let reply = Comment {
+        author: Cow::Borrowed(" b "),
+        tags: vec![],
+        reply_to: None,
+        attachment: None,
+        likes: 1,
+    };
+    let comment = Comment {
+        author: Cow::Owned(String::from(" a")),
+        tags: vec![Cow::Borrowed("x "), Cow::Borrowed("y")],
+        reply_to: Some(Box::new(reply)),
+        attachment: Some(Cow::Borrowed(b" raw ")),
+        likes: 2,
+    };
+
+    let comment = comment.map_cows(trimmed);
+
+    assert_eq!(comment.author, "a");
+    assert_eq!(comment.tags, vec!["x", "y"]);
+    assert_eq!(comment.reply_to.unwrap().author, "b");
+    assert_eq!(comment.attachment, Some(Cow::Borrowed(&b" raw "[..])));
+    assert_eq!(comment.likes, 2);
+}
+
+#[test]
+fn maps_enum_variants() {
+    let mut seen = Vec::new();
+    let event = Event::Renamed {
+        from: Cow::Borrowed("old"),
+        to: Cow::Borrowed("new"),
+    }
+    .map_cows(|s| {
+        seen.push(s.to_string());
+        Cow::Owned(s.to_uppercase())
+    });
+
+    assert_eq!(
+        event,
+        Event::Renamed {
+            from: Cow::Owned(String::from("OLD")),
+            to: Cow::Owned(String::from("NEW")),
+        }
+    );
+    assert_eq!(seen, ["old", "new"]);
+    assert_eq!(Event::Closed.map_cows(|_| unreachable!()), Event::Closed);
+
+    let posted = Event::Posted(Comment {
+        author: Cow::Borrowed(" c"),
+        tags: vec![Cow::Borrowed(" d ")],
+        reply_to: None,
+        attachment: None,
+        likes: 0,
+    })
+    .map_cows(trimmed);
+    match posted {
+        Event::Posted(comment) => assert_eq!(comment.tags, vec!["d"]),
+        other => panic!("unexpected {:?}", other),
+    }
+}
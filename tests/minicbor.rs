@@ -0,0 +1,92 @@
+#![cfg(feature = "minicbor")]
+#![allow(dead_code)]
+
+#[macro_use]
+extern crate derive_into_owned;
+
+use std::borrow::Cow;
+
+use minicbor::{Decode, Encode};
+
+#[derive(IntoOwned, Encode, Decode)]
+#[into_owned(mirror(minicbor, derive(Debug, PartialEq)))]
+#[cbor(map)]
+struct Message<'a> {
+    #[b(0)]
+    topic: Cow<'a, str>,
+    #[b(1)]
+    sender: &'a str,
+    #[cbor(b(2), with = "minicbor::bytes")]
+    payload: &'a [u8],
+    #[n(3)]
+    tags: Vec<Cow<'a, str>>,
+    #[n(4)]
+    kind: Kind,
+}
+
+#[derive(Debug, Clone, PartialEq, Encode, Decode)]
+enum Kind {
+    #[n(0)]
+    Text,
+    #[n(1)]
+    Binary,
+}
+
+#[derive(IntoOwned, Encode, Decode)]
+#[into_owned(mirror(minicbor, derive(Debug, PartialEq)))]
+enum Event<'a> {
+    #[n(0)]
+    Joined(#[b(0)] &'a str),
+    #[n(1)]
+    Said {
+        #[b(0)]
+        who: &'a str,
+        #[b(1)]
+        what: Cow<'a, str>,
+    },
+}
+
+#[test]
+fn decode_borrowed_into_mirror() {
+    let owned = MessageOwned {
+        topic: "news".to_owned(),
+        sender: "alice".to_owned(),
+        payload: b"hello".to_vec(),
+        tags: vec!["a".to_owned(), "b".to_owned()],
+        kind: Kind::Binary,
+    };
+    let encoded = minicbor::to_vec(&owned).unwrap();
+
+    let borrowed: Message = minicbor::decode(&encoded).unwrap();
+    assert!(matches!(borrowed.topic, Cow::Borrowed("news")));
+    assert_eq!(borrowed.sender, "alice");
+    assert_eq!(borrowed.payload, b"hello");
+
+    let mirror = MessageOwned::from(borrowed);
+    assert_eq!(mirror, owned);
+    assert_eq!(minicbor::to_vec(&mirror).unwrap(), encoded);
+    assert_eq!(minicbor::decode::<MessageOwned>(&encoded).unwrap(), owned);
+}
+
+#[test]
+fn enum_variants_keep_indices() {
+    let said = Event::Said {
+        who: "bob",
+        what: Cow::Borrowed("hi"),
+    };
+    let encoded = minicbor::to_vec(&said).unwrap();
+
+    let mirror: EventOwned = minicbor::decode(&encoded).unwrap();
+    assert_eq!(
+        mirror,
+        EventOwned::Said {
+            who: "bob".to_owned(),
+            what: "hi".to_owned(),
+        }
+    );
+    assert_eq!(minicbor::to_vec(EventOwned::from(said)).unwrap(), encoded);
+
+    let joined = minicbor::to_vec(EventOwned::Joined("carol".to_owned())).unwrap();
+    let borrowed: Event = minicbor::decode(&joined).unwrap();
+    assert!(matches!(borrowed, Event::Joined("carol")));
+}
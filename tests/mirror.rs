@@ -0,0 +1,208 @@
+#![allow(dead_code)]
+
+#[macro_use]
+extern crate derive_into_owned;
+
+use std::borrow::Cow;
+use std::collections::HashMap;
+use std::path::Path;
+
+#[derive(IntoOwned, Debug, Clone)]
+#[into_owned(mirror(derive(Debug, Clone, PartialEq)))]
+pub struct Message<'a> {
+    pub topic: Cow<'a, str>,
+    payload: Cow<'a, [u8]>,
+    path: Option<Cow<'a, Path>>,
+    tags: Vec<Cow<'a, str>>,
+    headers: HashMap<Cow<'a, str>, Option<Cow<'a, str>>>,
+    retries: u32,
+    nested: Option<Box<Header<'a>>>,
+}
+
+#[derive(IntoOwned, Debug, Clone, PartialEq)]
+pub struct Header<'a> {
+    name: Cow<'a, str>,
+}
+
+#[derive(IntoOwned)]
+#[into_owned(mirror(name = "OwnedPair"))]
+struct Pair<'a, T: Clone + 'a>(Cow<'a, str>, T);
+
+#[derive(IntoOwned)]
+#[into_owned(mirror)]
+struct Unit;
+
+#[test]
+fn mirror_struct() {
+    let data = String::from("data");
+
+    let message = Message {
+        topic: Cow::Borrowed(&data),
+        payload: Cow::Borrowed(data.as_bytes()),
+        path: Some(Cow::Borrowed(Path::new(&data))),
+        tags: vec![Cow::Borrowed(&data)],
+        headers: vec![(Cow::Borrowed(&data[..1]), Some(Cow::Borrowed(&data[1..])))]
+            .into_iter()
+            .collect(),
+        retries: 1,
+        nested: Some(Box::new(Header {
+            name: Cow::Borrowed(&data),
+        })),
+    };
+
+    let owned: MessageOwned = message.into();
+    drop(data);
+
+    let expected = MessageOwned {
+        topic: String::from("data"),
+        payload: b"data".to_vec(),
+        path: Some(Path::new("data").to_path_buf()),
+        tags: vec![String::from("data")],
+        headers: vec![(String::from("d"), Some(String::from("ata")))]
+            .into_iter()
+            .collect(),
+        retries: 1,
+        nested: Some(Box::new(Header {
+            name: Cow::Borrowed("data"),
+        })),
+    };
+
+    assert_eq!(owned, expected);
+}
+
+#[test]
+fn mirror_tuple_struct() {
+    let data = String::from("data");
+
+    let OwnedPair(s, n): OwnedPair<u32> = Pair(Cow::Borrowed(&data), 4).into();
+
+    assert_eq!(s, "data");
+    assert_eq!(n, 4);
+}
+
+#[test]
+fn mirror_unit_struct() {
+    let _: UnitOwned = Unit.into();
+}
+
+#[derive(IntoOwned, Debug)]
+#[into_owned(mirror(derive(Debug, PartialEq)))]
+pub struct Token<'a> {
+    text: &'a str,
+    bytes: &'a [u8],
+    parts: Vec<&'a str>,
+    prefix: Option<&'a str>,
+    kind: &'static str,
+}
+
+#[test]
+fn mirror_references() {
+    let data = String::from("let x");
+
+    let token = Token {
+        text: &data[..3],
+        bytes: data.as_bytes(),
+        parts: data.split(' ').collect(),
+        prefix: Some(&data[4..]),
+        kind: "keyword",
+    };
+
+    let owned = TokenOwned::from(token);
+    drop(data);
+
+    let expected = TokenOwned {
+        text: String::from("let"),
+        bytes: b"let x".to_vec(),
+        parts: vec![String::from("let"), String::from("x")],
+        prefix: Some(String::from("x")),
+        kind: "keyword",
+    };
+
+    assert_eq!(owned, expected);
+}
+
+#[derive(IntoOwned)]
+#[into_owned(mirror(derive(Debug, PartialEq)))]
+struct Customized<'a> {
+    #[into_owned(mirror(name = "label"))]
+    name: Cow<'a, str>,
+    #[into_owned(mirror(ty = "Box<str>"))]
+    value: Cow<'a, str>,
+    #[into_owned(mirror(skip))]
+    cache: Vec<u8>,
+}
+
+#[derive(IntoOwned)]
+#[into_owned(mirror(derive(Debug, PartialEq)))]
+struct CustomizedPair<'a>(
+    #[into_owned(mirror(skip))] &'a [u8],
+    #[into_owned(mirror(ty = "std::sync::Arc<str>"))] Cow<'a, str>,
+);
+
+#[test]
+fn mirror_field_options() {
+    let owned: CustomizedOwned = Customized {
+        name: Cow::Borrowed("n"),
+        value: Cow::Borrowed("v"),
+        cache: vec![1],
+    }
+    .into();
+
+    assert_eq!(
+        owned,
+        CustomizedOwned {
+            label: String::from("n"),
+            value: Box::from("v"),
+        }
+    );
+
+    let CustomizedPairOwned(s) = CustomizedPair(b"raw", Cow::Borrowed("s")).into();
+    assert_eq!(&*s, "s");
+}
+
+#[derive(IntoOwned, Debug, PartialEq)]
+#[into_owned(mirror(derive(Debug, PartialEq)))]
+pub enum Value<'a> {
+    /// No value
+    Null,
+    Text(Cow<'a, str>),
+    Bytes(#[into_owned(mirror(ty = "Box<[u8]>"))] Cow<'a, [u8]>),
+    List(Vec<Value<'a>>),
+    Entry {
+        #[into_owned(mirror(name = "name"))]
+        key: Cow<'a, str>,
+        value: Box<Value<'a>>,
+        #[into_owned(mirror(skip))]
+        position: usize,
+    },
+}
+
+#[test]
+fn mirror_enum() {
+    let data = String::from("data");
+
+    let value = Value::Entry {
+        key: Cow::Borrowed(&data),
+        value: Box::new(Value::List(vec![
+            Value::Null,
+            Value::Text(Cow::Borrowed(&data)),
+            Value::Bytes(Cow::Borrowed(data.as_bytes())),
+        ])),
+        position: 3,
+    };
+
+    let owned: ValueOwned = value.into();
+    drop(data);
+
+    assert_eq!(
+        owned,
+        ValueOwned::Entry {
+            name: String::from("data"),
+            value: Box::new(Value::List(vec![
+                Value::Null,
+                Value::Text(Cow::Owned(String::from("data"))),
+                Value::Bytes(Cow::Owned(b"data".to_vec())),
+            ])),
+        }
+    );
+}
@@ -0,0 +1,198 @@
+#![allow(dead_code)]
+// the std cells need Rust 1.70, which is newer than the MSRV of the crate
+#![allow(clippy::incompatible_msrv)]
+
+#[macro_use]
+extern crate derive_into_owned;
+
+use std::borrow::Cow;
+use std::cell::OnceCell;
+use std::collections::HashMap;
+use std::ops::Bound;
+use std::path::Path;
+use std::sync::Arc;
+
+#[derive(IntoOwned, Debug, PartialEq)]
+#[into_owned(mirror(partial_eq, derive(Debug)))]
+struct Message<'a> {
+    topic: Cow<'a, str>,
+    #[into_owned(mirror(name = "body"))]
+    payload: &'a [u8],
+    path: Option<Cow<'a, Path>>,
+    tags: Vec<Option<Cow<'a, str>>>,
+    headers: HashMap<Cow<'a, str>, &'a str>,
+    retries: u32,
+    nested: Box<Header<'a>>,
+    #[into_owned(mirror(skip))]
+    ignored: &'a str,
+}
+
+#[derive(IntoOwned, Debug, PartialEq)]
+struct Header<'a> {
+    name: Cow<'a, str>,
+}
+
+#[derive(IntoOwned, Debug)]
+#[into_owned(mirror(partial_eq, derive(Debug)))]
+struct Pair<'a, T: Clone + 'a>(#[into_owned(mirror(skip))] u8, Cow<'a, str>, T);
+
+#[derive(IntoOwned, Debug)]
+#[into_owned(mirror(partial_eq, arc, derive(Debug)))]
+enum Event<'a> {
+    Joined(&'a str),
+    Said { who: &'a str, what: Cow<'a, [u8]> },
+    Left,
+}
+
+#[derive(IntoOwned)]
+#[into_owned(mirror(partial_eq))]
+enum Never {}
+
+#[derive(IntoOwned, Debug)]
+#[into_owned(mirror(partial_eq, derive(Debug)))]
+struct Reply<'a> {
+    to: Option<&'a str>,
+    text: Cow<'a, str>,
+}
+
+#[derive(IntoOwned, Debug)]
+#[into_owned(mirror(partial_eq, derive(Debug)))]
+struct Quote<'a> {
+    text: Option<Cow<'a, str>>,
+}
+
+#[derive(IntoOwned, Debug)]
+#[into_owned(mirror(partial_eq, derive(Debug)))]
+struct Range<'a> {
+    start: Bound<Cow<'a, str>>,
+    end: Bound<Cow<'a, str>>,
+}
+
+#[derive(IntoOwned, Debug)]
+#[into_owned(mirror(partial_eq, derive(Debug)))]
+struct Lazy<'a> {
+    title: OnceCell<Cow<'a, str>>,
+    text: Cow<'a, str>,
+}
+
+#[test]
+fn struct_equals_mirror() {
+    let message = || Message {
+        topic: Cow::Borrowed("news"),
+        payload: b"body",
+        path: Some(Cow::Borrowed(Path::new("/tmp"))),
+        tags: vec![Some(Cow::Borrowed("a")), None],
+        headers: vec![(Cow::Borrowed("k1"), "v1"), (Cow::Borrowed("k2"), "v2")]
+            .into_iter()
+            .collect(),
+        retries: 2,
+        nested: Box::new(Header {
+            name: Cow::Borrowed("header"),
+        }),
+        ignored: "left out",
+    };
+
+    let expected = MessageOwned::from(message());
+    assert_eq!(message(), expected);
+    assert_eq!(expected, message());
+
+    let mut other = message();
+    other.tags[1] = Some(Cow::Borrowed("b"));
+    assert_ne!(other, expected);
+
+    let mut other = message();
+    other.headers.insert(Cow::Borrowed("k2"), "other");
+    assert_ne!(other, expected);
+
+    let mut other = message();
+    other.ignored = "differs";
+    assert_eq!(other, expected);
+}
+
+#[test]
+fn tuple_struct_skips_fields() {
+    let owned = PairOwned(String::from("one"), 1);
+
+    assert_eq!(Pair(0, Cow::Borrowed("one"), 1), owned);
+    assert_ne!(Pair(0, Cow::Borrowed("one"), 2), owned);
+    assert_ne!(Pair(0, Cow::Borrowed("two"), 1), owned);
+}
+
+#[test]
+fn enum_equals_mirror() {
+    let said = EventOwned::Said {
+        who: Arc::from("bob"),
+        what: Arc::from(&b"hi"[..]),
+    };
+
+    assert_eq!(
+        Event::Said {
+            who: "bob",
+            what: Cow::Borrowed(b"hi"),
+        },
+        said
+    );
+    assert_ne!(Event::Joined("bob"), said);
+    assert_eq!(Event::Joined("bob"), EventOwned::Joined(Arc::from("bob")));
+    assert_eq!(EventOwned::Left, Event::Left);
+}
+
+#[test]
+fn optional_first_field() {
+    let reply = ReplyOwned::from(Reply {
+        to: Some("alice"),
+        text: Cow::Borrowed("hi"),
+    });
+
+    assert_eq!(
+        Reply {
+            to: Some("alice"),
+            text: Cow::Borrowed("hi"),
+        },
+        reply
+    );
+    assert_ne!(
+        Reply {
+            to: None,
+            text: Cow::Borrowed("hi"),
+        },
+        reply
+    );
+    assert_eq!(Quote { text: None }, QuoteOwned::from(Quote { text: None }));
+}
+
+#[test]
+fn bound_first_field() {
+    let range = || Range {
+        start: Bound::Included(Cow::Borrowed("a")),
+        end: Bound::Unbounded,
+    };
+    let owned = RangeOwned::from(range());
+
+    assert_eq!(range(), owned);
+    assert_ne!(
+        Range {
+            start: Bound::Excluded(Cow::Borrowed("a")),
+            ..range()
+        },
+        owned
+    );
+}
+
+#[test]
+fn once_cell_first_field() {
+    let lazy = || Lazy {
+        title: OnceCell::from(Cow::Borrowed("title")),
+        text: Cow::Borrowed("text"),
+    };
+    let owned = LazyOwned::from(lazy());
+
+    assert_eq!(lazy(), owned);
+    assert_ne!(
+        Lazy {
+            title: OnceCell::new(),
+            ..lazy()
+        },
+        owned
+    );
+}
@@ -0,0 +1,68 @@
+use derive_into_owned::into_owned_module;
+use std::borrow::Cow;
+
+#[into_owned_module]
+mod contacts {
+    include!(concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/tests/pb_rs/contacts.rs"
+    ));
+}
+
+#[into_owned_module]
+mod inline {
+    use std::borrow::Cow;
+
+    #[derive(Debug, PartialEq)]
+    pub struct Tag<'a>(pub Cow<'a, str>);
+
+    // already derived types are left alone
+    #[derive(Debug, PartialEq, derive_into_owned::IntoOwned)]
+    pub struct Post<'a> {
+        pub title: Cow<'a, str>,
+        pub tags: Vec<Tag<'a>>,
+    }
+}
+
+use contacts::mod_Contact::{Kind, OneOfnote};
+use contacts::Contact;
+
+#[test]
+fn included_types_are_derived() {
+    let name = String::from("Bob");
+    let note = String::from("met at the conference");
+
+    let contact = Contact {
+        name: Cow::Borrowed(&name),
+        emails: vec![Cow::Borrowed("bob@example.com")],
+        kind: Kind::COMPANY,
+        note: OneOfnote::text(Cow::Borrowed(&note)),
+    };
+
+    let owned: Contact<'static> = contact.into_owned();
+    drop((name, note));
+
+    assert_eq!(owned.name, "Bob");
+    assert_eq!(owned.emails, ["bob@example.com"]);
+    assert_eq!(owned.kind.into_owned(), Kind::COMPANY);
+    assert_eq!(
+        owned.note,
+        OneOfnote::text(Cow::Borrowed("met at the conference"))
+    );
+}
+
+#[test]
+fn inline_types_are_derived() {
+    let title = String::from("hello");
+
+    let post = inline::Post {
+        title: Cow::Borrowed(&title),
+        tags: vec![inline::Tag(Cow::Borrowed("greeting"))],
+    };
+
+    let owned: inline::Post<'static> = post.into_owned();
+    drop(title);
+
+    assert_eq!(owned.title, "hello");
+    assert_eq!(owned.tags, [inline::Tag(Cow::Borrowed("greeting"))]);
+}
@@ -0,0 +1,41 @@
+#[macro_use]
+extern crate derive_into_owned;
+
+use std::borrow::Cow;
+
+#[derive(IntoOwned, Debug, PartialEq)]
+#[into_owned(msrv = "1.56")]
+struct Point {
+    x: i32,
+    y: i32,
+}
+
+#[derive(IntoOwned, Debug, PartialEq)]
+#[into_owned(msrv = "1.61.0")]
+struct Origin {
+    x: i32,
+}
+
+#[derive(IntoOwned, Debug)]
+#[into_owned(msrv = "1.56")]
+struct Named<'a> {
+    name: Cow<'a, str>,
+    point: Option<Point>,
+}
+
+const ORIGIN: Origin = Origin { x: 0 }.into_owned();
+
+#[test]
+fn declared_msrv() {
+    assert_eq!(Point { x: 1, y: 2 }.into_owned(), Point { x: 1, y: 2 });
+    assert_eq!(ORIGIN, Origin { x: 0 });
+
+    let name = String::from("a");
+    let named = Named {
+        name: Cow::Borrowed(&name),
+        point: None,
+    }
+    .into_owned();
+    drop(name);
+    assert_eq!(named.name, "a");
+}
@@ -0,0 +1,88 @@
+#[macro_use]
+extern crate derive_into_owned;
+
+use std::borrow::Cow;
+
+#[derive(IntoOwned, Borrowed, Debug, PartialEq)]
+struct Span<'a, 'b> {
+    name: Cow<'a, str>,
+    file: Cow<'b, str>,
+}
+
+#[derive(IntoOwned, Borrowed, Debug, PartialEq)]
+enum Trace<'a, 'b: 'a> {
+    Single(Span<'a, 'b>),
+    Chain {
+        first: Span<'b, 'a>,
+        rest: Vec<Span<'a, 'b>>,
+    },
+}
+
+#[test]
+fn multiple_lifetimes() {
+    let name = "main".to_string();
+    let file = "main.rs".to_string();
+
+    let span = Span {
+        name: Cow::Borrowed(&name),
+        file: Cow::Borrowed(&file),
+    };
+    let owned: Span<'static, 'static> = span.into_owned();
+
+    // both lifetimes of the borrowed copy are the lifetime of the borrow
+    let borrowed: Span<'_, '_> = owned.borrowed();
+    assert!(matches!(borrowed.name, Cow::Borrowed("main")));
+    assert!(matches!(borrowed.file, Cow::Borrowed("main.rs")));
+
+    let trace = Trace::Chain {
+        first: owned.borrowed(),
+        rest: vec![owned.borrowed()],
+    };
+    let owned_trace: Trace<'static, 'static> = trace.borrowed().into_owned();
+    assert_eq!(owned_trace, trace);
+
+    let single = Trace::Single(borrowed);
+    assert_eq!(
+        single.into_owned().borrowed(),
+        Trace::Single(owned.borrowed())
+    );
+}
+
+#[derive(IntoOwned, Borrowed)]
+struct Outlives<'a, 'b: 'a, 'c: 'a + 'b> {
+    short: Cow<'a, str>,
+    long: Cow<'b, str>,
+    longest: Cow<'c, [u8]>,
+}
+
+#[derive(IntoOwned, Borrowed)]
+struct WhereOutlives<'a, 'b, T: 'a + Clone>
+where
+    'b: 'a,
+{
+    values: Cow<'a, [T]>,
+    name: Option<Cow<'b, str>>,
+}
+
+#[test]
+fn lifetime_bounds() {
+    let name = "name".to_string();
+    let values = vec![1u32, 2];
+
+    let outlives = Outlives {
+        short: Cow::Borrowed(&name),
+        long: Cow::Borrowed(&name),
+        longest: Cow::Borrowed(b"bytes"),
+    };
+    let outlives: Outlives<'static, 'static, 'static> = outlives.borrowed().into_owned();
+    assert_eq!(outlives.short, outlives.borrowed().long);
+    assert_eq!(&*outlives.longest, b"bytes");
+
+    let where_outlives = WhereOutlives {
+        values: Cow::Borrowed(&values),
+        name: Some(Cow::Borrowed(&name)),
+    };
+    let owned: WhereOutlives<'static, 'static, u32> = where_outlives.borrowed().into_owned();
+    assert_eq!(owned.borrowed().values, Cow::Borrowed(&[1, 2][..]));
+    assert_eq!(owned.name.as_deref(), Some("name"));
+}
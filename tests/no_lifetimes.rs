@@ -0,0 +1,61 @@
+#[macro_use]
+extern crate derive_into_owned;
+
+use std::borrow::Cow;
+
+#[derive(IntoOwned, Borrowed, Clone, Debug, PartialEq)]
+struct Header {
+    name: Cow<'static, str>,
+    values: Vec<String>,
+}
+
+#[derive(IntoOwned, Borrowed, Clone, Debug, PartialEq)]
+enum Body<T> {
+    Empty,
+    Text(String),
+    Other { value: T, headers: Vec<Header> },
+}
+
+#[derive(IntoOwned, Borrowed, Debug, PartialEq)]
+struct Message<'a> {
+    subject: Cow<'a, str>,
+    headers: Vec<Header>,
+    body: Body<u32>,
+}
+
+const HEADER: Header = Header {
+    name: Cow::Borrowed("static"),
+    values: Vec::new(),
+}
+.into_owned();
+
+#[test]
+fn types_without_lifetimes() {
+    let header = Header {
+        name: Cow::Borrowed("content-type"),
+        values: vec!["text/plain".to_string()],
+    };
+    assert_eq!(header.borrowed(), header);
+    assert!(matches!(
+        header.clone().into_owned().name,
+        Cow::Borrowed("content-type")
+    ));
+    assert_eq!(HEADER.name, "static");
+
+    let body = Body::Other {
+        value: 7,
+        headers: vec![header.clone()],
+    };
+    assert_eq!(body.borrowed(), body);
+    assert_eq!(body.clone().into_owned(), body);
+    assert_eq!(Body::<u32>::Empty.borrowed(), Body::Empty);
+
+    let subject = "hello".to_string();
+    let message = Message {
+        subject: Cow::Borrowed(&subject),
+        headers: vec![header],
+        body: Body::Text("world".to_string()),
+    };
+    let owned: Message<'static> = message.borrowed().into_owned();
+    assert_eq!(owned, message);
+}
@@ -0,0 +1,62 @@
+// the std cells need Rust 1.70, which is newer than the MSRV of the crate
+#![allow(clippy::incompatible_msrv)]
+
+#[macro_use]
+extern crate derive_into_owned;
+
+use std::borrow::Cow;
+
+#[derive(IntoOwned, Borrowed, Debug)]
+#[into_owned(ownership, borrowed_strs)]
+struct Document<'a> {
+    source: Cow<'a, str>,
+    #[into_owned(expect = "once_cell(cow)")]
+    title: once_cell::unsync::OnceCell<Cow<'a, str>>,
+    summary: std::cell::OnceCell<Option<Cow<'a, str>>>,
+    words: once_cell::sync::OnceCell<Vec<Cow<'a, str>>>,
+    length: std::sync::OnceLock<usize>,
+}
+
+impl<'a> Document<'a> {
+    fn new(source: &'a str) -> Self {
+        Document {
+            source: Cow::Borrowed(source),
+            title: Default::default(),
+            summary: Default::default(),
+            words: Default::default(),
+            length: Default::default(),
+        }
+    }
+
+    fn title(&self) -> &str {
+        self.title.get_or_init(|| match self.source {
+            Cow::Borrowed(source) => Cow::Borrowed(source.lines().next().unwrap_or("")),
+            Cow::Owned(ref source) => Cow::Owned(source.lines().next().unwrap_or("").into()),
+        })
+    }
+}
+
+#[test]
+fn converts_initialized_cells() {
+    let data = String::from("title\nbody");
+
+    let document = Document::new(&data);
+    assert_eq!(document.title(), "title");
+    document.words.set(vec![Cow::Borrowed("body")]).unwrap();
+    document.length.set(10).unwrap();
+    assert_eq!(document.borrowed_strs(), ["title\nbody", "title", "body"]);
+    assert!(!document.ownership().title);
+
+    let document = document.into_owned();
+    drop(data);
+
+    assert_eq!(document.title(), "title");
+    assert!(matches!(document.title.get(), Some(Cow::Owned(_))));
+    assert!(document.summary.get().is_none());
+    assert_eq!(document.words.get().unwrap(), &["body"]);
+    assert_eq!(document.length.get(), Some(&10));
+    assert!(document.ownership().title);
+
+    let borrowed = document.borrowed();
+    assert!(matches!(borrowed.title.get(), Some(Cow::Borrowed("title"))));
+}
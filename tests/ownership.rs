@@ -0,0 +1,68 @@
+#[macro_use]
+extern crate derive_into_owned;
+
+use std::borrow::Cow;
+use std::collections::HashMap;
+
+#[derive(IntoOwned, Debug)]
+#[into_owned(ownership)]
+struct Request<'a> {
+    path: Cow<'a, str>,
+    query: Option<Option<Cow<'a, str>>>,
+    headers: Vec<Cow<'a, str>>,
+    cookies: HashMap<String, Cow<'a, [u8]>>,
+    body: Box<Cow<'a, [u8]>>,
+    retries: u32,
+}
+
+#[derive(IntoOwned, Debug)]
+#[into_owned(ownership)]
+struct Pair<'a>(u8, Cow<'a, str>);
+
+#[test]
+fn reports_each_field() {
+    let mut cookies = HashMap::new();
+    cookies.insert(String::from("a"), Cow::Borrowed(&b"1"[..]));
+
+    let request = Request {
+        path: Cow::Owned(String::from("/")),
+        query: Some(None),
+        headers: vec![Cow::Owned(String::from("a")), Cow::Borrowed("b")],
+        cookies,
+        body: Box::new(Cow::Borrowed(b"body")),
+        retries: 0,
+    };
+
+    assert_eq!(
+        request.ownership(),
+        RequestOwnership {
+            path: true,
+            query: true,
+            headers: false,
+            cookies: false,
+            body: false,
+        }
+    );
+
+    let request = request.into_owned();
+    assert_eq!(
+        request.ownership(),
+        RequestOwnership {
+            path: true,
+            query: true,
+            headers: true,
+            cookies: true,
+            body: true,
+        }
+    );
+}
+
+#[test]
+fn tuple_struct_fields() {
+    let pair = Pair(1, Cow::Borrowed("a"));
+    assert_eq!(pair.ownership(), PairOwnership { field_1: false });
+    assert_eq!(
+        pair.into_owned().ownership(),
+        PairOwnership { field_1: true }
+    );
+}
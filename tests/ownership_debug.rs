@@ -0,0 +1,70 @@
+#[macro_use]
+extern crate derive_into_owned;
+
+use std::borrow::Cow;
+use std::collections::HashMap;
+
+#[derive(OwnershipDebug)]
+struct Request<'a> {
+    path: Cow<'a, str>,
+    method: &'a str,
+    headers: Vec<Header<'a>>,
+    query: HashMap<Cow<'a, str>, Option<Cow<'a, str>>>,
+    body: Option<Box<Cow<'a, [u8]>>>,
+    retries: u8,
+}
+
+#[derive(OwnershipDebug)]
+enum Header<'a> {
+    Named {
+        name: Cow<'a, str>,
+        r#type: Cow<'a, str>,
+    },
+    Raw(Cow<'a, str>),
+    Empty,
+}
+
+#[derive(OwnershipDebug)]
+struct Tagged<'a, T>(Cow<'a, str>, T);
+
+#[test]
+fn marks_cows() {
+    let text = String::from("text");
+    let request = Request {
+        path: Cow::Borrowed(&text),
+        method: "GET",
+        headers: vec![
+            Header::Named {
+                name: Cow::Owned(String::from("accept")),
+                r#type: Cow::Borrowed(&text),
+            },
+            Header::Raw(Cow::Owned(String::from("raw"))),
+            Header::Empty,
+        ],
+        query: Some((Cow::Borrowed("q"), None)).into_iter().collect(),
+        body: Some(Box::new(Cow::Owned(vec![1]))),
+        retries: 3,
+    };
+
+    assert_eq!(
+        format!("{:?}", request.ownership_debug()),
+        "Request { path: [borrowed] \"text\", method: [borrowed] \"GET\", \
+         headers: [Named { name: [owned] \"accept\", type: [borrowed] \"text\" }, \
+         Raw([owned] \"raw\"), Empty], query: {[borrowed] \"q\": None}, \
+         body: Some([owned] [1]), retries: 3 }"
+    );
+}
+
+#[test]
+fn pretty_prints() {
+    let tagged = Tagged(Cow::Borrowed("name"), 1u8);
+
+    assert_eq!(
+        format!("{:?}", tagged.ownership_debug()),
+        "Tagged([borrowed] \"name\", 1)"
+    );
+    assert_eq!(
+        format!("{:#?}", tagged.ownership_debug()),
+        "Tagged(\n    [borrowed] \"name\",\n    1,\n)"
+    );
+}
@@ -0,0 +1,106 @@
+//! End-to-end test with types shaped the way pb-rs (quick-protobuf) generates them: nested
+//! messages, oneofs as enums, repeated bytes, maps and boxed self references.
+
+#[macro_use]
+extern crate derive_into_owned;
+
+use quick_protobuf::{BytesReader, MessageRead, MessageWrite, Writer};
+use std::borrow::Cow;
+
+#[path = "pb_rs/person.rs"]
+mod person;
+
+use person::mod_Person::{mod_PhoneNumber::PhoneType, OneOfcontact, PhoneNumber};
+use person::{Address, Person};
+
+fn example() -> Person<'static> {
+    let phone = PhoneNumber {
+        number: Cow::Borrowed("555-1234"),
+        kind: Some(PhoneType::HOME),
+    };
+
+    let manager = Person {
+        name: Cow::Borrowed("Alice"),
+        contact: OneOfcontact::extension(42),
+        ..Person::default()
+    };
+
+    Person {
+        name: Cow::Borrowed("Bob"),
+        email: Some(Cow::Borrowed("bob@example.com")),
+        avatars: vec![Cow::Borrowed(b"\x89PNG"), Cow::Borrowed(b"GIF8")],
+        phones: vec![phone.clone()],
+        labels: vec![(Cow::Borrowed("team"), Cow::Borrowed("infra"))]
+            .into_iter()
+            .collect(),
+        phones_by_id: vec![(7, phone)].into_iter().collect(),
+        manager: Some(Box::new(manager)),
+        address: Some(Address { zip: 12345 }),
+        contact: OneOfcontact::slack(Cow::Borrowed("@bob")),
+    }
+}
+
+fn encode(person: &Person<'_>) -> Vec<u8> {
+    let mut out = Vec::new();
+    let mut writer = Writer::new(&mut out);
+    person.write_message(&mut writer).unwrap();
+    out
+}
+
+fn decode(bytes: &[u8]) -> Person<'_> {
+    let mut reader = BytesReader::from_bytes(bytes);
+    Person::from_reader(&mut reader, bytes).unwrap()
+}
+
+#[test]
+fn decode_then_into_owned() {
+    let expected = example();
+    let bytes = encode(&expected);
+
+    let decoded = decode(&bytes);
+    assert!(matches!(decoded.name, Cow::Borrowed(_)));
+    assert!(matches!(decoded.avatars[0], Cow::Borrowed(_)));
+
+    let owned = accepts_only_static(decoded.into_owned());
+    drop(bytes);
+
+    assert!(matches!(owned.name, Cow::Owned(_)));
+    assert!(matches!(owned.avatars[1], Cow::Owned(_)));
+    assert!(matches!(owned.contact, OneOfcontact::slack(Cow::Owned(_))));
+    assert!(owned.labels.keys().all(|key| matches!(key, Cow::Owned(_))));
+    assert!(matches!(
+        owned.manager.as_ref().unwrap().name,
+        Cow::Owned(_)
+    ));
+    assert_eq!(owned, expected);
+}
+
+#[test]
+fn borrowed_does_not_copy() {
+    let owned = decode(&encode(&example())).into_owned();
+
+    let borrowed = owned.borrowed();
+
+    assert!(matches!(borrowed.name, Cow::Borrowed(_)));
+    assert!(matches!(borrowed.email, Some(Cow::Borrowed(_))));
+    assert!(matches!(borrowed.avatars[0], Cow::Borrowed(_)));
+    assert!(matches!(borrowed.phones[0].number, Cow::Borrowed(_)));
+    assert!(borrowed
+        .labels
+        .iter()
+        .all(|(k, v)| matches!((k, v), (Cow::Borrowed(_), Cow::Borrowed(_)))));
+    assert!(matches!(borrowed.phones_by_id[&7].number, Cow::Borrowed(_)));
+    assert!(matches!(
+        borrowed.manager.as_ref().unwrap().name,
+        Cow::Borrowed(_)
+    ));
+    assert!(matches!(
+        borrowed.contact,
+        OneOfcontact::slack(Cow::Borrowed(_))
+    ));
+    assert_eq!(borrowed, owned);
+}
+
+fn accepts_only_static<T: 'static>(anything: T) -> T {
+    anything
+}
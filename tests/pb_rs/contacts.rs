@@ -0,0 +1,54 @@
+// Automatically generated rust module for 'contacts.proto' file, left as generated without any
+// derives of this crate.
+
+#![allow(non_snake_case)]
+#![allow(non_upper_case_globals)]
+#![allow(non_camel_case_types)]
+#![allow(unused_imports)]
+#![allow(unknown_lints)]
+#![allow(clippy::all)]
+#![cfg_attr(rustfmt, rustfmt_skip)]
+
+
+use std::borrow::Cow;
+use super::*;
+
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Debug, Default, PartialEq, Clone)]
+pub struct Contact<'a> {
+    pub name: Cow<'a, str>,
+    pub emails: Vec<Cow<'a, str>>,
+    pub kind: mod_Contact::Kind,
+    pub note: mod_Contact::OneOfnote<'a>,
+}
+
+pub mod mod_Contact {
+
+use std::borrow::Cow;
+use super::*;
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum Kind {
+    PERSON = 0,
+    COMPANY = 1,
+}
+
+impl Default for Kind {
+    fn default() -> Self {
+        Kind::PERSON
+    }
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub enum OneOfnote<'a> {
+    text(Cow<'a, str>),
+    None,
+}
+
+impl<'a> Default for OneOfnote<'a> {
+    fn default() -> Self {
+        OneOfnote::None
+    }
+}
+
+}
@@ -0,0 +1,213 @@
+// Automatically generated rust module for 'person.proto' file, with
+// `#[derive(IntoOwned, Borrowed)]` added to every type.
+
+#![allow(non_snake_case)]
+#![allow(non_upper_case_globals)]
+#![allow(non_camel_case_types)]
+#![allow(unused_imports)]
+#![allow(unknown_lints)]
+#![allow(clippy::all)]
+#![cfg_attr(rustfmt, rustfmt_skip)]
+
+
+use std::borrow::Cow;
+use std::collections::HashMap;
+type KVMap<K, V> = HashMap<K, V>;
+use quick_protobuf::{MessageInfo, MessageRead, MessageWrite, BytesReader, Writer, WriterBackend, Result};
+use quick_protobuf::sizeofs::*;
+use super::*;
+
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Debug, Default, PartialEq, Clone, IntoOwned, Borrowed)]
+pub struct Person<'a> {
+    pub name: Cow<'a, str>,
+    pub email: Option<Cow<'a, str>>,
+    pub avatars: Vec<Cow<'a, [u8]>>,
+    pub phones: Vec<mod_Person::PhoneNumber<'a>>,
+    pub labels: KVMap<Cow<'a, str>, Cow<'a, str>>,
+    pub phones_by_id: KVMap<i32, mod_Person::PhoneNumber<'a>>,
+    pub manager: Option<Box<Person<'a>>>,
+    pub address: Option<Address>,
+    pub contact: mod_Person::OneOfcontact<'a>,
+}
+
+impl<'a> MessageRead<'a> for Person<'a> {
+    fn from_reader(r: &mut BytesReader, bytes: &'a [u8]) -> Result<Self> {
+        let mut msg = Self::default();
+        while !r.is_eof() {
+            match r.next_tag(bytes) {
+                Ok(10) => msg.name = r.read_string(bytes).map(Cow::Borrowed)?,
+                Ok(18) => msg.email = Some(r.read_string(bytes).map(Cow::Borrowed)?),
+                Ok(26) => msg.avatars.push(r.read_bytes(bytes).map(Cow::Borrowed)?),
+                Ok(34) => msg.phones.push(r.read_message::<mod_Person::PhoneNumber>(bytes)?),
+                Ok(42) => {
+                    let (key, value) = r.read_map(bytes, |r, bytes| Ok(r.read_string(bytes).map(Cow::Borrowed)?), |r, bytes| Ok(r.read_string(bytes).map(Cow::Borrowed)?))?;
+                    msg.labels.insert(key, value);
+                }
+                Ok(50) => {
+                    let (key, value) = r.read_map(bytes, |r, bytes| Ok(r.read_int32(bytes)?), |r, bytes| Ok(r.read_message::<mod_Person::PhoneNumber>(bytes)?))?;
+                    msg.phones_by_id.insert(key, value);
+                }
+                Ok(58) => msg.manager = Some(Box::new(r.read_message::<Person>(bytes)?)),
+                Ok(66) => msg.address = Some(r.read_message::<Address>(bytes)?),
+                Ok(74) => msg.contact = mod_Person::OneOfcontact::slack(r.read_string(bytes).map(Cow::Borrowed)?),
+                Ok(80) => msg.contact = mod_Person::OneOfcontact::extension(r.read_int32(bytes)?),
+                Ok(88) => msg.contact = mod_Person::OneOfcontact::unreachable(r.read_bool(bytes)?),
+                Ok(t) => { r.read_unknown(bytes, t)?; }
+                Err(e) => return Err(e),
+            }
+        }
+        Ok(msg)
+    }
+}
+
+impl<'a> MessageWrite for Person<'a> {
+    fn get_size(&self) -> usize {
+        0
+        + 1 + sizeof_len((&self.name).len())
+        + self.email.as_ref().map_or(0, |m| 1 + sizeof_len((m).len()))
+        + self.avatars.iter().map(|s| 1 + sizeof_len((s).len())).sum::<usize>()
+        + self.phones.iter().map(|s| 1 + sizeof_len((s).get_size())).sum::<usize>()
+        + self.labels.iter().map(|(k, v)| 1 + sizeof_len(2 + sizeof_len((k).len()) + sizeof_len((v).len()))).sum::<usize>()
+        + self.phones_by_id.iter().map(|(k, v)| 1 + sizeof_len(2 + sizeof_varint(*(k) as u64) + sizeof_len((v).get_size()))).sum::<usize>()
+        + self.manager.as_ref().map_or(0, |m| 1 + sizeof_len((m).get_size()))
+        + self.address.as_ref().map_or(0, |m| 1 + sizeof_len((m).get_size()))
+        + match self.contact {
+            mod_Person::OneOfcontact::slack(ref m) => 1 + sizeof_len((m).len()),
+            mod_Person::OneOfcontact::extension(ref m) => 1 + sizeof_varint(*(m) as u64),
+            mod_Person::OneOfcontact::unreachable(ref m) => 1 + sizeof_varint(*(m) as u64),
+            mod_Person::OneOfcontact::None => 0,
+    }    }
+
+    fn write_message<W: WriterBackend>(&self, w: &mut Writer<W>) -> Result<()> {
+        w.write_with_tag(10, |w| w.write_string(&**&self.name))?;
+        if let Some(ref s) = self.email { w.write_with_tag(18, |w| w.write_string(&**s))?; }
+        for s in &self.avatars { w.write_with_tag(26, |w| w.write_bytes(&**s))?; }
+        for s in &self.phones { w.write_with_tag(34, |w| w.write_message(s))?; }
+        for (k, v) in self.labels.iter() { w.write_with_tag(42, |w| w.write_map(2 + sizeof_len((k).len()) + sizeof_len((v).len()), 10, |w| w.write_string(&**k), 18, |w| w.write_string(&**v)))?; }
+        for (k, v) in self.phones_by_id.iter() { w.write_with_tag(50, |w| w.write_map(2 + sizeof_varint(*(k) as u64) + sizeof_len((v).get_size()), 8, |w| w.write_int32(*k), 18, |w| w.write_message(v)))?; }
+        if let Some(ref s) = self.manager { w.write_with_tag(58, |w| w.write_message(&**s))?; }
+        if let Some(ref s) = self.address { w.write_with_tag(66, |w| w.write_message(s))?; }
+        match self.contact {            mod_Person::OneOfcontact::slack(ref m) => { w.write_with_tag(74, |w| w.write_string(&**m))? },
+            mod_Person::OneOfcontact::extension(ref m) => { w.write_with_tag(80, |w| w.write_int32(*m))? },
+            mod_Person::OneOfcontact::unreachable(ref m) => { w.write_with_tag(88, |w| w.write_bool(*m))? },
+            mod_Person::OneOfcontact::None => {},
+    }        Ok(())
+    }
+}
+
+pub mod mod_Person {
+
+use std::borrow::Cow;
+use super::*;
+
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Debug, Default, PartialEq, Clone, IntoOwned, Borrowed)]
+pub struct PhoneNumber<'a> {
+    pub number: Cow<'a, str>,
+    pub kind: Option<mod_Person::mod_PhoneNumber::PhoneType>,
+}
+
+impl<'a> MessageRead<'a> for PhoneNumber<'a> {
+    fn from_reader(r: &mut BytesReader, bytes: &'a [u8]) -> Result<Self> {
+        let mut msg = Self::default();
+        while !r.is_eof() {
+            match r.next_tag(bytes) {
+                Ok(10) => msg.number = r.read_string(bytes).map(Cow::Borrowed)?,
+                Ok(16) => msg.kind = Some(r.read_enum(bytes)?),
+                Ok(t) => { r.read_unknown(bytes, t)?; }
+                Err(e) => return Err(e),
+            }
+        }
+        Ok(msg)
+    }
+}
+
+impl<'a> MessageWrite for PhoneNumber<'a> {
+    fn get_size(&self) -> usize {
+        0
+        + 1 + sizeof_len((&self.number).len())
+        + self.kind.as_ref().map_or(0, |m| 1 + sizeof_varint(*(m) as u64))
+    }
+
+    fn write_message<W: WriterBackend>(&self, w: &mut Writer<W>) -> Result<()> {
+        w.write_with_tag(10, |w| w.write_string(&**&self.number))?;
+        if let Some(ref s) = self.kind { w.write_with_tag(16, |w| w.write_enum(*s as i32))?; }
+        Ok(())
+    }
+}
+
+pub mod mod_PhoneNumber {
+
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy, IntoOwned, Borrowed)]
+pub enum PhoneType {
+    MOBILE = 0,
+    HOME = 1,
+}
+
+impl Default for PhoneType {
+    fn default() -> Self {
+        PhoneType::MOBILE
+    }
+}
+
+impl From<i32> for PhoneType {
+    fn from(i: i32) -> Self {
+        match i {
+            0 => PhoneType::MOBILE,
+            1 => PhoneType::HOME,
+            _ => Self::default(),
+        }
+    }
+}
+
+}
+
+#[derive(Debug, PartialEq, Clone, IntoOwned, Borrowed)]
+pub enum OneOfcontact<'a> {
+    slack(Cow<'a, str>),
+    extension(i32),
+    unreachable(bool),
+    None,
+}
+
+impl<'a> Default for OneOfcontact<'a> {
+    fn default() -> Self {
+        OneOfcontact::None
+    }
+}
+
+}
+
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Debug, Default, PartialEq, Clone, IntoOwned, Borrowed)]
+pub struct Address {
+    pub zip: i32,
+}
+
+impl<'a> MessageRead<'a> for Address {
+    fn from_reader(r: &mut BytesReader, bytes: &'a [u8]) -> Result<Self> {
+        let mut msg = Self::default();
+        while !r.is_eof() {
+            match r.next_tag(bytes) {
+                Ok(8) => msg.zip = r.read_int32(bytes)?,
+                Ok(t) => { r.read_unknown(bytes, t)?; }
+                Err(e) => return Err(e),
+            }
+        }
+        Ok(msg)
+    }
+}
+
+impl MessageWrite for Address {
+    fn get_size(&self) -> usize {
+        0
+        + 1 + sizeof_varint(*(&self.zip) as u64)
+    }
+
+    fn write_message<W: WriterBackend>(&self, w: &mut Writer<W>) -> Result<()> {
+        w.write_with_tag(8, |w| w.write_int32(*&self.zip))?;
+        Ok(())
+    }
+}
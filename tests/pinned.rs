@@ -0,0 +1,62 @@
+// the `Cow` is pinned to test the leaves as well
+#![allow(clippy::box_collection)]
+
+#[macro_use]
+extern crate derive_into_owned;
+
+use std::borrow::Cow;
+use std::pin::Pin;
+
+#[derive(IntoOwned, Borrowed, Debug, PartialEq)]
+#[into_owned(mirror(derive(Debug)), ownership)]
+struct Node<'a> {
+    name: Cow<'a, str>,
+    #[into_owned(expect = "option(pin(box(cow_alike)))")]
+    next: Option<Pin<Box<Node<'a>>>>,
+    label: std::pin::Pin<Box<Cow<'a, str>>>,
+}
+
+fn node<'a>(name: &'a str, next: Option<Node<'a>>) -> Node<'a> {
+    Node {
+        name: Cow::Borrowed(name),
+        next: next.map(Box::pin),
+        label: Box::pin(Cow::Borrowed(&name[..1])),
+    }
+}
+
+#[test]
+fn pinned_boxes() {
+    let text = String::from("head tail");
+    let list = node(&text[..4], Some(node(&text[5..], None)));
+
+    let borrowed = list.borrowed();
+    assert!(matches!(
+        borrowed.next.as_ref().unwrap().name,
+        Cow::Borrowed("tail")
+    ));
+    assert_eq!(borrowed, list);
+    assert_eq!(
+        list.ownership(),
+        NodeOwnership {
+            name: false,
+            label: false
+        }
+    );
+
+    let owned: Node<'static> = list.into_owned();
+    drop(text);
+
+    assert_eq!(
+        owned.ownership(),
+        NodeOwnership {
+            name: true,
+            label: true
+        }
+    );
+    assert!(matches!(owned.next.as_ref().unwrap().name, Cow::Owned(ref s) if s == "tail"));
+    assert!(matches!(*owned.label, Cow::Owned(ref s) if s == "h"));
+
+    let mirror = NodeOwned::from(owned);
+    assert_eq!(mirror.next.unwrap().name, "tail");
+    assert_eq!(*mirror.label, "h");
+}
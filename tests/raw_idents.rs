@@ -0,0 +1,80 @@
+// generated code keeps the names of the schema
+#![allow(non_camel_case_types)]
+
+#[macro_use]
+extern crate derive_into_owned;
+
+use std::borrow::Cow;
+
+#[derive(IntoOwned, Borrowed, Debug, PartialEq)]
+#[into_owned(field_info)]
+#[borrowed(view)]
+struct Field<'a> {
+    r#type: Cow<'a, str>,
+    r#default: Option<Cow<'a, str>>,
+    r#ref: u32,
+}
+
+#[derive(IntoOwned, Borrowed, Debug, PartialEq)]
+enum Token<'a> {
+    r#Type { r#type: Cow<'a, str> },
+    r#fn(Cow<'a, str>),
+}
+
+#[derive(IntoOwned)]
+#[into_owned(mirror)]
+struct Attribute<'a> {
+    r#type: &'a str,
+    r#in: Cow<'a, str>,
+}
+
+#[test]
+fn raw_field_names() {
+    let field = Field {
+        r#type: Cow::Borrowed("string"),
+        r#default: Some(Cow::Borrowed("\"\"")),
+        r#ref: 1,
+    };
+
+    assert_eq!(field.borrowed(), field);
+    let view = field.view();
+    assert_eq!(view.r#type, "string");
+    assert_eq!(view.r#default.as_deref(), Some("\"\""));
+    assert_eq!(*view.r#ref, 1);
+    assert_eq!(Field::BORROW_FIELDS[0].name, "type");
+    assert_eq!(Field::BORROW_FIELDS[1].name, "default");
+
+    let owned: Field<'static> = field.into_owned();
+    assert!(matches!(owned.r#type, Cow::Owned(_)));
+    assert_eq!(owned.r#ref, 1);
+}
+
+#[test]
+fn raw_variant_names() {
+    let token = Token::r#Type {
+        r#type: Cow::Borrowed("u8"),
+    };
+    assert_eq!(token.borrowed(), token);
+
+    let owned: Token<'static> = token.into_owned();
+    assert_eq!(
+        owned,
+        Token::r#Type {
+            r#type: Cow::Owned("u8".to_string())
+        }
+    );
+    let owned: Token<'static> = Token::r#fn(Cow::Borrowed("main")).into_owned();
+    assert_eq!(owned, Token::r#fn(Cow::Owned("main".to_string())));
+}
+
+#[test]
+fn raw_mirror_fields() {
+    let attribute = Attribute {
+        r#type: "derive",
+        r#in: Cow::Borrowed("crate"),
+    };
+
+    let owned = AttributeOwned::from(attribute);
+    assert_eq!(owned.r#type, "derive");
+    assert_eq!(owned.r#in, "crate");
+}
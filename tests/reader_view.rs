@@ -0,0 +1,58 @@
+//! Owning the values of a reader which only has accessor methods, like the tables generated by
+//! `flatbuffers`, through a view struct and its mirror type.
+
+#[macro_use]
+extern crate derive_into_owned;
+
+/// Stand-in for a generated table reader decoding `name\0inventory` on access.
+struct Monster<'a> {
+    buf: &'a [u8],
+}
+
+impl<'a> Monster<'a> {
+    fn name(&self) -> &'a str {
+        let end = self
+            .buf
+            .iter()
+            .position(|&b| b == 0)
+            .unwrap_or(self.buf.len());
+        std::str::from_utf8(&self.buf[..end]).unwrap()
+    }
+
+    fn inventory(&self) -> Option<&'a [u8]> {
+        let end = self.buf.iter().position(|&b| b == 0)?;
+        Some(&self.buf[end + 1..])
+    }
+}
+
+#[derive(IntoOwned, Debug)]
+#[into_owned(mirror(derive(Debug, PartialEq)))]
+struct MonsterView<'a> {
+    name: &'a str,
+    inventory: Option<&'a [u8]>,
+}
+
+impl<'a> From<&Monster<'a>> for MonsterView<'a> {
+    fn from(monster: &Monster<'a>) -> Self {
+        MonsterView {
+            name: monster.name(),
+            inventory: monster.inventory(),
+        }
+    }
+}
+
+#[test]
+fn owned_reader_values() {
+    let buf = b"orc\0\x01\x02".to_vec();
+
+    let owned = MonsterViewOwned::from(MonsterView::from(&Monster { buf: &buf }));
+    drop(buf);
+
+    assert_eq!(
+        owned,
+        MonsterViewOwned {
+            name: String::from("orc"),
+            inventory: Some(vec![1, 2]),
+        }
+    );
+}
@@ -0,0 +1,66 @@
+// generated parsers box the nodes of a tree also in vectors
+#![allow(clippy::vec_box)]
+
+#[macro_use]
+extern crate derive_into_owned;
+
+use std::borrow::Cow;
+
+#[derive(IntoOwned, Borrowed, Debug, PartialEq)]
+enum Expr<'a> {
+    Ident(Cow<'a, str>),
+    Neg(#[into_owned(expect = "box(cow_alike)")] Box<Expr<'a>>),
+    Call {
+        name: Cow<'a, str>,
+        #[into_owned(expect = "iterable(box(cow_alike))")]
+        args: Vec<Box<Expr<'a>>>,
+    },
+}
+
+#[derive(IntoOwned, Borrowed, Debug, PartialEq)]
+struct Statement<'a> {
+    expr: Expr<'a>,
+    #[into_owned(expect = "option(box(cow_alike))")]
+    next: Option<Box<Statement<'a>>>,
+}
+
+fn ident(name: &str) -> Box<Expr<'_>> {
+    Box::new(Expr::Ident(Cow::Borrowed(name)))
+}
+
+#[test]
+fn recursive_boxes() {
+    let source = String::from("f x y");
+    let mut words = source.split(' ');
+    let (name, x, y) = (
+        words.next().unwrap(),
+        words.next().unwrap(),
+        words.next().unwrap(),
+    );
+
+    let statement = Statement {
+        expr: Expr::Call {
+            name: Cow::Borrowed(name),
+            args: vec![ident(x), Box::new(Expr::Neg(ident(y)))],
+        },
+        next: Some(Box::new(Statement {
+            expr: *ident(x),
+            next: None,
+        })),
+    };
+
+    let borrowed = statement.borrowed();
+    assert_eq!(borrowed, statement);
+
+    let owned: Statement<'static> = statement.into_owned();
+    drop(source);
+
+    match owned.expr {
+        Expr::Call { ref name, ref args } => {
+            assert!(matches!(*name, Cow::Owned(ref name) if name == "f"));
+            assert!(matches!(*args[1], Expr::Neg(ref y) if **y == Expr::Ident("y".into())));
+        }
+        _ => panic!("expected a call"),
+    }
+    assert_eq!(owned.next.unwrap().expr, Expr::Ident("x".into()));
+}
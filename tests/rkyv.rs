@@ -0,0 +1,42 @@
+#![allow(dead_code)]
+
+#[macro_use]
+extern crate derive_into_owned;
+
+use std::borrow::Cow;
+
+#[derive(IntoOwned)]
+#[into_owned(mirror(
+    derive(rkyv::Archive, rkyv::Serialize, rkyv::Deserialize, Debug, PartialEq),
+    attr(archive(check_bytes)),
+    attr(archive_attr(derive(Debug)))
+))]
+struct Record<'a> {
+    key: Cow<'a, str>,
+    value: Cow<'a, [u8]>,
+    tags: Vec<Cow<'a, str>>,
+    version: u64,
+}
+
+#[test]
+fn archive_owned_mirror() {
+    let input = b"key=value".to_vec();
+
+    let record = Record {
+        key: Cow::Borrowed(std::str::from_utf8(&input[..3]).unwrap()),
+        value: Cow::Borrowed(&input[4..]),
+        tags: vec![Cow::Borrowed("a"), Cow::Borrowed("b")],
+        version: 7,
+    };
+
+    let owned = RecordOwned::from(record);
+    drop(input);
+
+    let bytes = rkyv::to_bytes::<_, 256>(&owned).unwrap();
+    let archived = rkyv::check_archived_root::<RecordOwned>(&bytes[..]).unwrap();
+
+    assert_eq!(archived.key, "key");
+    assert_eq!(&archived.value[..], b"value");
+    assert_eq!(archived.tags.len(), 2);
+    assert_eq!(archived.version, 7);
+}
@@ -0,0 +1,39 @@
+#![allow(dead_code)]
+
+#[macro_use]
+extern crate derive_into_owned;
+
+use std::borrow::Cow;
+use std::collections::HashMap;
+use std::sync::Arc;
+
+#[derive(IntoOwned)]
+#[into_owned(assert_send_sync)]
+struct Request<'a, T: Clone> {
+    path: Cow<'a, str>,
+    headers: HashMap<Cow<'a, str>, Vec<Cow<'a, [u8]>>>,
+    shared: Arc<str>,
+    extra: T,
+}
+
+#[derive(IntoOwned)]
+#[into_owned(assert_send_sync)]
+enum Event<'a> {
+    Line(Cow<'a, str>),
+    Closed,
+}
+
+#[tokio::test]
+async fn crosses_spawn_boundary() {
+    let path = String::from("/index.html");
+    let request = Request {
+        path: Cow::Borrowed(&path),
+        headers: HashMap::new(),
+        shared: Arc::from("shared"),
+        extra: 1u8,
+    }
+    .into_owned();
+
+    let handle = tokio::spawn(async move { request.path.len() });
+    assert_eq!(handle.await.unwrap(), 11);
+}
@@ -0,0 +1,96 @@
+#![allow(dead_code)]
+
+#[macro_use]
+extern crate derive_into_owned;
+
+use serde::Deserialize;
+use std::borrow::Cow;
+
+#[derive(Deserialize, IntoOwned, Borrowed, Debug, PartialEq)]
+struct Message<'a> {
+    #[serde(borrow)]
+    topic: Cow<'a, str>,
+    #[serde(borrow, rename = "payload")]
+    body: Option<Cow<'a, str>>,
+    #[serde(borrow)]
+    headers: Vec<Header<'a>>,
+    #[serde(default)]
+    retries: u32,
+}
+
+#[derive(Deserialize, IntoOwned, Borrowed, Debug, PartialEq)]
+struct Header<'a> {
+    #[serde(borrow)]
+    name: Cow<'a, str>,
+    #[serde(borrow)]
+    value: Cow<'a, str>,
+}
+
+#[derive(Deserialize, IntoOwned, Borrowed, Debug, PartialEq)]
+#[serde(tag = "type", rename_all = "lowercase")]
+enum Event<'a> {
+    Published {
+        #[serde(borrow)]
+        message: Message<'a>,
+    },
+    Dropped {
+        #[serde(borrow)]
+        reason: Cow<'a, str>,
+    },
+}
+
+const INPUT: &str = r#"{
+    "topic": "news",
+    "payload": "hello",
+    "headers": [{ "name": "content-type", "value": "text/plain" }]
+}"#;
+
+#[test]
+fn deserialize_zero_copy_then_own() {
+    let input = INPUT.to_string();
+
+    let message: Message<'_> = serde_json::from_str(&input).unwrap();
+    assert!(matches!(message.topic, Cow::Borrowed(_)));
+    assert!(matches!(message.headers[0].name, Cow::Borrowed(_)));
+
+    let owned = accepts_only_static(message.into_owned());
+    drop(input);
+
+    assert!(matches!(owned.topic, Cow::Owned(_)));
+    assert_eq!(owned.body.as_deref(), Some("hello"));
+    assert_eq!(owned.headers[0].value, "text/plain");
+    assert_eq!(owned.retries, 0);
+}
+
+#[test]
+fn deserialize_tagged_enum_then_own() {
+    let input = r#"{ "type": "dropped", "reason": "queue full" }"#.to_string();
+
+    let event: Event<'_> = serde_json::from_str(&input).unwrap();
+    let owned = accepts_only_static(event.into_owned());
+    drop(input);
+
+    assert_eq!(
+        owned,
+        Event::Dropped {
+            reason: Cow::Borrowed("queue full")
+        }
+    );
+}
+
+#[test]
+fn borrowed_after_deserialize() {
+    let input = INPUT.to_string();
+
+    let owned = serde_json::from_str::<Message<'_>>(&input)
+        .unwrap()
+        .into_owned();
+    let borrowed = owned.borrowed();
+
+    assert!(matches!(borrowed.topic, Cow::Borrowed("news")));
+    assert_eq!(borrowed, owned);
+}
+
+fn accepts_only_static<T: 'static>(anything: T) -> T {
+    anything
+}
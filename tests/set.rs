@@ -0,0 +1,109 @@
+#[macro_use]
+extern crate derive_into_owned;
+
+use std::borrow::Cow;
+use std::collections::{BTreeSet, BinaryHeap, HashSet};
+
+#[derive(IntoOwned, Borrowed, Debug, PartialEq)]
+#[into_owned(mirror(derive(Debug), partial_eq), ownership)]
+struct Tags<'a> {
+    #[into_owned(expect = "set(cow)")]
+    names: HashSet<Cow<'a, str>>,
+    #[into_owned(expect = "set(cow)")]
+    sorted: BTreeSet<Cow<'a, [u8]>>,
+    ids: HashSet<u32>,
+}
+
+#[test]
+fn sets() {
+    let text = String::from("alpha beta");
+
+    let tags = Tags {
+        names: text.split(' ').map(Cow::Borrowed).collect(),
+        sorted: vec![Cow::Borrowed(&text.as_bytes()[6..])]
+            .into_iter()
+            .collect(),
+        ids: vec![1, 2].into_iter().collect(),
+    };
+
+    let borrowed = tags.borrowed();
+    assert!(borrowed
+        .names
+        .iter()
+        .all(|name| matches!(name, Cow::Borrowed(_))));
+    assert_eq!(borrowed, tags);
+    assert_eq!(
+        tags.ownership(),
+        TagsOwnership {
+            names: false,
+            sorted: false
+        }
+    );
+
+    let owned: Tags<'static> = tags.into_owned();
+    drop(text);
+
+    assert!(owned.names.iter().all(|name| matches!(name, Cow::Owned(_))));
+    assert!(owned.names.contains("beta"));
+    assert!(owned.sorted.contains(&b"beta"[..]));
+
+    let mirror = TagsOwned::from(owned.borrowed());
+    assert_eq!(owned, mirror);
+    assert!(mirror.names.contains("alpha"));
+    assert_eq!(mirror.ids.len(), 2);
+}
+
+#[derive(IntoOwned, Borrowed, Debug, PartialEq)]
+struct HashedTags<'a> {
+    names: HashSet<
+        Cow<'a, str>,
+        std::hash::BuildHasherDefault<std::collections::hash_map::DefaultHasher>,
+    >,
+}
+
+#[test]
+fn custom_hasher() {
+    let text = String::from("name");
+
+    let mut names = HashSet::default();
+    names.insert(Cow::Borrowed(&text[..]));
+    let tags = HashedTags { names };
+    assert_eq!(tags.borrowed(), tags);
+
+    let owned: HashedTags<'static> = tags.into_owned();
+    drop(text);
+    assert!(owned
+        .names
+        .iter()
+        .all(|name| matches!(name, Cow::Owned(ref s) if s == "name")));
+}
+
+#[derive(IntoOwned, Borrowed, Debug)]
+#[into_owned(mirror(derive(Debug)))]
+struct Queue<'a> {
+    #[into_owned(expect = "set(cow)")]
+    pending: BinaryHeap<Cow<'a, str>>,
+}
+
+#[test]
+fn binary_heaps() {
+    let text = String::from("b c a");
+
+    let queue = Queue {
+        pending: text.split(' ').map(Cow::Borrowed).collect(),
+    };
+    assert!(matches!(
+        queue.borrowed().pending.peek(),
+        Some(Cow::Borrowed("c"))
+    ));
+
+    let owned: Queue<'static> = queue.into_owned();
+    drop(text);
+
+    assert!(owned
+        .pending
+        .iter()
+        .all(|item| matches!(item, Cow::Owned(_))));
+    let mirror = QueueOwned::from(owned);
+    assert_eq!(mirror.pending.into_sorted_vec(), ["a", "b", "c"]);
+}
@@ -0,0 +1,67 @@
+#[macro_use]
+extern crate derive_into_owned;
+
+use std::borrow::Cow;
+
+#[derive(IntoOwned, Debug, PartialEq)]
+#[into_owned(shrink)]
+struct Message<'a> {
+    subject: Cow<'a, str>,
+    body: Option<Cow<'a, [u8]>>,
+    headers: Vec<Header<'a>>,
+}
+
+#[derive(IntoOwned, Debug, PartialEq)]
+#[into_owned(shrink)]
+struct Header<'a> {
+    name: Cow<'a, str>,
+    values: Vec<Cow<'a, str>>,
+}
+
+fn over_allocated(s: &str) -> String {
+    let mut buffer = String::with_capacity(1024);
+    buffer.push_str(s);
+    buffer
+}
+
+#[test]
+fn owned_values_are_shrunk() {
+    let mut values = Vec::with_capacity(64);
+    values.push(Cow::Owned(over_allocated("text/plain")));
+
+    let mut body = Vec::with_capacity(4096);
+    body.extend_from_slice(b"hello");
+
+    let message = Message {
+        subject: Cow::Owned(over_allocated("greetings")),
+        body: Some(Cow::Owned(body)),
+        headers: vec![Header {
+            name: Cow::Borrowed("content-type"),
+            values,
+        }],
+    };
+
+    let owned: Message<'static> = message.into_owned_and_shrink();
+
+    assert_eq!(owned.subject.into_owned().capacity(), "greetings".len());
+    assert_eq!(owned.body.unwrap().into_owned().capacity(), b"hello".len());
+
+    let header = &owned.headers[0];
+    assert_eq!(header.name, "content-type");
+    assert_eq!(header.values.capacity(), 1);
+    match header.values[0] {
+        Cow::Owned(ref value) => assert_eq!(value.capacity(), "text/plain".len()),
+        Cow::Borrowed(_) => panic!("expected an owned value"),
+    }
+}
+
+#[test]
+fn into_owned_keeps_the_capacity() {
+    let message = Message {
+        subject: Cow::Owned(over_allocated("greetings")),
+        body: None,
+        headers: Vec::new(),
+    };
+
+    assert_eq!(message.into_owned().subject.into_owned().capacity(), 1024);
+}
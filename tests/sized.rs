@@ -0,0 +1,83 @@
+#[macro_use]
+extern crate derive_into_owned;
+
+use std::borrow::Cow;
+use std::path::Path;
+
+#[derive(IntoOwned, Borrowed)]
+struct Where<'a, T>
+where
+    T: ?Sized + ToOwned + 'a,
+{
+    value: Cow<'a, T>,
+    values: Vec<Cow<'a, T>>,
+}
+
+/// `Borrowed` would need `Box<T>: Clone`, which unsized `T` cannot have.
+#[derive(IntoOwned, Debug)]
+struct Boxed<'a, T: ?Sized> {
+    name: Cow<'a, str>,
+    value: Box<T>,
+}
+
+#[derive(IntoOwned, Borrowed)]
+enum Either<'a, L, R>
+where
+    L: ?Sized + ToOwned + 'a,
+    R: ?Sized + ToOwned + 'a,
+{
+    Left(Cow<'a, L>),
+    Right { value: Cow<'a, R> },
+}
+
+fn accepts_only_static<T: ?Sized + ToOwned + 'static>(_: Where<'static, T>) {}
+
+#[test]
+fn unsized_parameters_in_where_clause() {
+    let path = String::from("/tmp");
+
+    let value = Where {
+        value: Cow::Borrowed(Path::new(&path)),
+        values: vec![Cow::Borrowed(Path::new(&path))],
+    };
+    assert!(matches!(value.borrowed().value, Cow::Borrowed(_)));
+
+    let value = value.into_owned();
+    drop(path);
+    assert_eq!(value.value, Path::new("/tmp"));
+    accepts_only_static(value);
+}
+
+#[test]
+fn boxed_unsized() {
+    let name = String::from("name");
+
+    let value: Boxed<'_, [u8]> = Boxed {
+        name: Cow::Borrowed(&name),
+        value: vec![1, 2].into_boxed_slice(),
+    };
+    let value: Boxed<'static, [u8]> = value.into_owned();
+    drop(name);
+    assert_eq!(value.name, "name");
+}
+
+#[test]
+fn unsized_enum_parameters() {
+    let text = String::from("text");
+
+    let either: Either<'_, str, [u8]> = Either::Left(Cow::Borrowed(&text));
+    let either: Either<'static, str, [u8]> = either.into_owned();
+    drop(text);
+
+    assert!(matches!(either, Either::Left(Cow::Owned(ref s)) if s == "text"));
+
+    let either: Either<'_, str, [u8]> = Either::Right {
+        value: Cow::Borrowed(&[1]),
+    };
+    assert!(matches!(
+        either.into_owned(),
+        Either::Right {
+            value: Cow::Owned(_)
+        }
+    ));
+}
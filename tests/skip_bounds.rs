@@ -0,0 +1,79 @@
+#[macro_use]
+extern crate derive_into_owned;
+
+use std::borrow::Cow;
+use std::marker::PhantomData;
+
+/// Gets `T: Clone` on `borrowed` without declaring it.
+#[derive(IntoOwned, Borrowed, Debug, PartialEq)]
+struct Labeled<'a, T> {
+    label: Cow<'a, str>,
+    values: Vec<T>,
+}
+
+/// `PhantomData<T>` is `Clone` for any `T`.
+#[derive(IntoOwned, Borrowed, Debug)]
+#[into_owned(skip_bounds)]
+struct Tagged<'a, T> {
+    name: Cow<'a, str>,
+    tag: PhantomData<T>,
+}
+
+#[derive(IntoOwned, Borrowed, Debug)]
+enum Typed<'a, #[into_owned(skip_bounds)] U, T> {
+    Unit {
+        name: Cow<'a, str>,
+        unit: PhantomData<U>,
+    },
+    Value(Cow<'a, str>, Option<T>),
+}
+
+/// Neither `Clone` nor `Debug`.
+struct Meters;
+
+#[test]
+fn adds_clone_bounds() {
+    let label = String::from("label");
+    let labeled = Labeled {
+        label: Cow::Borrowed(&label),
+        values: vec![1, 2],
+    };
+
+    let borrowed = labeled.borrowed();
+    assert!(matches!(borrowed.label, Cow::Borrowed("label")));
+    assert_eq!(borrowed.values, [1, 2]);
+
+    let labeled = labeled.into_owned();
+    drop(label);
+    assert_eq!(labeled.label, "label");
+}
+
+#[test]
+fn skips_bounds() {
+    let name = String::from("name");
+
+    let tagged: Tagged<'_, Meters> = Tagged {
+        name: Cow::Owned(name.clone()),
+        tag: PhantomData,
+    };
+    assert!(matches!(tagged.borrowed().name, Cow::Borrowed("name")));
+
+    let typed: Typed<'_, Meters, u8> = Typed::Unit {
+        name: Cow::Borrowed(&name),
+        unit: PhantomData,
+    };
+    assert!(matches!(
+        typed.borrowed(),
+        Typed::Unit {
+            name: Cow::Borrowed("name"),
+            ..
+        }
+    ));
+
+    let typed: Typed<'static, Meters, u8> = typed.into_owned();
+    drop(name);
+    assert!(matches!(typed, Typed::Unit { .. }));
+
+    let typed: Typed<'_, Meters, u8> = Typed::Value(Cow::Borrowed("value"), Some(1));
+    assert!(matches!(typed.borrowed(), Typed::Value(_, Some(1))));
+}
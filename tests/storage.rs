@@ -0,0 +1,115 @@
+//! Types generic over how they store their data, converted with `with` and `bound`.
+
+#[macro_use]
+extern crate derive_into_owned;
+
+use std::borrow::Cow;
+
+pub trait Storage {
+    type Str<'a>;
+    type Bytes<'a>;
+}
+
+/// Conversion of the stored values into `'static` ones, kept apart from `Storage` so that
+/// storages which cannot be converted can still be used for parsing.
+pub trait IntoOwnedStorage: Storage {
+    fn owned_str(s: Self::Str<'_>) -> Self::Str<'static>;
+    fn owned_bytes(b: Self::Bytes<'_>) -> Self::Bytes<'static>;
+}
+
+pub struct Borrowing;
+
+impl Storage for Borrowing {
+    type Str<'a> = Cow<'a, str>;
+    type Bytes<'a> = Cow<'a, [u8]>;
+}
+
+impl IntoOwnedStorage for Borrowing {
+    fn owned_str(s: Cow<'_, str>) -> Cow<'static, str> {
+        Cow::Owned(s.into_owned())
+    }
+    fn owned_bytes(b: Cow<'_, [u8]>) -> Cow<'static, [u8]> {
+        Cow::Owned(b.into_owned())
+    }
+}
+
+pub struct Owning;
+
+impl Storage for Owning {
+    type Str<'a> = String;
+    type Bytes<'a> = Vec<u8>;
+}
+
+impl IntoOwnedStorage for Owning {
+    fn owned_str(s: String) -> String {
+        s
+    }
+    fn owned_bytes(b: Vec<u8>) -> Vec<u8> {
+        b
+    }
+}
+
+#[derive(IntoOwned)]
+#[into_owned(strict, bound = "S: IntoOwnedStorage")]
+struct Msg<'a, S: Storage> {
+    #[into_owned(with = "S::owned_str")]
+    topic: S::Str<'a>,
+    #[into_owned(with = "S::owned_bytes")]
+    body: S::Bytes<'a>,
+    fallback: Cow<'a, str>,
+    id: u32,
+}
+
+#[test]
+fn converts_through_the_storage() {
+    let data = String::from("data");
+
+    let msg: Msg<'_, Borrowing> = Msg {
+        topic: Cow::Borrowed(&data),
+        body: Cow::Borrowed(data.as_bytes()),
+        fallback: Cow::Borrowed(&data),
+        id: 1,
+    };
+    let msg: Msg<'static, Borrowing> = msg.into_owned();
+    drop(data);
+    assert_eq!(msg.topic, "data");
+    assert_eq!(msg.body, &b"data"[..]);
+    assert_eq!(msg.fallback, "data");
+
+    let msg: Msg<'_, Owning> = Msg {
+        topic: String::from("t"),
+        body: vec![1],
+        fallback: Cow::Borrowed("f"),
+        id: 2,
+    };
+    let msg = msg.into_owned();
+    assert_eq!(msg.topic, "t");
+    assert_eq!(msg.id, 2);
+}
+
+#[derive(IntoOwned, Debug, PartialEq)]
+enum Token<'a> {
+    Word {
+        #[into_owned(with = "trimmed")]
+        text: Cow<'a, str>,
+    },
+    Number(u64),
+}
+
+fn trimmed(s: Cow<'_, str>) -> Cow<'static, str> {
+    Cow::Owned(s.trim().to_owned())
+}
+
+#[test]
+fn converts_with_functions() {
+    let word = Token::Word {
+        text: Cow::Borrowed(" word "),
+    };
+    assert_eq!(
+        word.into_owned(),
+        Token::Word {
+            text: Cow::Borrowed("word")
+        }
+    );
+    assert_eq!(Token::Number(1).into_owned(), Token::Number(1));
+}
@@ -0,0 +1,52 @@
+#![allow(dead_code)]
+
+#[macro_use]
+extern crate derive_into_owned;
+
+use std::borrow::Cow;
+use std::error::Error;
+use std::fmt;
+
+#[derive(Debug)]
+struct ParseError<'a> {
+    input: &'a str,
+}
+
+impl fmt::Display for ParseError<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "cannot parse {:?}", self.input)
+    }
+}
+
+impl Error for ParseError<'_> {}
+
+#[derive(IntoOwned, Debug)]
+struct Failure<'a> {
+    context: Cow<'a, str>,
+    #[into_owned(stringify)]
+    source: Box<dyn Error + Send + Sync + 'a>,
+    #[into_owned(with = "as_cause")]
+    cause: Option<Box<dyn Error + 'a>>,
+}
+
+fn as_cause(error: Option<Box<dyn Error + '_>>) -> Option<Box<dyn Error + 'static>> {
+    error.map(|error| error.to_string().into())
+}
+
+#[test]
+fn errors_become_static() {
+    let input = String::from("12x");
+
+    let failure = Failure {
+        context: Cow::Borrowed("reading the count"),
+        source: Box::new(ParseError { input: &input }),
+        cause: Some(Box::new(ParseError { input: &input[2..] })),
+    };
+
+    let owned: Failure<'static> = failure.into_owned();
+    drop(input);
+
+    assert_eq!(owned.context, "reading the count");
+    assert_eq!(owned.source.to_string(), "cannot parse \"12x\"");
+    assert_eq!(owned.cause.unwrap().to_string(), "cannot parse \"x\"");
+}
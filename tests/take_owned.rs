@@ -0,0 +1,61 @@
+#[macro_use]
+extern crate derive_into_owned;
+
+use std::borrow::Cow;
+
+#[derive(IntoOwned, Debug, Default, PartialEq)]
+#[into_owned(take_owned)]
+struct Buffer<'a> {
+    lines: Vec<Cow<'a, str>>,
+    count: usize,
+}
+
+#[derive(IntoOwned, Debug, Default, PartialEq)]
+#[into_owned(take_owned)]
+enum Slot<'a> {
+    Full(Cow<'a, str>),
+    #[default]
+    Empty,
+}
+
+#[test]
+fn takes_and_leaves_default() {
+    let inputs = vec![String::from("a b"), String::from("c")];
+    let mut owned: Vec<Buffer<'static>> = Vec::new();
+    let mut buffer = Buffer::default();
+
+    for input in &inputs {
+        buffer.lines.extend(input.split(' ').map(Cow::Borrowed));
+        buffer.count += 1;
+
+        owned.push(buffer.take_owned());
+        assert_eq!(buffer, Buffer::default());
+    }
+    drop(inputs);
+
+    assert_eq!(
+        owned,
+        [
+            Buffer {
+                lines: vec![Cow::Owned(String::from("a")), Cow::Owned(String::from("b"))],
+                count: 1,
+            },
+            Buffer {
+                lines: vec![Cow::Owned(String::from("c"))],
+                count: 1,
+            },
+        ]
+    );
+}
+
+#[test]
+fn enum_default() {
+    let input = String::from("value");
+    let mut slot = Slot::Full(Cow::Borrowed(&input));
+
+    let taken: Slot<'static> = slot.take_owned();
+    assert_eq!(slot, Slot::Empty);
+    drop(input);
+
+    assert_eq!(taken, Slot::Full(Cow::Owned(String::from("value"))));
+}
@@ -0,0 +1,106 @@
+//! Error enums deriving both `thiserror::Error` and `IntoOwned`.
+
+#![deny(warnings)]
+
+#[macro_use]
+extern crate derive_into_owned;
+
+use std::borrow::Cow;
+use std::error::Error;
+
+type BoxError<'a> = Box<dyn Error + Send + Sync + 'a>;
+
+#[derive(Debug, thiserror::Error, IntoOwned)]
+enum ParseError<'a> {
+    #[error("unexpected `{token}` at {offset}")]
+    Unexpected { token: Cow<'a, str>, offset: usize },
+    #[error("cannot read {path}")]
+    Io {
+        path: Cow<'a, str>,
+        #[source]
+        source: std::io::Error,
+    },
+    #[error("invalid value `{0}`")]
+    Invalid(Cow<'a, str>, #[source] Box<dyn Error + Send + Sync>),
+    #[error("nested: {0}")]
+    Nested(Box<ParseError<'a>>),
+    #[error("custom: {message}: {cause}")]
+    Custom {
+        message: Cow<'a, str>,
+        #[into_owned(with = "detach")]
+        cause: BoxError<'a>,
+    },
+    #[error("context was dropped")]
+    Context {
+        #[into_owned(skip)]
+        context: Option<&'a str>,
+    },
+}
+
+/// Keeps the message of an error which borrows.
+fn detach(error: BoxError<'_>) -> BoxError<'static> {
+    error.to_string().into()
+}
+
+#[derive(Debug, thiserror::Error)]
+#[error("borrowing {0}")]
+struct Borrowing<'a>(&'a str);
+
+#[test]
+fn errors_cross_threads() {
+    let input = String::from("let x = ;");
+
+    let errors = vec![
+        ParseError::Unexpected {
+            token: Cow::Borrowed(&input[8..9]),
+            offset: 8,
+        },
+        ParseError::Io {
+            path: Cow::Borrowed(&input[4..5]),
+            source: std::io::Error::other("gone"),
+        },
+        ParseError::Invalid(Cow::Borrowed(&input[..3]), "not a number".into()),
+        ParseError::Nested(Box::new(ParseError::Unexpected {
+            token: Cow::Borrowed(&input[..1]),
+            offset: 0,
+        })),
+        ParseError::Custom {
+            message: Cow::Borrowed(&input[..3]),
+            cause: Box::new(Borrowing(&input[4..5])),
+        },
+        ParseError::Context {
+            context: Some(&input),
+        },
+    ]
+    .into_iter()
+    .map(ParseError::into_owned)
+    .collect::<Vec<_>>();
+    drop(input);
+
+    let messages = std::thread::spawn(move || {
+        errors
+            .iter()
+            .map(|error| {
+                let source = error.source().map(|source| source.to_string());
+                (error.to_string(), source)
+            })
+            .collect::<Vec<_>>()
+    })
+    .join()
+    .unwrap();
+
+    assert_eq!(
+        messages,
+        [
+            (String::from("unexpected `;` at 8"), None),
+            (String::from("cannot read x"), Some(String::from("gone"))),
+            (
+                String::from("invalid value `let`"),
+                Some(String::from("not a number"))
+            ),
+            (String::from("nested: unexpected `l` at 0"), None),
+            (String::from("custom: let: borrowing x"), None),
+            (String::from("context was dropped"), None),
+        ]
+    );
+}
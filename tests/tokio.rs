@@ -0,0 +1,54 @@
+#![cfg(feature = "tokio")]
+
+#[macro_use]
+extern crate derive_into_owned;
+
+use std::borrow::Cow;
+
+#[derive(IntoOwned)]
+#[into_owned(blocking)]
+struct Document<'a> {
+    title: Cow<'a, str>,
+    body: Vec<Cow<'a, [u8]>>,
+}
+
+#[derive(IntoOwned)]
+#[into_owned(free_fn, blocking)]
+struct Page<'a, T> {
+    text: Cow<'a, str>,
+    number: T,
+}
+
+#[tokio::test]
+async fn into_owned_blocking() {
+    // spawn_blocking needs the borrowed value to be 'static as well
+    let source: &'static [u8] = Box::leak(vec![b'x'; 4 * 1024 * 1024].into_boxed_slice());
+
+    let document = Document {
+        title: Cow::Borrowed("scraped"),
+        body: source.chunks(1024).map(Cow::Borrowed).collect(),
+    };
+
+    let document: Document<'static> = document.into_owned_blocking().await;
+
+    assert!(document
+        .body
+        .iter()
+        .all(|chunk| matches!(chunk, Cow::Owned(_))));
+    let owned = tokio::spawn(async move { document.body.len() })
+        .await
+        .unwrap();
+    assert_eq!(owned, 4 * 1024);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn free_fn_blocking() {
+    let page: Page<'static, u32> = page_into_owned_blocking(Page {
+        text: Cow::Borrowed("text"),
+        number: 1,
+    })
+    .await;
+
+    assert!(matches!(page.text, Cow::Owned(ref s) if s == "text"));
+    assert_eq!(page.number, 1);
+}
@@ -0,0 +1,54 @@
+#[macro_use]
+extern crate derive_into_owned;
+
+use std::borrow::Cow;
+
+use derive_into_owned_core::IntoOwned;
+
+#[derive(IntoOwned, Debug, PartialEq)]
+#[into_owned(trait_impl)]
+struct Line<'a> {
+    text: Cow<'a, str>,
+    number: usize,
+}
+
+#[derive(IntoOwned, Debug, PartialEq)]
+#[into_owned(trait_impl, transparent)]
+struct Word<'a>(Cow<'a, str>);
+
+#[derive(IntoOwned, Debug, PartialEq)]
+#[into_owned(trait_impl)]
+struct Tagged<'a, T> {
+    tag: Cow<'a, str>,
+    value: T,
+}
+
+fn owned<T: IntoOwned>(value: T) -> T::Owned {
+    value.into_owned()
+}
+
+#[test]
+fn collections() {
+    let input = String::from("text");
+    let line = || Line {
+        text: Cow::Borrowed(&input),
+        number: 0,
+    };
+
+    let lines: Vec<Line<'static>> = owned(vec![line(), line()]);
+    let maybe: Option<Line<'static>> = owned(Some(line()));
+    let boxed: Box<Line<'static>> = owned(Box::new(line()));
+    let nested: Option<Vec<Word<'static>>> = owned(Some(vec![Word(Cow::Borrowed(&input))]));
+    let tagged: Tagged<'static, u8> = owned(Tagged {
+        tag: Cow::Borrowed(&input),
+        value: 1,
+    });
+    drop(input);
+
+    assert_eq!(lines.len(), 2);
+    assert!(matches!(lines[0].text, Cow::Owned(_)));
+    assert_eq!(maybe.unwrap().text, "text");
+    assert_eq!(boxed.text, "text");
+    assert_eq!(nested.unwrap()[0], Word(Cow::Borrowed("text")));
+    assert_eq!(tagged.value, 1);
+}
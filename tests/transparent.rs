@@ -0,0 +1,44 @@
+#[macro_use]
+extern crate derive_into_owned;
+
+use std::borrow::Cow;
+
+#[derive(IntoOwned, Debug, PartialEq)]
+#[into_owned(transparent)]
+struct Name<'a>(Cow<'a, str>);
+
+#[derive(IntoOwned, Debug, PartialEq)]
+#[into_owned(transparent)]
+struct FullName<'a>(Name<'a>);
+
+#[derive(IntoOwned, Debug, PartialEq)]
+#[into_owned(transparent)]
+struct Label<'a> {
+    name: Option<FullName<'a>>,
+}
+
+#[derive(IntoOwned, Debug, PartialEq)]
+struct Person<'a> {
+    name: FullName<'a>,
+    nick: Vec<Name<'a>>,
+}
+
+#[test]
+fn delegates_to_the_field() {
+    let data = String::from("data");
+
+    let person = Person {
+        name: FullName(Name(Cow::Borrowed(&data))),
+        nick: vec![Name(Cow::Borrowed(&data[..1]))],
+    }
+    .into_owned();
+    let label = Label {
+        name: Some(FullName(Name(Cow::Borrowed(&data)))),
+    }
+    .into_owned();
+    drop(data);
+
+    assert_eq!(person.name, FullName(Name(Cow::Borrowed("data"))));
+    assert_eq!(person.nick, vec![Name(Cow::Borrowed("d"))]);
+    assert!(matches!(label.name, Some(FullName(Name(Cow::Owned(_))))));
+}
@@ -0,0 +1,52 @@
+#[macro_use]
+extern crate derive_into_owned;
+
+use std::borrow::Cow;
+
+#[derive(IntoOwned, Debug, PartialEq)]
+#[into_owned(try_into_owned)]
+enum Payload<'a> {
+    Raw(&'a [u8]),
+    Text(Cow<'a, str>),
+    Field { name: Cow<'a, str>, value: u32 },
+    Empty,
+}
+
+#[test]
+fn converts_owning_variants() {
+    let source = String::from("hello");
+
+    let text = Payload::Text(Cow::Borrowed(&source));
+    let field = Payload::Field {
+        name: Cow::Borrowed(&source[..1]),
+        value: 3,
+    };
+
+    let text: Payload<'static> = text.try_into_owned().unwrap();
+    let field: Payload<'static> = field.try_into_owned().unwrap();
+    let empty: Payload<'static> = Payload::Empty.try_into_owned().unwrap();
+    drop(source);
+
+    assert!(matches!(text, Payload::Text(Cow::Owned(ref text)) if text == "hello"));
+    assert!(
+        matches!(field, Payload::Field { name: Cow::Owned(ref name), value: 3 } if name == "h")
+    );
+    assert_eq!(empty, Payload::Empty);
+}
+
+#[test]
+fn returns_variants_with_references() {
+    let source = vec![1, 2, 3];
+
+    let raw = Payload::Raw(&source);
+    let error = raw.try_into_owned().unwrap_err();
+
+    assert_eq!(error, Payload::Raw(&[1, 2, 3]));
+    assert!(std::ptr::eq(
+        match error {
+            Payload::Raw(bytes) => bytes,
+            _ => unreachable!(),
+        },
+        &source[..]
+    ));
+}
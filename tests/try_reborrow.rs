@@ -0,0 +1,120 @@
+#[macro_use]
+extern crate derive_into_owned;
+
+use std::borrow::Cow;
+use std::collections::HashMap;
+
+#[derive(IntoOwned, Debug, PartialEq)]
+#[into_owned(try_reborrow_original)]
+struct Message<'a, T> {
+    topic: Cow<'a, str>,
+    tags: Vec<Cow<'a, str>>,
+    headers: HashMap<Cow<'a, str>, Option<Cow<'a, [u8]>>>,
+    header: Option<Box<Header<'a>>>,
+    extra: T,
+}
+
+#[derive(IntoOwned, Debug, PartialEq)]
+#[into_owned(try_reborrow_original)]
+struct Header<'a>(Cow<'a, str>);
+
+#[derive(IntoOwned, Debug, PartialEq)]
+#[into_owned(mirror, try_reborrow_original)]
+struct Token<'a> {
+    text: &'a str,
+    value: Cow<'a, str>,
+}
+
+#[derive(IntoOwned, Debug, PartialEq)]
+#[into_owned(try_reborrow_original)]
+enum Event<'a> {
+    Text { text: Cow<'a, str> },
+    Header(Header<'a>),
+    Empty,
+}
+
+fn message(data: &str) -> Message<'_, u32> {
+    Message {
+        topic: Cow::Borrowed(&data[..2]),
+        tags: vec![Cow::Borrowed(&data[2..])],
+        headers: vec![(Cow::Borrowed(data), Some(Cow::Borrowed(data.as_bytes())))]
+            .into_iter()
+            .collect(),
+        header: Some(Box::new(Header(Cow::Borrowed(data)))),
+        extra: 1,
+    }
+}
+
+#[test]
+fn reborrows_borrowed() {
+    let data = String::from("data");
+    let original = message(&data);
+
+    let copy = original.try_reborrow_original().unwrap();
+    assert_eq!(copy, original);
+
+    // the copy borrows the same data
+    match copy.topic {
+        Cow::Borrowed(topic) => assert_eq!(topic.as_ptr(), data.as_ptr()),
+        Cow::Owned(_) => panic!("copy owns the topic"),
+    }
+}
+
+#[test]
+fn none_when_owned() {
+    let data = String::from("data");
+
+    let mut owned_tag = message(&data);
+    owned_tag.tags.push(Cow::Owned(String::from("tag")));
+    assert!(owned_tag.try_reborrow_original().is_none());
+
+    let mut owned_nested = message(&data);
+    owned_nested.header = Some(Box::new(Header(Cow::Owned(String::from("h")))));
+    assert!(owned_nested.try_reborrow_original().is_none());
+
+    let mut owned_value = message(&data);
+    owned_value
+        .headers
+        .insert(Cow::Borrowed("key"), Some(Cow::Owned(vec![1])));
+    assert!(owned_value.try_reborrow_original().is_none());
+}
+
+#[test]
+fn enum_variants() {
+    let data = String::from("data");
+
+    let text = Event::Text {
+        text: Cow::Borrowed(&data),
+    };
+    assert_eq!(
+        text.try_reborrow_original(),
+        Some(Event::Text {
+            text: Cow::Borrowed("data")
+        })
+    );
+    assert_eq!(Event::Empty.try_reborrow_original(), Some(Event::Empty));
+    assert_eq!(
+        Event::Header(Header(Cow::Owned(data.clone()))).try_reborrow_original(),
+        None
+    );
+}
+
+#[test]
+fn plain_references() {
+    let data = String::from("data");
+
+    let token = Token {
+        text: &data,
+        value: Cow::Borrowed(&data[1..]),
+    };
+    assert_eq!(
+        token.try_reborrow_original(),
+        Some(Token {
+            text: "data",
+            value: Cow::Borrowed("ata")
+        })
+    );
+
+    let owned: TokenOwned = token.into();
+    assert_eq!(owned.value, "ata");
+}
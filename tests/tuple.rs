@@ -0,0 +1,82 @@
+#[macro_use]
+extern crate derive_into_owned;
+
+use std::borrow::Cow;
+
+#[derive(IntoOwned, Borrowed, Debug, PartialEq)]
+#[into_owned(
+    mirror(derive(Debug), partial_eq),
+    ownership,
+    try_reborrow_original,
+    borrowed_strs
+)]
+struct Headers<'a> {
+    #[into_owned(expect = "iterable(tuple(moved, cow))")]
+    pairs: Vec<(String, Cow<'a, str>)>,
+    #[into_owned(expect = "option(tuple(moved, cow_alike))")]
+    first: Option<(u32, Header<'a>)>,
+    #[into_owned(expect = "tuple(cow, tuple(moved, cow))")]
+    nested: (Cow<'a, str>, (u8, Cow<'a, [u8]>)),
+    counts: Vec<(u32, u32)>,
+}
+
+#[derive(IntoOwned, Borrowed, Debug, PartialEq)]
+#[into_owned(try_reborrow_original, borrowed_strs)]
+struct Header<'a>(Cow<'a, str>);
+
+#[derive(OwnershipDebug)]
+struct Pair<'a>((Cow<'a, str>, u8));
+
+fn headers(text: &str) -> Headers<'_> {
+    Headers {
+        pairs: vec![(String::from("host"), Cow::Borrowed(&text[..4]))],
+        first: Some((1, Header(Cow::Borrowed(&text[5..])))),
+        nested: (Cow::Borrowed(text), (2, Cow::Borrowed(text.as_bytes()))),
+        counts: vec![(1, 2)],
+    }
+}
+
+#[test]
+fn tuples() {
+    let text = String::from("name text");
+    let headers = headers(&text);
+
+    let borrowed = headers.borrowed();
+    assert!(matches!(borrowed.pairs[0].1, Cow::Borrowed("name")));
+    assert_eq!(borrowed, headers);
+    assert_eq!(
+        headers.ownership(),
+        HeadersOwnership {
+            pairs: false,
+            nested: false
+        }
+    );
+    assert_eq!(headers.borrowed_strs(), ["name", "text", "name text"]);
+    assert_eq!(headers.try_reborrow_original(), Some(headers.borrowed()));
+
+    let owned: Headers<'static> = headers.into_owned();
+    drop(text);
+
+    assert!(matches!(owned.pairs[0].1, Cow::Owned(ref s) if s == "name"));
+    assert!(matches!(owned.first, Some((1, Header(Cow::Owned(ref s)))) if s == "text"));
+    assert!(matches!((owned.nested.1).1, Cow::Owned(ref b) if b == b"name text"));
+    assert!(owned.borrowed_strs().is_empty());
+    assert_eq!(owned.try_reborrow_original(), None);
+    assert_eq!(owned.counts, [(1, 2)]);
+
+    let mirror = HeadersOwned::from(owned.borrowed());
+    assert_eq!(owned, mirror);
+    assert_eq!(mirror.pairs[0].1, "name");
+    assert_eq!((mirror.nested.1).1, b"name text");
+}
+
+#[test]
+fn tuple_ownership_debug() {
+    let text = String::from("text");
+    let pair = Pair((Cow::Borrowed(&text), 1));
+
+    assert_eq!(
+        format!("{:?}", pair.ownership_debug()),
+        r#"Pair(([borrowed] "text", 1))"#
+    );
+}
@@ -12,6 +12,12 @@ struct Foo<'a>(Cow<'a, str>);
 #[derive(IntoOwned)]
 struct FooExtraFields<'a>(u32, Cow<'a, str>, bool, Vec<bool>);
 
+#[derive(IntoOwned)]
+struct Pair<'a>(Cow<'a, str>, u32);
+
+#[derive(IntoOwned)]
+struct WithOptions<'a>(#[into_owned(skip)] Option<&'a str>, Vec<Cow<'a, str>>);
+
 #[derive(IntoOwned)]
 struct Bar<'a>(::std::borrow::Cow<'a, str>);
 
@@ -30,6 +36,23 @@ fn tuple_struct() {
     accepts_only_static(thing.into_owned());
 }
 
+#[test]
+fn fields_by_index() {
+    let non_static_string: String = "foobar".to_string();
+
+    let pair = Pair(Cow::Borrowed(&non_static_string), 7).into_owned();
+    assert!(matches!(pair.0, Cow::Owned(ref value) if value == "foobar"));
+    assert_eq!(pair.1, 7);
+
+    let with_options = WithOptions(
+        Some(&non_static_string),
+        vec![Cow::Borrowed(&non_static_string)],
+    );
+    let owned: WithOptions<'static> = with_options.into_owned();
+    assert_eq!(owned.0, None);
+    assert_eq!(owned.1, vec![Cow::Owned::<str>("foobar".to_string())]);
+}
+
 fn accepts_only_static(static_foo: Foo<'static>) {
     drop(static_foo);
 }
@@ -0,0 +1,14 @@
+#[macro_use]
+extern crate derive_into_owned;
+
+use std::borrow::Cow;
+
+#[derive(IntoOwned)]
+struct Message<'a> {
+    #[into_owned(expect = "iterable(cow)")]
+    tags: Vec<Vec<Cow<'a, str>>>,
+    #[into_owned(expect = "cow")]
+    count: u32,
+}
+
+fn main() {}
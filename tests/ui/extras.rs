@@ -0,0 +1,28 @@
+#[macro_use]
+extern crate derive_into_owned;
+
+use std::borrow::Cow;
+
+#[derive(IntoOwned)]
+#[into_owned(leak, arc)]
+struct Leaked<'a> {
+    field: Cow<'a, str>,
+}
+
+#[derive(IntoOwned)]
+#[into_owned(no_std, arc)]
+struct NoStd<'a> {
+    field: Cow<'a, str>,
+}
+
+#[derive(IntoOwned)]
+#[into_owned(transparent, arc)]
+struct Name<'a>(Cow<'a, str>);
+
+#[derive(IntoOwned)]
+#[into_owned(try_into_owned, blocking)]
+enum Fallible<'a> {
+    Field(Cow<'a, str>),
+}
+
+fn main() {}
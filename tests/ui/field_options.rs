@@ -0,0 +1,12 @@
+#[macro_use]
+extern crate derive_into_owned;
+
+use std::borrow::Cow;
+
+#[derive(IntoOwned)]
+struct Both<'a> {
+    #[into_owned(skip, with = "Cow::into_owned")]
+    name: Cow<'a, str>,
+}
+
+fn main() {}
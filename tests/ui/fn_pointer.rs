@@ -0,0 +1,18 @@
+#[macro_use]
+extern crate derive_into_owned;
+
+use std::borrow::Cow;
+
+#[derive(IntoOwned)]
+struct Lookup<'a> {
+    name: Cow<'a, str>,
+    lookup: fn(usize) -> &'a str,
+}
+
+#[derive(Borrowed)]
+struct Handler<'a> {
+    name: Cow<'a, str>,
+    handle: Option<fn(&'a str)>,
+}
+
+fn main() {}
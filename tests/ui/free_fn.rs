@@ -0,0 +1,18 @@
+#[macro_use]
+extern crate derive_into_owned;
+
+use std::borrow::Cow;
+
+#[derive(IntoOwned)]
+#[into_owned(free_fn, boxed)]
+struct Boxed<'a> {
+    field: Cow<'a, str>,
+}
+
+#[derive(IntoOwned, Default)]
+#[into_owned(take_owned, free_fn = "detach")]
+struct Taken<'a> {
+    field: Cow<'a, str>,
+}
+
+fn main() {}
@@ -0,0 +1,24 @@
+#[macro_use]
+extern crate derive_into_owned;
+
+use std::borrow::Cow;
+
+#[derive(IntoOwned)]
+struct Labelled<'a, T>
+where
+    T: Into<Cow<'a, str>>,
+{
+    label: Cow<'a, str>,
+    value: T,
+}
+
+#[derive(Borrowed)]
+struct Indexed<'a, T: 'a>
+where
+    &'a T: IntoIterator,
+{
+    name: Cow<'a, str>,
+    items: T,
+}
+
+fn main() {}
@@ -0,0 +1,23 @@
+#[macro_use]
+extern crate derive_into_owned;
+
+use std::borrow::Cow;
+
+#[derive(IntoOwned)]
+struct NoMirror<'a> {
+    #[into_owned(mirror(name = "other"))]
+    name: Cow<'a, str>,
+}
+
+#[derive(IntoOwned)]
+#[into_owned(mirror)]
+struct Renamed<'a>(#[into_owned(mirror(name = "other"))] Cow<'a, str>);
+
+#[derive(IntoOwned)]
+#[into_owned(mirror)]
+struct Unknown<'a> {
+    #[into_owned(mirror(flatten))]
+    name: Cow<'a, str>,
+}
+
+fn main() {}
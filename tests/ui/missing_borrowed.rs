@@ -0,0 +1,27 @@
+#[macro_use]
+extern crate derive_into_owned;
+
+use std::borrow::Cow;
+
+struct Label<'a> {
+    text: Cow<'a, str>,
+}
+
+struct Counted<'a> {
+    text: Cow<'a, str>,
+}
+
+impl<'a> Counted<'a> {
+    fn borrowed(&self) -> usize {
+        self.text.len()
+    }
+}
+
+#[derive(Borrowed)]
+struct Message<'a> {
+    topic: Cow<'a, str>,
+    labels: Vec<Label<'a>>,
+    counted: Option<Counted<'a>>,
+}
+
+fn main() {}
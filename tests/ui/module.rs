@@ -0,0 +1,11 @@
+use derive_into_owned::into_owned_module;
+
+#[into_owned_module]
+mod relative {
+    include!("../pb_rs/contacts.rs");
+}
+
+#[into_owned_module(borrowed)]
+mod with_args {}
+
+fn main() {}
@@ -0,0 +1,16 @@
+#[macro_use]
+extern crate derive_into_owned;
+
+#[derive(IntoOwned)]
+#[into_owned(msrv = "1.40")]
+struct Old {
+    x: u32,
+}
+
+#[derive(IntoOwned)]
+#[into_owned(msrv = "latest")]
+struct Unknown {
+    x: u32,
+}
+
+fn main() {}
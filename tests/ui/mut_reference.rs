@@ -0,0 +1,18 @@
+#[macro_use]
+extern crate derive_into_owned;
+
+use std::borrow::Cow;
+
+#[derive(IntoOwned)]
+struct Cursor<'a> {
+    name: Cow<'a, str>,
+    buffer: Option<&'a mut Vec<u8>>,
+}
+
+#[derive(Borrowed)]
+enum Target<'a> {
+    Buffer(&'a mut [u8]),
+    Name(Cow<'a, str>),
+}
+
+fn main() {}
@@ -0,0 +1,24 @@
+#[macro_use]
+extern crate derive_into_owned;
+
+use std::borrow::Cow;
+
+#[derive(IntoOwned)]
+#[into_owned(no_std, intern)]
+struct Interned<'a> {
+    field: Cow<'a, str>,
+}
+
+#[derive(IntoOwned)]
+#[into_owned(field_info, no_std)]
+struct Described<'a> {
+    field: Cow<'a, str>,
+}
+
+#[derive(IntoOwned)]
+#[into_owned(no_std, trait_impl)]
+struct Implemented<'a> {
+    field: Cow<'a, str>,
+}
+
+fn main() {}
@@ -0,0 +1,13 @@
+#[macro_use]
+extern crate derive_into_owned;
+
+use std::borrow::Cow;
+use std::collections::HashMap;
+
+#[derive(IntoOwned)]
+struct Index<'a> {
+    name: Cow<'a, str>,
+    words: HashMap<&'a str, Vec<u32>>,
+}
+
+fn main() {}
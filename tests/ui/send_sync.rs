@@ -0,0 +1,16 @@
+#[macro_use]
+extern crate derive_into_owned;
+
+use std::borrow::Cow;
+use std::cell::Cell;
+use std::rc::Rc;
+
+#[derive(IntoOwned)]
+#[into_owned(assert_send_sync)]
+struct Session<'a> {
+    name: Cow<'a, str>,
+    shared: Rc<String>,
+    hits: Cell<u32>,
+}
+
+fn main() {}
@@ -0,0 +1,27 @@
+#[macro_use]
+extern crate derive_into_owned;
+
+use std::borrow::Cow;
+
+#[derive(IntoOwned, Borrowed)]
+struct Unknown<'a, #[into_owned(skip)] T> {
+    name: Cow<'a, str>,
+    value: T,
+}
+
+/// Not `Clone`.
+struct Meters;
+
+#[derive(Borrowed)]
+struct Cloned<'a, T> {
+    name: Cow<'a, str>,
+    value: T,
+}
+
+fn main() {
+    let cloned = Cloned {
+        name: Cow::Borrowed("name"),
+        value: Meters,
+    };
+    cloned.borrowed();
+}
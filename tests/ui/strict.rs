@@ -0,0 +1,23 @@
+#[macro_use]
+extern crate derive_into_owned;
+
+use std::borrow::Cow;
+use std::cell::Cell;
+
+#[derive(IntoOwned)]
+#[into_owned(strict)]
+struct Strict<'a> {
+    fine: Option<Cow<'a, str>>,
+    plain: u32,
+    cell: Cell<&'a str>,
+    nested: Vec<std::cell::RefCell<Cow<'a, str>>>,
+}
+
+#[derive(IntoOwned)]
+#[into_owned(strict)]
+enum StrictEnum<'a> {
+    Borrowed(&'a str),
+    Owned { value: String },
+}
+
+fn main() {}
@@ -0,0 +1,18 @@
+#[macro_use]
+extern crate derive_into_owned;
+
+use std::borrow::Cow;
+
+#[derive(IntoOwned)]
+#[into_owned(leak, trait_impl)]
+struct Leaked<'a> {
+    field: Cow<'a, str>,
+}
+
+#[derive(IntoOwned)]
+#[into_owned(trait_impl, try_into_owned)]
+enum Fallible<'a> {
+    Field(Cow<'a, str>),
+}
+
+fn main() {}
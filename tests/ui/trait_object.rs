@@ -0,0 +1,15 @@
+#[macro_use]
+extern crate derive_into_owned;
+
+use std::sync::Arc;
+
+trait Visitor<'a> {
+    fn visit(&self, name: &'a str);
+}
+
+#[derive(IntoOwned)]
+struct Walker<'a> {
+    visitor: Arc<dyn Visitor<'a> + 'a>,
+}
+
+fn main() {}
@@ -0,0 +1,16 @@
+#[macro_use]
+extern crate derive_into_owned;
+
+use std::borrow::Cow;
+
+#[derive(IntoOwned)]
+#[into_owned(transparent)]
+struct Pair<'a>(Cow<'a, str>, Cow<'a, str>);
+
+#[derive(IntoOwned)]
+#[into_owned(transparent)]
+enum Either<'a> {
+    Left(Cow<'a, str>),
+}
+
+fn main() {}
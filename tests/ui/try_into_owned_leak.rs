@@ -0,0 +1,20 @@
+#[macro_use]
+extern crate derive_into_owned;
+
+use std::borrow::Cow;
+
+#[derive(IntoOwned)]
+#[into_owned(leak, try_into_owned)]
+enum Leaked<'a> {
+    Field(Cow<'a, str>),
+    Reference(&'a str),
+}
+
+#[derive(IntoOwned)]
+#[into_owned(try_into_owned, leak)]
+enum Fallible<'a> {
+    Field(Cow<'a, str>),
+    Reference(&'a str),
+}
+
+fn main() {}
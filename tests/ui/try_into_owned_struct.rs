@@ -0,0 +1,12 @@
+#[macro_use]
+extern crate derive_into_owned;
+
+use std::borrow::Cow;
+
+#[derive(IntoOwned)]
+#[into_owned(try_into_owned)]
+struct Foo<'a> {
+    field: Cow<'a, str>,
+}
+
+fn main() {}
@@ -0,0 +1,10 @@
+#[macro_use]
+extern crate derive_into_owned;
+
+#[derive(IntoOwned)]
+union Borrowing<'a> {
+    int: &'a u32,
+    float: &'a f32,
+}
+
+fn main() {}
@@ -0,0 +1,19 @@
+#![deny(deprecated)]
+
+#[macro_use]
+extern crate derive_into_owned;
+
+use std::borrow::Cow;
+
+#[derive(Borrowed)]
+#[borrowed(warn_clones)]
+struct Message<'a> {
+    topic: Cow<'a, str>,
+    id: u64,
+    body: String,
+    attachments: Option<Vec<u8>>,
+    #[borrowed(clone)]
+    accepted: String,
+}
+
+fn main() {}
@@ -0,0 +1,27 @@
+#![allow(dead_code)]
+
+#[macro_use]
+extern crate derive_into_owned;
+
+#[derive(IntoOwned, Borrowed, Clone, Copy)]
+union Number {
+    int: u32,
+    float: f32,
+}
+
+#[derive(IntoOwned, Borrowed, Clone, Copy)]
+union Generic<T: Copy> {
+    value: T,
+    name: &'static str,
+}
+
+#[test]
+fn copy_unions() {
+    let number = Number { int: 7 };
+    assert_eq!(unsafe { number.borrowed().int }, 7);
+    assert_eq!(unsafe { number.into_owned().int }, 7);
+
+    let generic = Generic::<u64> { name: "name" };
+    let owned: Generic<u64> = generic.borrowed().into_owned();
+    assert_eq!(unsafe { owned.name }, "name");
+}
@@ -0,0 +1,111 @@
+#[macro_use]
+extern crate derive_into_owned;
+
+use std::borrow::Cow;
+use std::rc::Rc;
+use std::sync::Arc;
+
+#[derive(IntoOwned, Borrowed, Debug, Clone, PartialEq)]
+struct Payload<'a> {
+    body: Cow<'a, str>,
+}
+
+#[derive(IntoOwned, Borrowed, Debug, PartialEq)]
+#[into_owned(unwrap_or_clone, mirror(derive(Debug, PartialEq)), ownership)]
+struct Envelope<'a> {
+    #[into_owned(expect = "rc(cow_alike)")]
+    payload: Rc<Payload<'a>>,
+    topics: Vec<Rc<Cow<'a, str>>>,
+    shared: Rc<str>,
+}
+
+fn envelope(text: &str) -> Envelope<'_> {
+    Envelope {
+        payload: Rc::new(Payload {
+            body: Cow::Borrowed(text),
+        }),
+        topics: vec![Rc::new(Cow::Borrowed(&text[..2]))],
+        shared: Rc::from("shared"),
+    }
+}
+
+#[test]
+fn unwraps_or_clones() {
+    let text = String::from("text");
+
+    let unique = envelope(&text);
+    let owned: Envelope<'static> = unique.into_owned();
+    assert!(matches!(owned.payload.body, Cow::Owned(ref s) if s == "text"));
+    assert!(matches!(*owned.topics[0], Cow::Owned(ref s) if s == "te"));
+
+    let shared = envelope(&text);
+    let payload = Rc::clone(&shared.payload);
+    let owned: Envelope<'static> = shared.into_owned();
+
+    assert!(matches!(owned.payload.body, Cow::Owned(ref s) if s == "text"));
+    assert!(matches!(payload.body, Cow::Borrowed("text")));
+    assert_eq!(&*owned.shared, "shared");
+}
+
+#[test]
+fn borrowed_shares_the_rc() {
+    let text = String::from("text");
+    let envelope = envelope(&text);
+
+    let borrowed = envelope.borrowed();
+    assert!(Rc::ptr_eq(&borrowed.payload, &envelope.payload));
+    assert_eq!(borrowed, envelope);
+    assert_eq!(envelope.ownership(), EnvelopeOwnership { topics: false });
+    assert_eq!(
+        envelope.into_owned().ownership(),
+        EnvelopeOwnership { topics: true }
+    );
+}
+
+#[test]
+fn mirror() {
+    let text = String::from("text");
+    let mirror = EnvelopeOwned::from(envelope(&text));
+    drop(text);
+
+    assert_eq!(mirror.payload.body, "text");
+    assert_eq!(*mirror.topics[0], "te");
+}
+
+#[derive(IntoOwned, Borrowed, Debug, PartialEq)]
+#[into_owned(unwrap_or_clone, assert_send_sync)]
+enum Message<'a> {
+    Single(#[into_owned(expect = "arc(cow_alike)")] Arc<Payload<'a>>),
+    Batch(Vec<std::sync::Arc<Payload<'a>>>, Option<Arc<Cow<'a, str>>>),
+}
+
+#[test]
+fn arc_fields() {
+    let text = String::from("text");
+    let payload = Arc::new(Payload {
+        body: Cow::Borrowed(&text),
+    });
+
+    let batch = Message::Batch(
+        vec![Arc::clone(&payload), Arc::clone(&payload)],
+        Some(Arc::new(Cow::Borrowed(&text[1..]))),
+    );
+    let borrowed = batch.borrowed();
+    assert!(
+        matches!(borrowed, Message::Batch(ref payloads, _) if Arc::ptr_eq(&payloads[0], &payload))
+    );
+
+    let owned: Message<'static> = batch.into_owned();
+    let single: Message<'static> = Message::Single(payload).into_owned();
+    drop(text);
+
+    let handle = std::thread::spawn(move || match (owned, single) {
+        (Message::Batch(payloads, Some(topic)), Message::Single(payload)) => {
+            assert!(matches!(payloads[1].body, Cow::Owned(ref s) if s == "text"));
+            assert!(matches!(*topic, Cow::Owned(ref s) if s == "ext"));
+            assert!(matches!(payload.body, Cow::Owned(ref s) if s == "text"));
+        }
+        other => panic!("unexpected {:?}", other),
+    });
+    handle.join().unwrap();
+}
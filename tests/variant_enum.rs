@@ -30,3 +30,33 @@ fn enum_with_only_cow_variants() {
 fn accepts_only_static<T: 'static>(anything: T) {
     drop(anything)
 }
+
+#[derive(IntoOwned, Borrowed, Debug, PartialEq)]
+enum Mixed<'a> {
+    Unit,
+    Tuple(Cow<'a, str>, u32),
+    Struct {
+        name: Cow<'a, str>,
+        inner: Option<Box<Mixed<'a>>>,
+    },
+}
+
+#[test]
+fn enum_with_mixed_variants() {
+    let s = "foobar".to_string();
+
+    let things = vec![
+        Mixed::Unit,
+        Mixed::Tuple(Cow::Borrowed(&s), 7),
+        Mixed::Struct {
+            name: Cow::Borrowed(&s),
+            inner: Some(Box::new(Mixed::Tuple(Cow::Borrowed(&s), 8))),
+        },
+    ];
+
+    for thing in things {
+        let owned: Mixed<'static> = thing.borrowed().into_owned();
+        assert_eq!(owned, thing);
+        assert_eq!(owned.borrowed(), thing);
+    }
+}
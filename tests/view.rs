@@ -0,0 +1,120 @@
+#[macro_use]
+extern crate derive_into_owned;
+
+use std::borrow::Cow;
+
+#[derive(Borrowed, Debug)]
+#[borrowed(view(derive(Debug, Clone)))]
+struct Message<'a> {
+    /// The topic
+    topic: Cow<'a, str>,
+    payload: &'a [u8],
+    tags: Vec<Cow<'a, str>>,
+    #[borrowed(view)]
+    header: Header,
+    #[borrowed(view)]
+    trailer: Trailer<'a>,
+    sender: Sender<'a>,
+    retries: u32,
+}
+
+#[derive(Debug, Clone, Borrowed)]
+#[borrowed(view(derive(Debug, Clone, Copy)))]
+struct Header {
+    name: String,
+    values: Vec<u8>,
+}
+
+#[derive(IntoOwned, Borrowed, Debug)]
+#[borrowed(view(derive(Debug, Clone, Copy)))]
+struct Trailer<'a> {
+    bytes: Cow<'a, [u8]>,
+}
+
+#[derive(IntoOwned, Borrowed, Debug, Clone, PartialEq)]
+#[borrowed(view(derive(Debug, Clone, Copy, PartialEq)))]
+enum Sender<'a> {
+    Anonymous,
+    Named(Cow<'a, str>),
+    Host { name: Cow<'a, str>, port: u16 },
+}
+
+#[derive(IntoOwned, Borrowed)]
+#[borrowed(view(name = "GenericView"))]
+struct Generic<'a, T: Clone> {
+    values: [T; 2],
+    name: Cow<'a, str>,
+}
+
+#[test]
+fn views_borrow_everything() {
+    let message = Message {
+        topic: Cow::Owned(String::from("news")),
+        payload: b"data",
+        tags: vec![Cow::Borrowed("a")],
+        header: Header {
+            name: String::from("h"),
+            values: vec![1, 2],
+        },
+        trailer: Trailer {
+            bytes: Cow::Borrowed(b"end"),
+        },
+        sender: Sender::Host {
+            name: Cow::Borrowed("localhost"),
+            port: 80,
+        },
+        retries: 3,
+    };
+
+    let view: MessageRef<'_> = message.view();
+    let copy = view.clone();
+
+    let topic: &str = copy.topic;
+    assert_eq!(topic, "news");
+    assert_eq!(view.payload, b"data");
+    assert_eq!(view.tags, &vec![Cow::Borrowed("a")]);
+    // owned strings and vectors are viewed as slices
+    let name: &str = view.header.name;
+    let values: &[u8] = view.header.values;
+    assert_eq!(name, "h");
+    assert_eq!(values, &[1, 2]);
+    let trailer: TrailerRef<'_> = view.trailer;
+    assert_eq!(trailer.bytes, b"end");
+    assert_eq!(
+        view.sender,
+        Sender::Host {
+            name: Cow::Borrowed("localhost"),
+            port: 80,
+        }
+    );
+    assert_eq!(*view.retries, 3);
+}
+
+#[test]
+fn enum_views() {
+    let sender = Sender::Named(Cow::Owned(String::from("alice")));
+    assert!(matches!(sender.view(), SenderRef::Named("alice")));
+    assert!(matches!(Sender::Anonymous.view(), SenderRef::Anonymous));
+    let host = Sender::Host {
+        name: Cow::Borrowed("h"),
+        port: 1,
+    };
+    assert_eq!(
+        host.view(),
+        SenderRef::Host {
+            name: "h",
+            port: &1
+        }
+    );
+}
+
+#[test]
+fn generic_views() {
+    let generic = Generic {
+        values: [1, 2],
+        name: Cow::Borrowed("g"),
+    };
+    let view: GenericView<'_, i32> = generic.view();
+    assert_eq!(view.values, &[1, 2]);
+    assert_eq!(view.name, "g");
+}
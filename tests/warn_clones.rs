@@ -0,0 +1,30 @@
+#![deny(deprecated)]
+
+#[macro_use]
+extern crate derive_into_owned;
+
+use std::borrow::Cow;
+
+#[derive(Borrowed)]
+#[borrowed(warn_clones)]
+struct Message<'a> {
+    topic: Cow<'a, str>,
+    id: u64,
+    #[borrowed(clone)]
+    body: String,
+}
+
+#[test]
+fn accepted_clones_do_not_warn() {
+    let message = Message {
+        topic: Cow::Borrowed("topic"),
+        id: 1,
+        body: String::from("body"),
+    };
+
+    let borrowed = message.borrowed();
+
+    assert!(matches!(borrowed.topic, Cow::Borrowed("topic")));
+    assert_eq!(borrowed.id, 1);
+    assert_eq!(borrowed.body, "body");
+}
@@ -0,0 +1,70 @@
+#[macro_use]
+extern crate derive_into_owned;
+
+use serde::Serialize;
+use std::borrow::Cow;
+
+#[derive(IntoOwned, Borrowed, Debug, PartialEq)]
+struct Event<'a, T>
+where
+    T: Serialize + Clone + 'a,
+{
+    topic: Cow<'a, str>,
+    payload: T,
+}
+
+#[derive(IntoOwned, Borrowed, Debug, PartialEq)]
+enum Update<'a, 'b, K, V>
+where
+    'b: 'a,
+    K: Ord + Clone,
+    V: Clone + for<'x> From<&'x str>,
+{
+    Insert(K, V, Cow<'a, str>),
+    Remove { key: K, reason: Cow<'b, str> },
+}
+
+#[derive(IntoOwned, Borrowed, Debug, PartialEq)]
+struct Batch<'a, T>
+where
+    for<'x> &'x T: IntoIterator,
+    for<'x> Cow<'x, str>: From<&'x str>,
+{
+    label: Cow<'a, str>,
+    items: T,
+}
+
+#[test]
+fn where_clauses() {
+    let topic = "orders".to_string();
+    let event = Event {
+        topic: Cow::Borrowed(&topic),
+        payload: vec![1u32, 2],
+    };
+    let owned: Event<'static, Vec<u32>> = event.borrowed().into_owned();
+    assert_eq!(owned, event);
+    assert_eq!(serde_json::to_string(&owned.payload).unwrap(), "[1,2]");
+
+    let update: Update<'_, '_, u8, String> = Update::Remove {
+        key: 1,
+        reason: Cow::Borrowed(&topic),
+    };
+    let owned: Update<'static, 'static, u8, String> = update.borrowed().into_owned();
+    assert_eq!(owned, update);
+    assert_eq!(
+        Update::Insert(2u8, String::from("value"), Cow::Borrowed(&topic)).into_owned(),
+        Update::Insert(2, String::from("value"), Cow::Borrowed("orders"))
+    );
+}
+
+#[test]
+fn bounded_types_over_all_lifetimes() {
+    let label = "batch".to_string();
+    let batch = Batch {
+        label: Cow::Borrowed(&label),
+        items: vec![1u32, 2],
+    };
+    let owned: Batch<'static, Vec<u32>> = batch.borrowed().into_owned();
+    assert_eq!(owned, batch);
+    assert_eq!(owned.items.len(), 2);
+}
@@ -0,0 +1,118 @@
+#[macro_use]
+extern crate derive_into_owned;
+
+use std::borrow::Cow;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Span {
+    start: usize,
+    end: usize,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Spanned<T> {
+    span: Span,
+    value: T,
+}
+
+impl<T> Spanned<T> {
+    pub fn map<U>(self, f: impl FnOnce(T) -> U) -> Spanned<U> {
+        Spanned {
+            span: self.span,
+            value: f(self.value),
+        }
+    }
+
+    pub fn as_ref(&self) -> Spanned<&T> {
+        Spanned {
+            span: self.span,
+            value: &self.value,
+        }
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub struct Tagged<T>(&'static str, T);
+
+impl<T> Tagged<T> {
+    pub fn map_tagged<U>(self, f: impl FnOnce(T) -> U) -> Tagged<U> {
+        Tagged(self.0, f(self.1))
+    }
+}
+
+#[derive(IntoOwned, Borrowed, Debug, PartialEq)]
+#[into_owned(wrapper(Spanned), mirror(derive(Debug, PartialEq)))]
+struct Call<'a> {
+    #[into_owned(expect = "wrapper(cow)")]
+    name: Spanned<Cow<'a, str>>,
+    args: Vec<Spanned<Arg<'a>>>,
+    depth: Spanned<u32>,
+}
+
+#[derive(IntoOwned, Borrowed, Debug, PartialEq)]
+#[into_owned(wrapper(crate::Spanned))]
+enum Arg<'a> {
+    Text(Spanned<Cow<'a, str>>),
+    Number(u64),
+}
+
+#[derive(IntoOwned, Debug, PartialEq)]
+#[into_owned(wrapper(Tagged = "map_tagged"))]
+struct Note<'a> {
+    text: Option<Tagged<Cow<'a, str>>>,
+}
+
+fn spanned<T>(start: usize, value: T) -> Spanned<T> {
+    Spanned {
+        span: Span {
+            start,
+            end: start + 1,
+        },
+        value,
+    }
+}
+
+#[test]
+fn converts_the_payload() {
+    let source = String::from("print(x)");
+
+    let call = Call {
+        name: spanned(0, Cow::Borrowed(&source[..5])),
+        args: vec![
+            spanned(6, Arg::Text(spanned(6, Cow::Borrowed(&source[6..7])))),
+            spanned(7, Arg::Number(2)),
+        ],
+        depth: spanned(0, 1),
+    };
+
+    let borrowed = call.borrowed();
+    assert!(matches!(borrowed.name.value, Cow::Borrowed("print")));
+    assert_eq!(borrowed, call);
+
+    let owned: Call<'static> = call.into_owned();
+    let mirror = CallOwned::from(owned);
+    drop(source);
+
+    assert_eq!(mirror.name, spanned(0, String::from("print")));
+    assert_eq!(
+        mirror.args,
+        vec![
+            spanned(6, Arg::Text(spanned(6, Cow::Borrowed("x")))),
+            spanned(7, Arg::Number(2)),
+        ]
+    );
+    assert_eq!(mirror.depth, spanned(0, 1));
+}
+
+#[test]
+fn custom_method() {
+    let text = String::from("text");
+    let note = Note {
+        text: Some(Tagged("tag", Cow::Borrowed(&text))),
+    };
+
+    let note: Note<'static> = note.into_owned();
+    drop(text);
+
+    assert_eq!(note.text, Some(Tagged("tag", Cow::Borrowed("text"))));
+}
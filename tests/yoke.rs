@@ -0,0 +1,98 @@
+#![cfg(feature = "yoke")]
+
+#[macro_use]
+extern crate derive_into_owned;
+
+use std::borrow::Cow;
+use std::rc::Rc;
+use yoke::{Yoke, Yokeable};
+
+#[derive(IntoOwned, Yokeable)]
+#[into_owned(yoke)]
+struct Line<'a> {
+    key: Cow<'a, str>,
+    value: Cow<'a, str>,
+}
+
+fn parse_line(input: &str) -> Line<'_> {
+    let (key, value) = input.split_once('=').unwrap_or((input, ""));
+    Line {
+        key: Cow::Borrowed(key),
+        value: Cow::Borrowed(value),
+    }
+}
+
+#[test]
+fn into_yoke_keeps_borrowing() {
+    let cart: Rc<str> = Rc::from("greeting=hello");
+
+    let yoke: Yoke<Line<'static>, Rc<str>> = Line::into_yoke(cart.clone(), parse_line);
+
+    assert!(matches!(yoke.get().key, Cow::Borrowed("greeting")));
+    assert!(matches!(yoke.get().value, Cow::Borrowed("hello")));
+    assert_eq!(Rc::strong_count(&cart), 2);
+}
+
+#[test]
+fn into_yoke_outlives_scope() {
+    let yoke = {
+        let cart: Box<str> = String::from("a=b").into_boxed_str();
+        Line::into_yoke(cart, parse_line)
+    };
+
+    assert_eq!(yoke.get().value, "b");
+}
+
+#[derive(IntoOwned, Yokeable, Debug)]
+#[into_owned(self_owned)]
+struct Record<'a> {
+    fields: Vec<Cow<'a, str>>,
+}
+
+fn parse_record(input: &str) -> Result<Record<'_>, &'static str> {
+    if input.is_empty() {
+        return Err("empty record");
+    }
+    Ok(Record {
+        fields: input.split(',').map(Cow::Borrowed).collect(),
+    })
+}
+
+fn read_record(line: &str) -> Result<RecordSelfOwned<String>, &'static str> {
+    // the buffer is moved into the wrapper which is returned from the function
+    RecordSelfOwned::try_new(line.to_owned(), |s: &str| parse_record(s))
+}
+
+#[test]
+fn self_owned_outlives_parsing_function() {
+    let record = read_record("a,b,c").unwrap();
+
+    assert_eq!(record.get().fields, ["a", "b", "c"]);
+    assert!(record
+        .get()
+        .fields
+        .iter()
+        .all(|field| matches!(field, Cow::Borrowed(_))));
+    assert_eq!(record.backing_cart(), "a,b,c");
+    assert_eq!(record.into_backing_cart(), "a,b,c");
+}
+
+#[test]
+fn self_owned_try_new_error() {
+    assert_eq!(read_record("").err(), Some("empty record"));
+}
+
+#[test]
+fn self_owned_from_bytes() {
+    let record = RecordSelfOwned::new(b"x,y".to_vec(), |bytes: &[u8]| Record {
+        fields: bytes
+            .split(|b| *b == b',')
+            .map(|field| Cow::Borrowed(std::str::from_utf8(field).unwrap()))
+            .collect(),
+    });
+
+    assert_eq!(record.get().fields, ["x", "y"]);
+
+    let yoke: Yoke<Record<'static>, Vec<u8>> = record.into_yoke();
+    assert_eq!(yoke.get().fields.len(), 2);
+}
@@ -0,0 +1,60 @@
+#![cfg(feature = "zerovec")]
+#![allow(dead_code)]
+
+#[macro_use]
+extern crate derive_into_owned;
+
+use std::borrow::Cow;
+use zerovec::{VarZeroVec, ZeroVec};
+
+#[derive(IntoOwned, Borrowed)]
+struct DataStruct<'data> {
+    name: Cow<'data, str>,
+    numbers: ZeroVec<'data, u32>,
+    strings: VarZeroVec<'data, str>,
+    maybe_numbers: Option<ZeroVec<'data, u16>>,
+    many_strings: Vec<VarZeroVec<'data, str>>,
+}
+
+#[test]
+fn zerovec_into_owned() {
+    let bytes = 0x0102_0304u32.to_le_bytes();
+    let strings = VarZeroVec::<str>::from(&["foo", "bar"]);
+    let strings_bytes = strings.as_bytes().to_vec();
+
+    let thing = DataStruct {
+        name: Cow::Borrowed("name"),
+        numbers: ZeroVec::parse_byte_slice(&bytes).unwrap(),
+        strings: VarZeroVec::parse_byte_slice(&strings_bytes).unwrap(),
+        maybe_numbers: None,
+        many_strings: vec![VarZeroVec::parse_byte_slice(&strings_bytes).unwrap()],
+    };
+
+    let owned = accepts_only_static(thing.into_owned());
+
+    assert_eq!(owned.numbers.get(0), Some(0x0102_0304));
+    assert_eq!(owned.strings.get(1), Some("bar"));
+    assert_eq!(owned.many_strings[0].get(0), Some("foo"));
+}
+
+#[test]
+fn zerovec_borrowed() {
+    let owned = DataStruct {
+        name: Cow::Owned("name".to_string()),
+        numbers: ZeroVec::alloc_from_slice(&[1, 2, 3]),
+        strings: VarZeroVec::from(&["foo", "bar"]),
+        maybe_numbers: Some(ZeroVec::alloc_from_slice(&[4, 5])),
+        many_strings: vec![],
+    };
+
+    let borrowed = owned.borrowed();
+
+    assert!(!borrowed.numbers.is_owned());
+    assert!(!borrowed.maybe_numbers.as_ref().unwrap().is_owned());
+    assert!(!borrowed.strings.is_owned());
+    assert_eq!(borrowed.strings.get(0), Some("foo"));
+}
+
+fn accepts_only_static<T: 'static>(anything: T) -> T {
+    anything
+}